@@ -0,0 +1,206 @@
+//! `#[axum_doc(...)]` annotations overriding what static analysis infers for
+//! an operation.
+//!
+//! The same keys are accepted in three places, so the annotation can stay
+//! inert in crates that do not depend on a proc macro:
+//!
+//! ```text
+//! #[axum_doc(tag = "users", status = 201)]
+//! #[cfg_attr(any(), axum_doc(deprecated, response(404, ErrorBody)))]
+//! /// @axum_doc(security = "bearer")
+//! ```
+
+use syn::{meta::ParseNestedMeta, Attribute, LitInt, LitStr, Meta, MetaList, Token, Type};
+use quote::ToTokens;
+
+/// Operation metadata set explicitly on a handler.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationOverrides {
+    /// `tag = "..."`, repeatable; replaces the module-derived tag
+    pub tags: Vec<String>,
+    /// `status = 201`: the success status, replacing inferred ones
+    pub status: Option<u16>,
+    /// `deprecated`, or a Rust `#[deprecated]` attribute
+    pub deprecated: bool,
+    /// `response(404)`, `response(404, ErrorBody)` or
+    /// `response(404, ErrorBody, description = "...")`, repeatable
+    pub responses: Vec<ResponseOverride>,
+    /// `security = "bearer"`, repeatable
+    pub security: Vec<String>,
+    /// Authentication is optional (only `Option` auth extractors), so `{}`
+    /// is listed as an alternative requirement
+    pub security_optional: bool,
+    /// `operation_id = "..."`
+    pub operation_id: Option<String>,
+    /// `summary = "..."`, taking precedence over the doc comment
+    pub summary: Option<String>,
+    /// `description = "..."`, taking precedence over the doc comment
+    pub description: Option<String>,
+    /// `hidden`: leave the operation out of the document
+    pub hidden: bool,
+}
+
+/// An explicitly documented response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseOverride {
+    pub status: u16,
+    /// Type string of the JSON body, if any
+    pub body: Option<String>,
+    pub description: Option<String>,
+}
+
+impl OperationOverrides {
+    /// Reads `#[axum_doc(...)]`, `#[cfg_attr(.., axum_doc(...))]` and
+    /// `#[deprecated]` from `attrs`. Invalid annotations are reported on
+    /// stderr and otherwise ignored.
+    pub fn parse(attrs: &[Attribute], handler_name: &str) -> Self {
+        let mut overrides = OperationOverrides::default();
+        for attr in attrs {
+            if attr.path().is_ident("deprecated") {
+                overrides.deprecated = true;
+            } else if let Meta::List(list) = &attr.meta {
+                if list.path.is_ident("axum_doc") {
+                    overrides.apply(list, handler_name);
+                } else if list.path.is_ident("cfg_attr") {
+                    let nested = list.parse_args_with(
+                        syn::punctuated::Punctuated::<Meta, Token![,]>::parse_terminated,
+                    );
+                    for meta in nested.into_iter().flatten().skip(1) {
+                        if let Meta::List(inner) = meta {
+                            if inner.path.is_ident("axum_doc") {
+                                overrides.apply(&inner, handler_name);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        overrides
+    }
+
+    /// Applies a `@axum_doc(...)` doc-comment line. Returns `false` if the
+    /// line is not a directive, so it can be kept as documentation.
+    pub fn apply_doc_line(&mut self, line: &str, handler_name: &str) -> bool {
+        let Some(directive) = line.trim().strip_prefix('@') else { return false };
+        if !directive.starts_with("axum_doc") {
+            return false;
+        }
+        match syn::parse_str::<Meta>(directive) {
+            Ok(Meta::List(list)) => self.apply(&list, handler_name),
+            _ => eprintln!("Warning: Invalid @axum_doc directive on '{}': {}", handler_name, line.trim()),
+        }
+        true
+    }
+
+    /// Adds an authentication scheme an extractor uses. An optional
+    /// extractor (`Option<..>`) makes authentication optional, unless a
+    /// scheme is already required.
+    pub fn add_security(&mut self, scheme: &str, required: bool) {
+        if required {
+            self.security_optional = false;
+        } else if self.security.is_empty() {
+            self.security_optional = true;
+        }
+        if !self.security.iter().any(|existing| existing == scheme) {
+            self.security.push(scheme.to_string());
+        }
+    }
+
+    fn apply(&mut self, list: &MetaList, handler_name: &str) {
+        let result = list.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                self.tags.push(string_value(&meta)?);
+            } else if meta.path.is_ident("status") {
+                self.status = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("deprecated") {
+                self.deprecated = true;
+            } else if meta.path.is_ident("hidden") {
+                self.hidden = true;
+            } else if meta.path.is_ident("response") {
+                self.responses.push(parse_response(&meta)?);
+            } else if meta.path.is_ident("security") {
+                self.security.push(string_value(&meta)?);
+                self.security_optional = false;
+            } else if meta.path.is_ident("operation_id") {
+                self.operation_id = Some(string_value(&meta)?);
+            } else if meta.path.is_ident("summary") {
+                self.summary = Some(string_value(&meta)?);
+            } else if meta.path.is_ident("description") {
+                self.description = Some(string_value(&meta)?);
+            } else {
+                return Err(meta.error("unknown axum_doc key"));
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            eprintln!("Warning: Invalid #[axum_doc] annotation on '{}': {}", handler_name, e);
+        }
+    }
+}
+
+fn string_value(meta: &ParseNestedMeta) -> syn::Result<String> {
+    Ok(meta.value()?.parse::<LitStr>()?.value())
+}
+
+// `(404)`, `(404, Body)`, `(404, description = "..")` or `(404, Body, description = "..")`
+fn parse_response(meta: &ParseNestedMeta) -> syn::Result<ResponseOverride> {
+    let content;
+    syn::parenthesized!(content in meta.input);
+    let mut response = ResponseOverride {
+        status: content.parse::<LitInt>()?.base10_parse()?,
+        body: None,
+        description: None,
+    };
+    while content.parse::<Option<Token![,]>>()?.is_some() {
+        if content.is_empty() {
+            break;
+        }
+        if content.peek(syn::Ident) && content.peek2(Token![=]) {
+            let key: syn::Ident = content.parse()?;
+            if key != "description" {
+                return Err(syn::Error::new(key.span(), "expected `description`"));
+            }
+            content.parse::<Token![=]>()?;
+            response.description = Some(content.parse::<LitStr>()?.value());
+        } else {
+            response.body = Some(content.parse::<Type>()?.to_token_stream().to_string());
+        }
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attribute_forms() {
+        let func: syn::ItemFn = syn::parse_quote! {
+            #[axum_doc(tag = "users", tag = "admin", status = 201, security = "bearer")]
+            #[cfg_attr(any(), axum_doc(response(404, ErrorBody), response(409, description = "Taken"), hidden))]
+            #[deprecated]
+            async fn create() {}
+        };
+        let overrides = OperationOverrides::parse(&func.attrs, "create");
+        assert_eq!(overrides.tags, vec!["users", "admin"]);
+        assert_eq!(overrides.status, Some(201));
+        assert_eq!(overrides.security, vec!["bearer"]);
+        assert!(overrides.deprecated && overrides.hidden);
+        assert_eq!(overrides.responses, vec![
+            ResponseOverride { status: 404, body: Some("ErrorBody".to_string()), description: None },
+            ResponseOverride { status: 409, body: None, description: Some("Taken".to_string()) },
+        ]);
+    }
+
+    #[test]
+    fn test_doc_line_directives() {
+        let mut overrides = OperationOverrides::default();
+        assert!(!overrides.apply_doc_line("Creates a user", "create"));
+        assert!(overrides.apply_doc_line(r#" @axum_doc(operation_id = "createUser", summary = "Create")"#, "create"));
+        assert_eq!(overrides.operation_id.as_deref(), Some("createUser"));
+        assert_eq!(overrides.summary.as_deref(), Some("Create"));
+
+        // Unknown keys are reported but the line is still consumed
+        assert!(overrides.apply_doc_line("@axum_doc(colour = \"red\")", "create"));
+    }
+}
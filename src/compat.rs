@@ -0,0 +1,634 @@
+//! Compatibility check between two OpenAPI documents: every change to an
+//! operation or schema, classified as breaking or non-breaking for clients.
+//!
+//! Whether a schema change breaks clients depends on where the schema is
+//! used. Narrowing what a request accepts (a narrower type, removed enum
+//! values, a new required field) breaks clients that send the old shape;
+//! widening what a response returns (a removed field, added enum values)
+//! breaks clients that read it. Component schemas take their direction from
+//! the operations that reference them, directly or through other schemas.
+
+use std::{collections::{BTreeSet, HashMap}, fmt};
+use serde_json::Value;
+
+use crate::diff::union_keys;
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// A single change between two documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiChange {
+    pub breaking: bool,
+    /// `METHOD /path`, or `#/components/schemas/Name`
+    pub location: String,
+    pub message: String,
+}
+
+/// All changes between two documents, in document order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeReport {
+    pub changes: Vec<ApiChange>,
+}
+
+impl ChangeReport {
+    pub fn breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes.iter().filter(|change| change.breaking)
+    }
+
+    pub fn non_breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes.iter().filter(|change| !change.breaking)
+    }
+
+    pub fn has_breaking(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+
+    /// Renders the report as a Markdown changelog section.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("## API changes\n");
+        if self.changes.is_empty() {
+            markdown.push_str("\nNo changes.\n");
+            return markdown;
+        }
+        for (title, changes) in [
+            ("### ⚠️ Breaking changes", self.breaking().collect::<Vec<_>>()),
+            ("### Non-breaking changes", self.non_breaking().collect()),
+        ] {
+            if changes.is_empty() {
+                continue;
+            }
+            markdown.push_str(&format!("\n{}\n\n", title));
+            for change in changes {
+                markdown.push_str(&format!("- `{}`: {}\n", change.location, change.message));
+            }
+        }
+        markdown
+    }
+}
+
+impl fmt::Display for ChangeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "No API changes");
+        }
+        for (title, changes) in [
+            ("Breaking changes", self.breaking().collect::<Vec<_>>()),
+            ("Non-breaking changes", self.non_breaking().collect()),
+        ] {
+            if changes.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", title)?;
+            for change in changes {
+                writeln!(f, "  {}: {}", change.location, change.message)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares two OpenAPI documents (as produced by [`crate::generate_openapi`]
+/// and serialized with [`crate::OpenApi::to_value`]) and classifies every
+/// change to their operations and component schemas.
+pub fn compare_specs(old: &Value, new: &Value) -> ChangeReport {
+    let mut changes = Vec::new();
+
+    let old_paths = old.get("paths");
+    let new_paths = new.get("paths");
+    for path in union_keys(old_paths, new_paths) {
+        let old_item = old_paths.and_then(|paths| paths.get(&path));
+        let new_item = new_paths.and_then(|paths| paths.get(&path));
+        for method in union_keys(old_item, new_item) {
+            let location = format!("{} {}", method.to_uppercase(), path);
+            match (old_item.and_then(|item| item.get(&method)), new_item.and_then(|item| item.get(&method))) {
+                (Some(_), None) => changes.push(change(true, &location, "operation removed".to_string())),
+                (None, Some(_)) => changes.push(change(false, &location, "operation added".to_string())),
+                (Some(old_op), Some(new_op)) => compare_operation(&location, old_op, new_op, &mut changes),
+                (None, None) => {}
+            }
+        }
+    }
+
+    let mut usage = schema_usage(old);
+    for (name, new_usage) in schema_usage(new) {
+        let entry = usage.entry(name).or_default();
+        entry.input |= new_usage.input;
+        entry.output |= new_usage.output;
+    }
+    let old_schemas = old.pointer("/components/schemas");
+    let new_schemas = new.pointer("/components/schemas");
+    for name in union_keys(old_schemas, new_schemas) {
+        let location = format!("{}{}", SCHEMA_REF_PREFIX, name);
+        match (old_schemas.and_then(|s| s.get(&name)), new_schemas.and_then(|s| s.get(&name))) {
+            (Some(_), None) => changes.push(change(false, &location, "schema removed".to_string())),
+            (None, Some(_)) => changes.push(change(false, &location, "schema added".to_string())),
+            (Some(old_schema), Some(new_schema)) => {
+                let usage = usage.get(&name).copied().unwrap_or_default();
+                SchemaComparison { location: &location, usage, changes: &mut changes }
+                    .compare("", old_schema, new_schema);
+            }
+            (None, None) => {}
+        }
+    }
+
+    ChangeReport { changes }
+}
+
+fn change(breaking: bool, location: &str, message: String) -> ApiChange {
+    ApiChange { breaking, location: location.to_string(), message }
+}
+
+fn compare_operation(location: &str, old: &Value, new: &Value, changes: &mut Vec<ApiChange>) {
+    // 参数按 (in, name) 匹配
+    let parameters = |op: &Value| -> Vec<((String, String), Value)> {
+        op.get("parameters").and_then(Value::as_array).into_iter().flatten()
+            .map(|param| {
+                let key = (
+                    param.get("in").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    param.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                );
+                (key, param.clone())
+            })
+            .collect()
+    };
+    let old_params = parameters(old);
+    let new_params = parameters(new);
+    let find = |params: &[((String, String), Value)], key: &(String, String)| {
+        params.iter().find(|(k, _)| k == key).map(|(_, param)| param.clone())
+    };
+    let keys: BTreeSet<_> = old_params.iter().chain(&new_params).map(|(key, _)| key.clone()).collect();
+    for key in keys {
+        let (location_in, name) = &key;
+        match (find(&old_params, &key), find(&new_params, &key)) {
+            (Some(_), None) => changes.push(change(false, location, format!("{} parameter `{}` removed", location_in, name))),
+            (None, Some(param)) if is_required(&param) => {
+                changes.push(change(true, location, format!("new required {} parameter `{}`", location_in, name)));
+            }
+            (None, Some(_)) => changes.push(change(false, location, format!("new optional {} parameter `{}`", location_in, name))),
+            (Some(old_param), Some(new_param)) => {
+                match (is_required(&old_param), is_required(&new_param)) {
+                    (false, true) => changes.push(change(true, location, format!("{} parameter `{}` became required", location_in, name))),
+                    (true, false) => changes.push(change(false, location, format!("{} parameter `{}` became optional", location_in, name))),
+                    _ => {}
+                }
+                if let (Some(old_schema), Some(new_schema)) = (old_param.get("schema"), new_param.get("schema")) {
+                    SchemaComparison { location, usage: Usage::INPUT, changes: &mut *changes }
+                        .compare(&format!("{} parameter `{}`", location_in, name), old_schema, new_schema);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    match (old.get("requestBody"), new.get("requestBody")) {
+        (None, Some(body)) => {
+            let required = is_required(body);
+            let kind = if required { "required" } else { "optional" };
+            changes.push(change(required, location, format!("{} request body added", kind)));
+        }
+        (Some(_), None) => changes.push(change(false, location, "request body removed".to_string())),
+        (Some(old_body), Some(new_body)) => {
+            if !is_required(old_body) && is_required(new_body) {
+                changes.push(change(true, location, "request body became required".to_string()));
+            }
+            compare_content(location, "request body", Usage::INPUT, old_body, new_body, changes);
+        }
+        (None, None) => {}
+    }
+
+    let old_responses = old.get("responses");
+    let new_responses = new.get("responses");
+    for status in union_keys(old_responses, new_responses) {
+        let what = format!("response `{}`", status);
+        match (old_responses.and_then(|r| r.get(&status)), new_responses.and_then(|r| r.get(&status))) {
+            // 成功响应被移除会影响客户端；错误响应被移除则不会
+            (Some(_), None) => changes.push(change(status.starts_with('2'), location, format!("{} removed", what))),
+            (None, Some(_)) => changes.push(change(false, location, format!("{} added", what))),
+            (Some(old_response), Some(new_response)) => {
+                compare_content(location, &what, Usage::OUTPUT, old_response, new_response, changes);
+            }
+            (None, None) => {}
+        }
+    }
+
+    let has_security = |op: &Value| op.get("security").and_then(Value::as_array).is_some_and(|s| !s.is_empty());
+    match (has_security(old), has_security(new)) {
+        (false, true) => changes.push(change(true, location, "authentication is now required".to_string())),
+        (true, false) => changes.push(change(false, location, "authentication is no longer required".to_string())),
+        _ if old.get("security") != new.get("security") => {
+            changes.push(change(true, location, "security requirements changed".to_string()));
+        }
+        _ => {}
+    }
+
+    let is_deprecated = |op: &Value| op.get("deprecated") == Some(&Value::Bool(true));
+    if !is_deprecated(old) && is_deprecated(new) {
+        changes.push(change(false, location, "operation deprecated".to_string()));
+    }
+    for key in ["operationId", "summary", "description", "tags"] {
+        if old.get(key) != new.get(key) {
+            changes.push(change(false, location, format!("`{}` changed", key)));
+        }
+    }
+}
+
+// Compares the media types and schemas of a request body or response
+fn compare_content(location: &str, what: &str, usage: Usage, old: &Value, new: &Value, changes: &mut Vec<ApiChange>) {
+    let old_content = old.get("content");
+    let new_content = new.get("content");
+    for media_type in union_keys(old_content, new_content) {
+        let old_media = old_content.and_then(|c| c.get(&media_type));
+        let new_media = new_content.and_then(|c| c.get(&media_type));
+        match (old_media, new_media) {
+            (Some(_), None) => changes.push(change(true, location, format!("{} no longer uses `{}`", what, media_type))),
+            (None, Some(_)) => {
+                // 响应新增媒体类型（如原本无响应体）对已有客户端是新的返回形态
+                let breaking = usage.output && old_content.is_some_and(|c| c.as_object().is_some_and(|c| !c.is_empty()));
+                changes.push(change(breaking, location, format!("{} now uses `{}`", what, media_type)));
+            }
+            (Some(old_media), Some(new_media)) => {
+                if let (Some(old_schema), Some(new_schema)) = (old_media.get("schema"), new_media.get("schema")) {
+                    SchemaComparison { location, usage, changes: &mut *changes }.compare(what, old_schema, new_schema);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    if old.get("description") != new.get("description") {
+        changes.push(change(false, location, format!("{} description changed", what)));
+    }
+}
+
+fn is_required(value: &Value) -> bool {
+    value.get("required") == Some(&Value::Bool(true))
+}
+
+/// Where a schema is used: in requests (input), responses (output) or both.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Usage {
+    input: bool,
+    output: bool,
+}
+
+impl Usage {
+    const INPUT: Usage = Usage { input: true, output: false };
+    const OUTPUT: Usage = Usage { input: false, output: true };
+}
+
+// Usage of every component schema, following references between schemas
+fn schema_usage(spec: &Value) -> HashMap<String, Usage> {
+    let mut usage: HashMap<String, Usage> = HashMap::new();
+    let schemas = spec.pointer("/components/schemas");
+
+    let mark = |roots: Vec<&Value>, direction: Usage, usage: &mut HashMap<String, Usage>| {
+        let mut pending = Vec::new();
+        roots.into_iter().for_each(|root| collect_refs(root, &mut pending));
+        let mut seen = BTreeSet::new();
+        while let Some(name) = pending.pop() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            let entry = usage.entry(name.clone()).or_default();
+            entry.input |= direction.input;
+            entry.output |= direction.output;
+            if let Some(schema) = schemas.and_then(|s| s.get(&name)) {
+                collect_refs(schema, &mut pending);
+            }
+        }
+    };
+
+    let operations = spec.get("paths").and_then(Value::as_object).into_iter()
+        .flat_map(|paths| paths.values())
+        .filter_map(Value::as_object)
+        .flat_map(|item| item.values());
+    for operation in operations {
+        let inputs = ["parameters", "requestBody"].iter().filter_map(|key| operation.get(*key)).collect();
+        mark(inputs, Usage::INPUT, &mut usage);
+        mark(operation.get("responses").into_iter().collect(), Usage::OUTPUT, &mut usage);
+    }
+    usage
+}
+
+fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(name) = object.get("$ref").and_then(Value::as_str).and_then(|r| r.strip_prefix(SCHEMA_REF_PREFIX)) {
+                refs.push(name.to_string());
+            }
+            object.values().for_each(|value| collect_refs(value, refs));
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+        _ => {}
+    }
+}
+
+struct SchemaComparison<'a> {
+    location: &'a str,
+    usage: Usage,
+    changes: &'a mut Vec<ApiChange>,
+}
+
+// Schema keys compared structurally; any other key is documentation
+const STRUCTURAL_KEYS: &[&str] = &[
+    "$ref", "type", "format", "nullable", "enum", "properties", "required",
+    "items", "additionalProperties", "oneOf", "anyOf", "allOf",
+];
+
+impl SchemaComparison<'_> {
+    fn push(&mut self, breaking: bool, path: &str, message: String) {
+        let message = if path.is_empty() { message } else { format!("{}: {}", path, message) };
+        self.changes.push(change(breaking, self.location, message));
+    }
+
+    // Narrowing breaks requests, widening breaks responses, and any other
+    // change breaks both
+    fn narrowed(&mut self, path: &str, message: String) {
+        let breaking = self.usage.input;
+        self.push(breaking, path, message);
+    }
+
+    fn widened(&mut self, path: &str, message: String) {
+        let breaking = self.usage.output;
+        self.push(breaking, path, message);
+    }
+
+    fn changed(&mut self, path: &str, message: String) {
+        let breaking = self.usage.input || self.usage.output;
+        self.push(breaking, path, message);
+    }
+
+    fn compare(&mut self, path: &str, old: &Value, new: &Value) {
+        let (Some(old_object), Some(new_object)) = (old.as_object(), new.as_object()) else {
+            if old != new {
+                self.changed(path, "schema changed".to_string());
+            }
+            return;
+        };
+
+        let old_ref = old.get("$ref").and_then(Value::as_str);
+        let new_ref = new.get("$ref").and_then(Value::as_str);
+        if old_ref.is_some() || new_ref.is_some() {
+            // 同名引用的变化在对应的组件 schema 中比较
+            if old_ref != new_ref {
+                self.changed(path, format!("type changed from {} to {}", describe(old), describe(new)));
+            }
+            return;
+        }
+
+        self.compare_types(path, old, new);
+
+        match (old.get("format"), new.get("format")) {
+            (None, Some(format)) => self.narrowed(path, format!("format {} added", format)),
+            (Some(format), None) => self.widened(path, format!("format {} removed", format)),
+            (Some(old_format), Some(new_format)) if old_format != new_format => {
+                self.changed(path, format!("format changed from {} to {}", old_format, new_format));
+            }
+            _ => {}
+        }
+
+        let is_nullable = |schema: &Value| schema.get("nullable") == Some(&Value::Bool(true));
+        match (is_nullable(old), is_nullable(new)) {
+            (true, false) => self.narrowed(path, "no longer nullable".to_string()),
+            (false, true) => self.widened(path, "became nullable".to_string()),
+            _ => {}
+        }
+
+        self.compare_enums(path, old, new);
+        self.compare_properties(path, old, new);
+
+        for key in ["items", "additionalProperties"] {
+            if let (Some(old_sub), Some(new_sub)) = (old.get(key), new.get(key)) {
+                self.compare(&join(path, key), old_sub, new_sub);
+            }
+        }
+
+        for key in ["oneOf", "anyOf", "allOf"] {
+            let variants = |schema: &Value| schema.get(key).and_then(Value::as_array).cloned().unwrap_or_default();
+            let (old_variants, new_variants) = (variants(old), variants(new));
+            for (index, (old_sub, new_sub)) in old_variants.iter().zip(&new_variants).enumerate() {
+                self.compare(&join(path, &format!("{}[{}]", key, index)), old_sub, new_sub);
+            }
+            // allOf 中每个 schema 都是约束，oneOf/anyOf 中每个 schema 都是可选项
+            let more = new_variants.len() > old_variants.len();
+            let fewer = new_variants.len() < old_variants.len();
+            match key {
+                "allOf" if more => self.narrowed(path, "allOf schemas added".to_string()),
+                "allOf" if fewer => self.widened(path, "allOf schemas removed".to_string()),
+                _ if more => self.widened(path, format!("{} variants added", key)),
+                _ if fewer => self.narrowed(path, format!("{} variants removed", key)),
+                _ => {}
+            }
+        }
+
+        let documentation_changed = old_object.keys().chain(new_object.keys())
+            .filter(|key| !STRUCTURAL_KEYS.contains(&key.as_str()))
+            .any(|key| old.get(key) != new.get(key));
+        if documentation_changed {
+            self.push(false, path, "documentation changed".to_string());
+        }
+    }
+
+    fn compare_types(&mut self, path: &str, old: &Value, new: &Value) {
+        let old_types = types(old);
+        let new_types = types(new);
+        if old_types == new_types {
+            return;
+        }
+        let message = format!(
+            "type changed from {} to {}",
+            display_types(&old_types),
+            display_types(&new_types),
+        );
+        if covers(&old_types, &new_types) {
+            self.narrowed(path, message);
+        } else if covers(&new_types, &old_types) {
+            self.widened(path, message);
+        } else {
+            self.changed(path, message);
+        }
+    }
+
+    fn compare_enums(&mut self, path: &str, old: &Value, new: &Value) {
+        match (old.get("enum").and_then(Value::as_array), new.get("enum").and_then(Value::as_array)) {
+            (None, Some(_)) => self.narrowed(path, "restricted to enum values".to_string()),
+            (Some(_), None) => self.widened(path, "no longer restricted to enum values".to_string()),
+            (Some(old_values), Some(new_values)) => {
+                let removed: Vec<String> = old_values.iter().filter(|v| !new_values.contains(v)).map(Value::to_string).collect();
+                let added: Vec<String> = new_values.iter().filter(|v| !old_values.contains(v)).map(Value::to_string).collect();
+                if !removed.is_empty() {
+                    self.narrowed(path, format!("enum values removed: {}", removed.join(", ")));
+                }
+                if !added.is_empty() {
+                    self.widened(path, format!("enum values added: {}", added.join(", ")));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn compare_properties(&mut self, path: &str, old: &Value, new: &Value) {
+        let required = |schema: &Value, name: &str| {
+            schema.get("required").and_then(Value::as_array).is_some_and(|r| r.iter().any(|v| v == name))
+        };
+        let old_properties = old.get("properties");
+        let new_properties = new.get("properties");
+        for name in union_keys(old_properties, new_properties) {
+            let field = format!("field `{}`", name);
+            match (old_properties.and_then(|p| p.get(&name)), new_properties.and_then(|p| p.get(&name))) {
+                (Some(_), None) => {
+                    let breaking = self.usage.output;
+                    self.push(breaking, path, format!("{} removed", field));
+                }
+                (None, Some(_)) if required(new, &name) => self.narrowed(path, format!("new required {}", field)),
+                (None, Some(_)) => self.push(false, path, format!("new optional {}", field)),
+                (Some(old_field), Some(new_field)) => {
+                    match (required(old, &name), required(new, &name)) {
+                        (false, true) => self.narrowed(path, format!("{} became required", field)),
+                        (true, false) => self.widened(path, format!("{} became optional", field)),
+                        _ => {}
+                    }
+                    self.compare(&join(path, &name), old_field, new_field);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) }
+}
+
+// `type` as a set; empty for an untyped schema, which accepts anything
+fn types(schema: &Value) -> BTreeSet<String> {
+    match schema.get("type") {
+        Some(Value::String(ty)) => BTreeSet::from([ty.clone()]),
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+// Whether every value of `narrow` is also a value of `wide`
+fn covers(wide: &BTreeSet<String>, narrow: &BTreeSet<String>) -> bool {
+    if wide.is_empty() {
+        return true;
+    }
+    !narrow.is_empty() && narrow.iter().all(|ty| wide.contains(ty) || (ty == "integer" && wide.contains("number")))
+}
+
+fn display_types(types: &BTreeSet<String>) -> String {
+    match types.len() {
+        0 => "any".to_string(),
+        1 => format!("`{}`", types.iter().next().unwrap()),
+        _ => format!("`{}`", types.iter().cloned().collect::<Vec<_>>().join(" | ")),
+    }
+}
+
+fn describe(schema: &Value) -> String {
+    match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => format!("`{}`", reference.strip_prefix(SCHEMA_REF_PREFIX).unwrap_or(reference)),
+        None => display_types(&types(schema)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(paths: Value, schemas: Value) -> Value {
+        json!({"openapi": "3.0.0", "info": {"title": "A", "version": "1"}, "paths": paths, "components": {"schemas": schemas}})
+    }
+
+    fn messages(report: &ChangeReport, breaking: bool) -> Vec<String> {
+        report.changes.iter()
+            .filter(|change| change.breaking == breaking)
+            .map(|change| format!("{}: {}", change.location, change.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_operation_changes() {
+        let old = spec(json!({
+            "/users": {"get": {"parameters": [
+                {"in": "query", "name": "page", "required": false, "schema": {"type": "integer"}}
+            ], "responses": {"200": {"description": "OK"}}}},
+            "/users/:id": {"delete": {"responses": {"204": {"description": "No Content"}}}}
+        }), json!({}));
+        let new = spec(json!({
+            "/users": {
+                "get": {"parameters": [
+                    {"in": "query", "name": "page", "required": false, "schema": {"type": "number"}},
+                    {"in": "query", "name": "org", "required": true, "schema": {"type": "string"}}
+                ], "responses": {"200": {"description": "OK"}, "404": {"description": "Not Found"}}},
+                "post": {"responses": {"201": {"description": "Created"}}}
+            }
+        }), json!({}));
+
+        let report = compare_specs(&old, &new);
+        assert_eq!(messages(&report, true), vec![
+            "GET /users: new required query parameter `org`",
+            "DELETE /users/:id: operation removed",
+        ]);
+        assert_eq!(messages(&report, false), vec![
+            "GET /users: query parameter `page`: type changed from `integer` to `number`",
+            "GET /users: response `404` added",
+            "POST /users: operation added",
+        ]);
+    }
+
+    #[test]
+    fn test_schema_changes_by_direction() {
+        let paths = json!({
+            "/orders": {"post": {
+                "requestBody": {"required": true, "content": {"application/json": {"schema": {"$ref": "#/components/schemas/NewOrder"}}}},
+                "responses": {"200": {"description": "OK", "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Order"}}}}}
+            }}
+        });
+        let old = spec(paths.clone(), json!({
+            "NewOrder": {"type": "object", "properties": {
+                "size": {"type": "string", "enum": ["s", "m", "l"]},
+                "amount": {"type": "number"}
+            }, "required": ["size"]},
+            "Order": {"type": "object", "properties": {
+                "id": {"type": "integer"},
+                "status": {"type": "string", "enum": ["open", "closed"]},
+                "note": {"type": "string"}
+            }, "required": ["id", "status"]}
+        }));
+        let new = spec(paths, json!({
+            "NewOrder": {"type": "object", "properties": {
+                "size": {"type": "string", "enum": ["s", "m"]},
+                "amount": {"type": "integer"},
+                "currency": {"type": "string"}
+            }, "required": ["size", "currency"]},
+            "Order": {"type": "object", "properties": {
+                "id": {"type": "integer"},
+                "status": {"type": "string", "enum": ["open", "closed", "cancelled"]}
+            }, "required": ["id", "status"], "description": "An order"}
+        }));
+
+        let report = compare_specs(&old, &new);
+        assert_eq!(messages(&report, true), vec![
+            "#/components/schemas/NewOrder: amount: type changed from `number` to `integer`",
+            "#/components/schemas/NewOrder: new required field `currency`",
+            "#/components/schemas/NewOrder: size: enum values removed: \"l\"",
+            "#/components/schemas/Order: field `note` removed",
+            "#/components/schemas/Order: status: enum values added: \"cancelled\"",
+        ]);
+        assert_eq!(messages(&report, false), vec!["#/components/schemas/Order: documentation changed"]);
+        assert!(report.has_breaking());
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("### ⚠️ Breaking changes\n\n- `#/components/schemas/NewOrder`: amount: type changed"));
+        assert!(markdown.contains("### Non-breaking changes\n\n- `#/components/schemas/Order`: documentation changed\n"));
+    }
+
+    #[test]
+    fn test_identical_specs() {
+        let doc = spec(json!({"/": {"get": {"responses": {"200": {"description": "OK"}}}}}), json!({}));
+        let report = compare_specs(&doc, &doc);
+        assert!(report.changes.is_empty());
+        assert_eq!(report.to_string(), "No API changes\n");
+    }
+}
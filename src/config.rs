@@ -0,0 +1,292 @@
+//! Project configuration: `axum_doc.toml`, or `[package.metadata.axum_doc]`
+//! in `Cargo.toml`.
+//!
+//! ```toml
+//! entry_file = "src/main.rs"
+//! model_files = ["src/dto.rs"]
+//!
+//! [info]
+//! title = "Shop API"
+//! version = "2.1.0"
+//! contact = { name = "API team", email = "api@example.com" }
+//! license = { name = "MIT" }
+//!
+//! [[servers]]
+//! url = "https://api.example.com"
+//!
+//! [types]
+//! "rust_decimal::Decimal" = { type = "string", format = "decimal" }
+//!
+//! [extractors]
+//! ApiKey = { kind = "header", name = "x-api-key" }
+//! CurrentTenant = { kind = "server" }
+//!
+//! [[tags]]
+//! name = "users"
+//! description = "User management"
+//!
+//! [output]
+//! path = "openapi.yaml"
+//! spec_version = "3.1"
+//! ```
+
+use std::{collections::BTreeMap, error::Error, fs, path::{Path, PathBuf}};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::extractor_registry::{CustomExtractor, ExtractorInput};
+use crate::openapi::{Contact, License, OpenApi, Server, Tag};
+use crate::version::{OutputFormat, SpecVersion};
+
+/// Name of the configuration file looked up in the project directory.
+pub const CONFIG_FILE: &str = "axum_doc.toml";
+
+/// Project-wide settings. Every field is optional; unset values keep the
+/// generator's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// File holding the top-level router, relative to the project directory
+    pub entry_file: Option<PathBuf>,
+    /// Extra model files, relative to the project directory
+    pub model_files: Vec<PathBuf>,
+    pub info: InfoConfig,
+    pub servers: Vec<Server>,
+    /// Schemas used for types by name (`"Decimal"` or a path such as
+    /// `"rust_decimal::Decimal"`; only the last segment is matched)
+    pub types: BTreeMap<String, Value>,
+    /// What custom extractors document, by type name or crate path (needed
+    /// when several modules define the name, e.g. `"auth::Claims"`), for
+    /// extractors whose `FromRequest`/`FromRequestParts` impl can't be
+    /// analyzed; an entry replaces the analysis
+    pub extractors: BTreeMap<String, ExtractorConfig>,
+    /// Tag descriptions, in the order they should be listed
+    pub tags: Vec<Tag>,
+    pub output: OutputConfig,
+}
+
+/// The `[info]` table.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InfoConfig {
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub terms_of_service: Option<String>,
+    pub contact: Option<Contact>,
+    pub license: Option<License>,
+}
+
+/// An `[extractors]` entry.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExtractorConfig {
+    pub kind: ExtractorKind,
+    /// Type a `json`, `query`, `path` or `form` extractor documents; the
+    /// extractor's first type argument if unset, so required for extractors
+    /// without type arguments
+    #[serde(rename = "type")]
+    pub ty: Option<String>,
+    /// Header name, for `kind = "header"`
+    pub name: Option<String>,
+}
+
+/// What a configured extractor reads from the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractorKind {
+    Json,
+    Query,
+    Path,
+    Form,
+    Header,
+    Bearer,
+    /// Server-side only; documents nothing
+    Server,
+}
+
+impl ExtractorConfig {
+    /// The extractor this entry describes, in the form the impl analysis produces.
+    pub fn to_extractor(&self) -> Result<CustomExtractor, String> {
+        let builtin = |kind: &str| ExtractorInput::Builtin { kind: kind.to_string(), ty: self.ty.clone() };
+        let inputs = match self.kind {
+            ExtractorKind::Json => vec![builtin("Json")],
+            ExtractorKind::Query => vec![builtin("Query")],
+            ExtractorKind::Path => vec![builtin("Path")],
+            ExtractorKind::Form => vec![builtin("Form")],
+            ExtractorKind::Header => match &self.name {
+                Some(name) => vec![ExtractorInput::Header(name.to_lowercase())],
+                None => return Err("kind = \"header\" needs a header name".to_string()),
+            },
+            ExtractorKind::Bearer => vec![ExtractorInput::Bearer],
+            ExtractorKind::Server => Vec::new(),
+        };
+        Ok(CustomExtractor { generics: Vec::new(), inputs })
+    }
+}
+
+/// The `[output]` table.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Output file, relative to the project directory
+    pub path: Option<PathBuf>,
+    /// `"json"`, `"yaml"`, `"html"` or `"markdown"`; inferred from the output
+    /// file extension if unset
+    pub format: Option<OutputFormat>,
+    /// Markdown output: one file per tag in the output directory instead of
+    /// a single document
+    pub split: bool,
+    /// `"3.0"` (default) or `"3.1"`
+    pub spec_version: Option<SpecVersion>,
+}
+
+impl Config {
+    /// Loads the configuration of the project at `base_dir`: `axum_doc.toml`
+    /// if present, otherwise `[package.metadata.axum_doc]` from `Cargo.toml`,
+    /// otherwise the defaults.
+    pub fn load(base_dir: &Path) -> Result<Self, Box<dyn Error>> {
+        let config_file = base_dir.join(CONFIG_FILE);
+        if config_file.exists() {
+            return Config::from_file(&config_file);
+        }
+
+        let manifest = base_dir.join("Cargo.toml");
+        if manifest.exists() {
+            let content = fs::read_to_string(&manifest)?;
+            let manifest_value: toml::Value = toml::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", manifest.display(), e))?;
+            let metadata = manifest_value.get("package")
+                .and_then(|package| package.get("metadata"))
+                .and_then(|metadata| metadata.get("axum_doc"));
+            if let Some(metadata) = metadata {
+                return metadata.clone().try_into()
+                    .map_err(|e| format!("Invalid [package.metadata.axum_doc] in {}: {}", manifest.display(), e).into());
+            }
+        }
+
+        Ok(Config::default())
+    }
+
+    /// Reads a configuration file in `axum_doc.toml` format.
+    pub fn from_file(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {}: {}", path.display(), e))?;
+        toml::from_str(&content)
+            .map_err(|e| format!("Invalid config file {}: {}", path.display(), e).into())
+    }
+
+    /// Writes the document-level settings (info, servers, tags) into `spec`.
+    /// The target version is applied separately, by [`crate::Generator`].
+    pub fn apply(&self, spec: &mut OpenApi) {
+        let info = &self.info;
+        if let Some(title) = &info.title {
+            spec.info.title = title.clone();
+        }
+        if let Some(version) = &info.version {
+            spec.info.version = version.clone();
+        }
+        if info.description.is_some() {
+            spec.info.description = info.description.clone();
+        }
+        if info.terms_of_service.is_some() {
+            spec.info.terms_of_service = info.terms_of_service.clone();
+        }
+        if info.contact.is_some() {
+            spec.info.contact = info.contact.clone();
+        }
+        if info.license.is_some() {
+            spec.info.license = info.license.clone();
+        }
+        if !self.servers.is_empty() {
+            spec.servers = self.servers.clone();
+        }
+        if !self.tags.is_empty() {
+            spec.tags = self.tags.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(r#"
+            entry_file = "src/app.rs"
+            model_files = ["src/dto.rs"]
+
+            [info]
+            title = "Shop API"
+            version = "2.1.0"
+            license = { name = "MIT" }
+
+            [[servers]]
+            url = "https://api.example.com"
+            description = "Production"
+
+            [types]
+            "rust_decimal::Decimal" = { type = "string", format = "decimal" }
+
+            [extractors]
+            ValidatedJson = { kind = "json" }
+            ApiKey = { kind = "header", name = "X-Api-Key" }
+
+            [[tags]]
+            name = "users"
+            description = "User management"
+
+            [output]
+            path = "docs/openapi.json"
+            format = "yaml"
+            spec_version = "3.1"
+        "#).unwrap();
+
+        assert_eq!(config.entry_file, Some(PathBuf::from("src/app.rs")));
+        assert_eq!(config.model_files, vec![PathBuf::from("src/dto.rs")]);
+        assert_eq!(config.info.title.as_deref(), Some("Shop API"));
+        assert_eq!(config.info.license.as_ref().map(|l| l.name.as_str()), Some("MIT"));
+        assert_eq!(config.servers[0].url, "https://api.example.com");
+        assert_eq!(config.types["rust_decimal::Decimal"], serde_json::json!({"type": "string", "format": "decimal"}));
+        assert_eq!(config.extractors["ValidatedJson"].kind, ExtractorKind::Json);
+        assert_eq!(config.extractors["ApiKey"].to_extractor().unwrap().inputs, vec![ExtractorInput::Header("x-api-key".into())]);
+        assert_eq!(config.tags[0].description.as_deref(), Some("User management"));
+        assert_eq!(config.output.path, Some(PathBuf::from("docs/openapi.json")));
+        assert_eq!(config.output.format, Some(OutputFormat::Yaml));
+        assert_eq!(config.output.spec_version, Some(SpecVersion::V3_1));
+    }
+
+    #[test]
+    fn test_unknown_keys_rejected() {
+        assert!(toml::from_str::<Config>("entry = \"src/main.rs\"").is_err());
+        assert!(toml::from_str::<Config>("[info]\nname = \"x\"").is_err());
+        assert!(toml::from_str::<Config>("[output]\nspec_version = \"2.0\"").is_err());
+    }
+
+    #[test]
+    fn test_load_from_cargo_metadata() {
+        let dir = std::env::temp_dir().join("axum_doc_config_metadata");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Cargo.toml"), r#"
+            [package]
+            name = "demo"
+            version = "0.1.0"
+
+            [package.metadata.axum_doc]
+            entry_file = "src/lib.rs"
+            info = { title = "Demo" }
+        "#).unwrap();
+
+        let config = Config::load(&dir).unwrap();
+        assert_eq!(config.entry_file, Some(PathBuf::from("src/lib.rs")));
+        assert_eq!(config.info.title.as_deref(), Some("Demo"));
+
+        // axum_doc.toml takes precedence over the manifest
+        fs::write(dir.join(CONFIG_FILE), "[info]\ntitle = \"From file\"").unwrap();
+        let config = Config::load(&dir).unwrap();
+        assert_eq!(config.info.title.as_deref(), Some("From file"));
+        assert_eq!(config.entry_file, None);
+    }
+}
@@ -0,0 +1,658 @@
+//! Crate-wide module tree: follows `mod` declarations from the entry file the
+//! way rustc does, records each module's `use` imports and indexes every
+//! struct, enum and function by its fully-qualified module path.
+
+use std::{collections::{HashMap, HashSet}, error::Error, path::{Path as StdPath, PathBuf}};
+use quote::ToTokens;
+use syn::{visit_mut::VisitMut, Item, Type, UseTree};
+
+use crate::error_registry::{parse_error_items, ErrorResponse, ResponseBody};
+use crate::extractor_registry::{parse_extractor_items, CustomExtractor, ExtractorInput};
+use crate::models::{parse_model_items, StructInfo};
+use crate::schema::{path_name, schema_name};
+use crate::router::extract_module_from_path;
+use crate::sources::{SourceCache, SourceError};
+
+// Upper bound when following `pub use` re-export chains
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// A single module of the crate, either file-backed or an inline `mod x { .. }`.
+pub struct ModuleInfo {
+    /// Module path from the crate root, e.g. `["api", "users"]`.
+    pub path: Vec<String>,
+    /// Source file the module's items live in.
+    pub file: PathBuf,
+    /// Whether this is an inline `mod x { .. }` block inside `file`.
+    pub inline: bool,
+    pub items: Vec<Item>,
+    /// Imported names mapped to the path they refer to, resolved to crate-absolute
+    /// paths where the target is inside the crate.
+    pub imports: HashMap<String, Vec<String>>,
+    /// Prefixes of `use prefix::*` imports.
+    pub globs: Vec<Vec<String>>,
+}
+
+/// Index of every module reachable from the crate root and entry file.
+pub struct CrateIndex {
+    modules: Vec<ModuleInfo>,
+    definitions: HashSet<Vec<String>>,
+    /// Type names defined in more than one module
+    clashes: HashSet<String>,
+    functions: HashSet<Vec<String>>,
+    sources: SourceCache,
+}
+
+impl CrateIndex {
+    /// Walks the module tree of the project at `base_dir`.
+    ///
+    /// Both the crate roots (`src/lib.rs`, `src/main.rs`) and `entry_file` are
+    /// used as starting points, so an entry file that is itself a submodule
+    /// still sees models declared elsewhere in the crate.
+    pub fn build(base_dir: &StdPath, entry_file: &StdPath) -> Result<Self, Box<dyn Error>> {
+        CrateIndex::build_with(base_dir, entry_file, &SourceCache::new())
+    }
+
+    /// Like [`CrateIndex::build`], reading files through `sources` so
+    /// unchanged files are not parsed again.
+    pub fn build_with(base_dir: &StdPath, entry_file: &StdPath, sources: &SourceCache) -> Result<Self, Box<dyn Error>> {
+        let mut index = CrateIndex {
+            modules: Vec::new(),
+            definitions: HashSet::new(),
+            clashes: HashSet::new(),
+            functions: HashSet::new(),
+            sources: sources.clone(),
+        };
+        let mut visited = HashSet::new();
+
+        for root in ["src/lib.rs", "src/main.rs"] {
+            let root_path = base_dir.join(root);
+            if root_path.exists() {
+                index.load_file(&root_path, Vec::new(), &mut visited)?;
+            }
+        }
+
+        if !visited.contains(entry_file) {
+            let module_path = extract_module_from_path(base_dir, entry_file);
+            index.load_file(entry_file, module_path, &mut visited)?;
+        }
+
+        let mut names = HashSet::new();
+        for path in &index.definitions {
+            let name = path.last().expect("definition paths are never empty");
+            if !names.insert(name) {
+                index.clashes.insert(name.clone());
+            }
+        }
+
+        index.resolve_imports();
+        Ok(index)
+    }
+
+    pub fn modules(&self) -> &[ModuleInfo] {
+        &self.modules
+    }
+
+    /// Looks a module up by its path from the crate root.
+    pub fn module(&self, path: &[String]) -> Option<&ModuleInfo> {
+        self.modules.iter().find(|m| m.path == path)
+    }
+
+    /// Returns the file-level module whose source is `file`.
+    pub fn module_for_file(&self, file: &StdPath) -> Option<&ModuleInfo> {
+        self.modules.iter().find(|m| !m.inline && m.file == file)
+    }
+
+    /// Resolves `segments`, written inside module `from`, to the crate-absolute
+    /// path of a struct or enum definition.
+    pub fn resolve_path(&self, from: &[String], segments: &[String]) -> Option<Vec<String>> {
+        let candidate = self.qualify(&self.definitions, from, segments)?;
+        self.canonicalize(&self.definitions, candidate, 0)
+    }
+
+    /// Resolves `segments`, written inside module `from`, to the crate-absolute
+    /// path of a free function, following imports and re-exports.
+    pub fn resolve_fn(&self, from: &[String], segments: &[String]) -> Option<Vec<String>> {
+        let candidate = self.qualify(&self.functions, from, segments)?;
+        self.canonicalize(&self.functions, candidate, 0)
+    }
+
+    /// Looks a free function up by its crate-absolute path, along with the
+    /// module defining it.
+    pub fn function(&self, path: &[String]) -> Option<(&ModuleInfo, &syn::ItemFn)> {
+        let (name, parent) = path.split_last()?;
+        let module = self.module(parent)?;
+        module.items.iter().find_map(|item| match item {
+            Item::Fn(func) if func.sig.ident == name => Some((module, func)),
+            _ => None,
+        })
+    }
+
+    /// Component name of the struct or enum defined at `path`: its bare name,
+    /// or its module path joined with dots (`users.Item`) when another module
+    /// defines a type with the same name.
+    pub fn model_name(&self, path: &[String]) -> String {
+        match path.last() {
+            Some(name) if !self.clashes.contains(name) => name.clone(),
+            _ => path.join("."),
+        }
+    }
+
+    /// Rewrites every path type in `ty` that names a crate type (through an
+    /// import, alias or qualified path) to the bare name of its definition,
+    /// or to its `crate::` path when the name clashes, so that
+    /// [`schema_name`] gives the keys of [`CrateIndex::models`]. Renamed
+    /// imports of external types, such as `use axum::extract::Path as AxumPath`,
+    /// are rewritten to their original name.
+    pub fn normalize_type(&self, from: &[String], ty: &Type) -> Type {
+        let mut ty = ty.clone();
+        TypeNormalizer { index: self, from }.visit_type_mut(&mut ty);
+        ty
+    }
+
+    /// Parses every struct and enum in the crate into models keyed by
+    /// [`CrateIndex::model_name`], with field types normalized through each
+    /// module's imports.
+    pub fn models(&self) -> HashMap<String, StructInfo> {
+        let mut models = HashMap::new();
+
+        for module in &self.modules {
+            let mut items = module.items.clone();
+            for item in &mut items {
+                let mut normalizer = TypeNormalizer { index: self, from: &module.path };
+                match item {
+                    Item::Struct(item_struct) => normalizer.visit_fields_mut(&mut item_struct.fields),
+                    Item::Enum(item_enum) => {
+                        for variant in &mut item_enum.variants {
+                            normalizer.visit_fields_mut(&mut variant.fields);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            for (name, mut info) in parse_model_items(&items) {
+                // 同名类型以模块路径区分，如 users.Item 与 orders.Item
+                info.name = self.model_name(&child_path(&module.path, &name));
+                models.insert(info.name.clone(), info);
+            }
+        }
+
+        models
+    }
+
+    /// Collects the responses of every `impl IntoResponse for X` in the crate,
+    /// keyed by the normalized name of `X`. JSON body types are normalized
+    /// the same way as model fields.
+    pub fn error_responses(&self) -> HashMap<String, Vec<ErrorResponse>> {
+        let mut registry = HashMap::new();
+        for module in &self.modules {
+            for (self_ty, mut responses) in parse_error_items(&module.items) {
+                for response in &mut responses {
+                    if let ResponseBody::Json(ty) = &mut response.body {
+                        if let Ok(parsed) = syn::parse_str::<Type>(ty) {
+                            *ty = self.normalize_type(&module.path, &parsed).to_token_stream().to_string();
+                        }
+                    }
+                }
+                let self_ty = self.normalize_type(&module.path, &self_ty).to_token_stream().to_string();
+                registry.entry(schema_name(&self_ty)).or_insert(responses);
+            }
+        }
+        registry
+    }
+
+    /// Collects the inputs of every `FromRequest`/`FromRequestParts` impl in
+    /// the crate, keyed by the implementing type's name, qualified like
+    /// [`CrateIndex::model_name`] when it clashes. Types read by the
+    /// extractors are normalized the same way as model fields; impls whose
+    /// inputs can't be told are left out.
+    pub fn custom_extractors(&self) -> HashMap<String, CustomExtractor> {
+        let mut registry = HashMap::new();
+        for module in &self.modules {
+            for (self_ty, mut extractor) in parse_extractor_items(&module.items) {
+                if extractor.inputs.is_empty() {
+                    continue;
+                }
+                for input in &mut extractor.inputs {
+                    if let ExtractorInput::Builtin { ty: Some(ty), .. } | ExtractorInput::Custom(ty) = input {
+                        if let Ok(parsed) = syn::parse_str::<Type>(ty) {
+                            *ty = self.normalize_type(&module.path, &parsed).to_token_stream().to_string();
+                        }
+                    }
+                }
+                if let Type::Path(type_path) = self.normalize_type(&module.path, &self_ty) {
+                    registry.entry(path_name(&type_path.path)).or_insert(extractor);
+                }
+            }
+        }
+        registry
+    }
+
+    fn load_file(
+        &mut self,
+        file: &StdPath,
+        module_path: Vec<String>,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Result<(), Box<dyn Error>> {
+        if !visited.insert(file.to_path_buf()) {
+            return Ok(());
+        }
+
+        let ast = match self.sources.parse(file) {
+            Ok(ast) => ast,
+            Err(SourceError::Parse(e)) => {
+                eprintln!("Warning: Failed to parse module file {}: {}", file.display(), e);
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let dir = file.parent().map(StdPath::to_path_buf).unwrap_or_default();
+        let file_name = file.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        // main.rs, lib.rs and mod.rs own their directory; foo.rs owns foo/
+        let child_dir = if matches!(file_name, "main.rs" | "lib.rs" | "mod.rs") {
+            dir
+        } else {
+            dir.join(file_name.trim_end_matches(".rs"))
+        };
+
+        self.load_items(file, module_path, ast.items.clone(), child_dir, false, visited)
+    }
+
+    fn load_items(
+        &mut self,
+        file: &StdPath,
+        module_path: Vec<String>,
+        items: Vec<Item>,
+        child_dir: PathBuf,
+        inline: bool,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut imports = HashMap::new();
+        let mut globs = Vec::new();
+        let mut children = Vec::new();
+
+        for item in &items {
+            match item {
+                Item::Struct(s) => {
+                    self.definitions.insert(child_path(&module_path, &s.ident.to_string()));
+                }
+                Item::Enum(e) => {
+                    self.definitions.insert(child_path(&module_path, &e.ident.to_string()));
+                }
+                Item::Fn(f) => {
+                    self.functions.insert(child_path(&module_path, &f.sig.ident.to_string()));
+                }
+                Item::Use(u) => collect_use_tree(&u.tree, Vec::new(), &mut imports, &mut globs),
+                Item::Mod(m) if !is_cfg_test(&m.attrs) => children.push(m.clone()),
+                _ => {}
+            }
+        }
+
+        self.modules.push(ModuleInfo {
+            path: module_path.clone(),
+            file: file.to_path_buf(),
+            inline,
+            items,
+            imports,
+            globs,
+        });
+
+        for item_mod in children {
+            let name = item_mod.ident.to_string();
+            let path = child_path(&module_path, &name);
+
+            if let Some((_, items)) = item_mod.content {
+                self.load_items(file, path, items, child_dir.join(&name), true, visited)?;
+                continue;
+            }
+
+            let candidates = match path_attribute(&item_mod.attrs) {
+                Some(custom) => vec![child_dir.join(custom)],
+                None => vec![
+                    child_dir.join(format!("{}.rs", name)),
+                    child_dir.join(&name).join("mod.rs"),
+                    // Lenient fallback for sibling files that rustc itself would reject
+                    file.parent().unwrap_or(StdPath::new("")).join(format!("{}.rs", name)),
+                ],
+            };
+
+            match candidates.iter().find(|p| p.exists()) {
+                Some(module_file) => self.load_file(module_file, path, visited)?,
+                None => eprintln!("Warning: Module file not found for 'mod {}' in {} (tried paths: {:?})",
+                                  name, file.display(), candidates),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Turns the raw `use` paths recorded per module into crate-absolute paths
+    fn resolve_imports(&mut self) {
+        let resolved: Vec<_> = self.modules.iter()
+            .map(|module| {
+                let imports = module.imports.iter()
+                    .map(|(name, raw)| {
+                        let path = self.qualify_use(&module.path, raw).unwrap_or_else(|| raw.clone());
+                        (name.clone(), path)
+                    })
+                    .collect::<HashMap<_, _>>();
+                let globs = module.globs.iter()
+                    .map(|raw| self.qualify_use(&module.path, raw).unwrap_or_else(|| raw.clone()))
+                    .collect::<Vec<_>>();
+                (imports, globs)
+            })
+            .collect();
+
+        for (module, (imports, globs)) in self.modules.iter_mut().zip(resolved) {
+            module.imports = imports;
+            module.globs = globs;
+        }
+    }
+
+    // Applies `crate::`/`self::`/`super::` and child-module rules to a use path
+    fn qualify_use(&self, from: &[String], raw: &[String]) -> Option<Vec<String>> {
+        let (first, rest) = raw.split_first()?;
+        match first.as_str() {
+            "crate" => Some(rest.to_vec()),
+            "self" => Some([from, rest].concat()),
+            "super" => {
+                let mut base = from.to_vec();
+                let mut rest = raw;
+                while let Some((seg, tail)) = rest.split_first() {
+                    if seg != "super" {
+                        break;
+                    }
+                    base.pop()?;
+                    rest = tail;
+                }
+                Some([base.as_slice(), rest].concat())
+            }
+            _ => {
+                let relative = child_path(from, first);
+                if self.module(&relative).is_some()
+                    || self.definitions.contains(&relative)
+                    || self.functions.contains(&relative) {
+                    Some([from, raw].concat())
+                } else if self.module(std::slice::from_ref(first)).is_some() {
+                    // 2015-style path relative to the crate root
+                    Some(raw.to_vec())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    // Qualifies a path used inside module `from`, consulting its imports;
+    // `definitions` holds the items the path may name
+    fn qualify(&self, definitions: &HashSet<Vec<String>>, from: &[String], segments: &[String]) -> Option<Vec<String>> {
+        let (first, rest) = segments.split_first()?;
+        if matches!(first.as_str(), "crate" | "self" | "super") {
+            return self.qualify_use(from, segments);
+        }
+
+        let module = self.module(from)?;
+        if let Some(target) = module.imports.get(first) {
+            return Some([target.as_slice(), rest].concat());
+        }
+
+        let local = [from, segments].concat();
+        if definitions.contains(&local) || (segments.len() > 1 && self.module(&local[..local.len() - 1]).is_some()) {
+            return Some(local);
+        }
+
+        for glob in &module.globs {
+            let candidate = [glob.as_slice(), segments].concat();
+            if self.canonicalize(definitions, candidate.clone(), 0).is_some() {
+                return Some(candidate);
+            }
+        }
+
+        None
+    }
+
+    // Follows re-exports until `path` names an actual definition
+    fn canonicalize(&self, definitions: &HashSet<Vec<String>>, path: Vec<String>, depth: usize) -> Option<Vec<String>> {
+        if definitions.contains(&path) {
+            return Some(path);
+        }
+        if depth >= MAX_REEXPORT_DEPTH {
+            return None;
+        }
+
+        let (name, parent) = path.split_last()?;
+        let module = self.module(parent)?;
+        if let Some(target) = module.imports.get(name) {
+            return self.canonicalize(definitions, target.clone(), depth + 1);
+        }
+        module.globs.iter()
+            .find_map(|glob| self.canonicalize(definitions, child_path(glob, name), depth + 1))
+    }
+}
+
+struct TypeNormalizer<'a> {
+    index: &'a CrateIndex,
+    from: &'a [String],
+}
+
+impl VisitMut for TypeNormalizer<'_> {
+    fn visit_type_path_mut(&mut self, type_path: &mut syn::TypePath) {
+        // Normalize generic arguments first, e.g. Json<Account> -> Json<User>
+        syn::visit_mut::visit_type_path_mut(self, type_path);
+
+        if type_path.qself.is_some() {
+            return;
+        }
+        let segments: Vec<String> = type_path.path.segments.iter()
+            .map(|s| s.ident.to_string())
+            .collect();
+        if let Some(resolved) = self.index.resolve_path(self.from, &segments) {
+            let (name, module) = resolved.split_last().expect("resolved paths are never empty");
+            let mut segment = type_path.path.segments.pop().expect("type paths have a segment").into_value();
+            let span = segment.ident.span();
+            segment.ident = syn::Ident::new(name, span);
+            type_path.path.segments.clear();
+            type_path.path.leading_colon = None;
+            // 重名类型保留 crate 路径，schema_name 据此生成限定名
+            if self.index.clashes.contains(name) {
+                for ident in std::iter::once("crate").chain(module.iter().map(String::as_str)) {
+                    type_path.path.segments.push(syn::PathSegment::from(syn::Ident::new(ident, span)));
+                }
+            }
+            type_path.path.segments.push(segment);
+        } else if let [alias] = segments.as_slice() {
+            // 外部类型的别名（如 `use axum::extract::Path as AxumPath`）还原为原名
+            let original = self.index.module(self.from)
+                .and_then(|module| module.imports.get(alias))
+                .and_then(|target| target.last());
+            if let Some(original) = original.filter(|original| *original != alias) {
+                let segment = &mut type_path.path.segments[0];
+                segment.ident = syn::Ident::new(original, segment.ident.span());
+            }
+        }
+    }
+}
+
+fn collect_use_tree(
+    tree: &UseTree,
+    prefix: Vec<String>,
+    imports: &mut HashMap<String, Vec<String>>,
+    globs: &mut Vec<Vec<String>>,
+) {
+    match tree {
+        UseTree::Path(p) => collect_use_tree(&p.tree, child_path(&prefix, &p.ident.to_string()), imports, globs),
+        UseTree::Name(n) => {
+            let name = n.ident.to_string();
+            if name == "self" {
+                if let Some(last) = prefix.last() {
+                    imports.insert(last.clone(), prefix.clone());
+                }
+            } else {
+                imports.insert(name.clone(), child_path(&prefix, &name));
+            }
+        }
+        UseTree::Rename(r) => {
+            let target = if r.ident == "self" { prefix } else { child_path(&prefix, &r.ident.to_string()) };
+            imports.insert(r.rename.to_string(), target);
+        }
+        UseTree::Glob(_) => globs.push(prefix),
+        UseTree::Group(g) => {
+            for item in &g.items {
+                collect_use_tree(item, prefix.clone(), imports, globs);
+            }
+        }
+    }
+}
+
+fn child_path(parent: &[String], name: &str) -> Vec<String> {
+    let mut path = parent.to_vec();
+    path.push(name.to_string());
+    path
+}
+
+// Reads `#[path = "..."]` from a module declaration
+fn path_attribute(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("path") {
+            return None;
+        }
+        if let syn::Meta::NameValue(nv) = &attr.meta {
+            if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) = &nv.value {
+                return Some(s.value());
+            }
+        }
+        None
+    })
+}
+
+fn is_cfg_test(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("cfg")
+            && attr.parse_args::<syn::Ident>().map(|i| i == "test").unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn index_from(name: &str, files: &[(&str, &str)]) -> (PathBuf, CrateIndex) {
+        let dir = std::env::temp_dir().join(format!("axum_doc_index_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (path, content) in files {
+            let file = dir.join(path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, content).unwrap();
+        }
+        let index = CrateIndex::build(&dir, &dir.join("src/main.rs")).unwrap();
+        (dir, index)
+    }
+
+    #[test]
+    fn test_follows_mod_declarations() {
+        let (dir, index) = index_from("mods", &[
+            ("src/main.rs", "mod api; mod types; mod inline { pub struct Inline; }"),
+            ("src/api/mod.rs", "pub mod users;"),
+            ("src/api/users.rs", "pub struct UserDto { pub id: u64 }"),
+            ("src/types.rs", "pub struct User { pub name: String }"),
+        ]);
+
+        let paths: Vec<String> = index.modules().iter().map(|m| m.path.join("::")).collect();
+        assert_eq!(paths, vec!["", "api", "api::users", "types", "inline"]);
+
+        let models = index.models();
+        assert!(models.contains_key("UserDto"));
+        assert!(models.contains_key("User"));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_resolves_aliases_and_qualified_paths() {
+        let (dir, index) = index_from("aliases", &[
+            ("src/main.rs", "mod types; mod handlers;"),
+            ("src/types.rs", "pub struct User { pub id: u64 }"),
+            ("src/handlers.rs", "use crate::types::User as Account;\nuse super::types;\nuse axum::extract::Path as AxumPath;"),
+        ]);
+
+        let from = vec!["handlers".to_string()];
+        let ty: Type = syn::parse_quote!(Json<Account>);
+        let normalized = index.normalize_type(&from, &ty);
+        assert_eq!(quote::ToTokens::to_token_stream(&normalized).to_string(), "Json < User >");
+
+        let ty: Type = syn::parse_quote!(Vec<types::User>);
+        let normalized = index.normalize_type(&from, &ty);
+        assert_eq!(quote::ToTokens::to_token_stream(&normalized).to_string(), "Vec < User >");
+
+        let ty: Type = syn::parse_quote!(AxumPath<u64>);
+        let normalized = index.normalize_type(&from, &ty);
+        assert_eq!(quote::ToTokens::to_token_stream(&normalized).to_string(), "Path < u64 >");
+
+        assert_eq!(
+            index.resolve_path(&from, &["crate".into(), "types".into(), "User".into()]),
+            Some(vec!["types".to_string(), "User".to_string()])
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_follows_reexports_and_globs() {
+        let (dir, index) = index_from("reexports", &[
+            ("src/main.rs", "mod models; use models::*;"),
+            ("src/models/mod.rs", "mod inner;\npub use inner::Order;"),
+            ("src/models/inner.rs", "pub struct Order { pub total: f64 }"),
+        ]);
+
+        assert_eq!(
+            index.resolve_path(&[], &["Order".into()]),
+            Some(vec!["models".to_string(), "inner".to_string(), "Order".to_string()])
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_qualifies_clashing_model_names() {
+        let (dir, index) = index_from("clashes", &[
+            ("src/main.rs", "mod users; mod orders;\nuse orders::Item as OrderItem;\npub struct Summary { pub order: OrderItem }"),
+            ("src/users.rs", "pub struct Item { pub name: String }\npub struct Profile { pub id: u64 }"),
+            ("src/orders.rs", "pub struct Item { pub total: f64 }"),
+        ]);
+
+        let models = index.models();
+        let mut names: Vec<&str> = models.keys().map(String::as_str).collect();
+        names.sort();
+        // 只有重名的类型才带模块路径
+        assert_eq!(names, vec!["Profile", "Summary", "orders.Item", "users.Item"]);
+        assert_eq!(models["users.Item"].name, "users.Item");
+        assert_eq!(models["Summary"].fields[0].ty, "crate :: orders :: Item");
+
+        let from = vec!["users".to_string()];
+        let ty: Type = syn::parse_quote!(Json<Vec<Item>>);
+        let normalized = index.normalize_type(&from, &ty).to_token_stream().to_string();
+        assert_eq!(normalized, "Json < Vec < crate :: users :: Item > >");
+        assert_eq!(schema_name("crate :: users :: Item"), "users.Item");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_custom_extractors_keyed_by_path() {
+        let impl_for = |header: &str| format!(
+            "pub struct Claims;\nimpl<S> FromRequestParts<S> for Claims {{\n\
+             async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, ()> {{ parts.headers.get(\"{}\"); Ok(Claims) }}\n}}",
+            header);
+        let (dir, index) = index_from("extractor_clashes", &[
+            ("src/main.rs", "mod auth; mod admin;"),
+            ("src/auth.rs", &impl_for("x-user")),
+            ("src/admin.rs", &impl_for("x-admin")),
+        ]);
+
+        let registry = index.custom_extractors();
+        let mut names: Vec<&str> = registry.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, vec!["admin.Claims", "auth.Claims"]);
+        assert_eq!(registry["auth.Claims"].inputs, vec![ExtractorInput::Header("x-user".into())]);
+        assert_eq!(registry["admin.Claims"].inputs, vec![ExtractorInput::Header("x-admin".into())]);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,267 @@
+//! Semantic comparison of two OpenAPI documents.
+//!
+//! Documents are compared as JSON values, so key order and formatting do not
+//! matter. Differences are grouped by operation (`GET /users/:id`), by
+//! component schema, and for the remaining document-level fields.
+
+use std::{collections::BTreeSet, error::Error, fmt, fs, path::Path};
+use serde_json::Value;
+
+use crate::version::OutputFormat;
+
+/// Differences between two documents.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpecDiff {
+    /// Operations, named `METHOD /path`
+    pub operations: Vec<ItemDiff>,
+    /// Component schemas, by name
+    pub schemas: Vec<ItemDiff>,
+    /// Fields outside paths and schemas (`info`, `servers`, ...)
+    pub document: Vec<FieldChange>,
+}
+
+/// An operation or schema that was added, removed or changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemDiff {
+    pub name: String,
+    pub change: ItemChange,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemChange {
+    Added,
+    Removed,
+    /// The changed fields, relative to the item
+    Changed(Vec<FieldChange>),
+}
+
+/// A field whose value differs. `old` is `None` for an added field and
+/// `new` is `None` for a removed one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Dot-separated location, e.g. `responses.200.description`
+    pub path: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+impl SpecDiff {
+    /// Whether the documents are semantically equal.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty() && self.schemas.is_empty() && self.document.is_empty()
+    }
+}
+
+/// Reads an OpenAPI document from a JSON or YAML file (by extension).
+pub fn load_spec(path: &Path) -> Result<Value, Box<dyn Error>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let spec = match OutputFormat::from_path(path) {
+        OutputFormat::Yaml => serde_yaml::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+        _ => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+    };
+    Ok(spec)
+}
+
+/// Compares two OpenAPI documents.
+pub fn diff_specs(old: &Value, new: &Value) -> SpecDiff {
+    let mut diff = SpecDiff::default();
+
+    let old_paths = old.get("paths");
+    let new_paths = new.get("paths");
+    for path in union_keys(old_paths, new_paths) {
+        let old_item = old_paths.and_then(|paths| paths.get(&path));
+        let new_item = new_paths.and_then(|paths| paths.get(&path));
+        for method in union_keys(old_item, new_item) {
+            let name = format!("{} {}", method.to_uppercase(), path);
+            let old_op = old_item.and_then(|item| item.get(&method));
+            let new_op = new_item.and_then(|item| item.get(&method));
+            if let Some(item) = item_diff(name, old_op, new_op) {
+                diff.operations.push(item);
+            }
+        }
+    }
+
+    let old_schemas = old.pointer("/components/schemas");
+    let new_schemas = new.pointer("/components/schemas");
+    for name in union_keys(old_schemas, new_schemas) {
+        let old_schema = old_schemas.and_then(|schemas| schemas.get(&name));
+        let new_schema = new_schemas.and_then(|schemas| schemas.get(&name));
+        if let Some(item) = item_diff(name, old_schema, new_schema) {
+            diff.schemas.push(item);
+        }
+    }
+
+    field_changes("", &without_items(old), &without_items(new), &mut diff.document);
+    diff
+}
+
+fn item_diff(name: String, old: Option<&Value>, new: Option<&Value>) -> Option<ItemDiff> {
+    let change = match (old, new) {
+        (None, None) => return None,
+        (None, Some(_)) => ItemChange::Added,
+        (Some(_), None) => ItemChange::Removed,
+        (Some(old), Some(new)) => {
+            let mut changes = Vec::new();
+            field_changes("", old, new, &mut changes);
+            if changes.is_empty() {
+                return None;
+            }
+            ItemChange::Changed(changes)
+        }
+    };
+    Some(ItemDiff { name, change })
+}
+
+// Leaf differences between two values. Objects are compared key by key;
+// anything else (including arrays) is reported as a whole.
+fn field_changes(prefix: &str, old: &Value, new: &Value, out: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(_), Value::Object(_)) => {
+            for key in union_keys(Some(old), Some(new)) {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                match (old.get(&key), new.get(&key)) {
+                    (Some(old), Some(new)) => field_changes(&path, old, new, out),
+                    (old, new) => out.push(FieldChange { path, old: old.cloned(), new: new.cloned() }),
+                }
+            }
+        }
+        _ if old != new => out.push(FieldChange {
+            path: prefix.to_string(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Keys present in either of two JSON objects, in sorted order.
+pub(crate) fn union_keys(old: Option<&Value>, new: Option<&Value>) -> BTreeSet<String> {
+    [old, new]
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+        .flat_map(|object| object.keys().cloned())
+        .collect()
+}
+
+// The document without the parts compared item by item
+fn without_items(spec: &Value) -> Value {
+    let mut rest = spec.clone();
+    if let Some(object) = rest.as_object_mut() {
+        object.remove("paths");
+        if let Some(components) = object.get_mut("components").and_then(Value::as_object_mut) {
+            components.remove("schemas");
+            if components.is_empty() {
+                object.remove("components");
+            }
+        }
+    }
+    rest
+}
+
+impl fmt::Display for SpecDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (title, items) in [("Paths", &self.operations), ("Schemas", &self.schemas)] {
+            if items.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", title)?;
+            for item in items {
+                match &item.change {
+                    ItemChange::Added => writeln!(f, "  + {}", item.name)?,
+                    ItemChange::Removed => writeln!(f, "  - {}", item.name)?,
+                    ItemChange::Changed(changes) => {
+                        writeln!(f, "  ~ {}", item.name)?;
+                        for change in changes {
+                            writeln!(f, "      {}", change)?;
+                        }
+                    }
+                }
+            }
+        }
+        if !self.document.is_empty() {
+            writeln!(f, "Document:")?;
+            for change in &self.document {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (None, Some(new)) => write!(f, "+ {}: {}", self.path, new),
+            (Some(old), None) => write!(f, "- {}: {}", self.path, old),
+            (Some(old), Some(new)) => write!(f, "~ {}: {} -> {}", self.path, old, new),
+            (None, None) => write!(f, "~ {}", self.path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_order_is_ignored() {
+        let old: Value = serde_json::from_str(r#"{"openapi": "3.0.0", "info": {"title": "A", "version": "1"}}"#).unwrap();
+        let new: Value = serde_json::from_str(r#"{"info": {"version": "1", "title": "A"},
+            "openapi": "3.0.0"}"#).unwrap();
+        assert!(diff_specs(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_operation_and_schema_changes() {
+        let old = json!({
+            "openapi": "3.0.0",
+            "info": {"title": "A", "version": "1"},
+            "paths": {
+                "/users": {"get": {"operationId": "list"}},
+                "/users/:id": {"get": {"operationId": "get"}, "delete": {"operationId": "delete"}}
+            },
+            "components": {"schemas": {
+                "User": {"type": "object", "properties": {"id": {"type": "integer"}}},
+                "Legacy": {"type": "object"}
+            }}
+        });
+        let new = json!({
+            "openapi": "3.0.0",
+            "info": {"title": "A", "version": "2"},
+            "paths": {
+                "/users": {"get": {"operationId": "list"}, "post": {"operationId": "create"}},
+                "/users/:id": {"get": {"operationId": "get_user"}}
+            },
+            "components": {"schemas": {
+                "User": {"type": "object", "properties": {"id": {"type": "string"}, "name": {"type": "string"}}}
+            }}
+        });
+
+        let diff = diff_specs(&old, &new);
+        assert_eq!(diff.operations, vec![
+            ItemDiff { name: "POST /users".to_string(), change: ItemChange::Added },
+            ItemDiff { name: "DELETE /users/:id".to_string(), change: ItemChange::Removed },
+            ItemDiff {
+                name: "GET /users/:id".to_string(),
+                change: ItemChange::Changed(vec![FieldChange {
+                    path: "operationId".to_string(),
+                    old: Some(json!("get")),
+                    new: Some(json!("get_user")),
+                }]),
+            },
+        ]);
+        assert_eq!(diff.schemas[0], ItemDiff { name: "Legacy".to_string(), change: ItemChange::Removed });
+        assert_eq!(diff.document.len(), 1);
+
+        let rendered = diff.to_string();
+        assert!(rendered.contains("  + POST /users\n"));
+        assert!(rendered.contains("      ~ properties.id.type: \"integer\" -> \"string\"\n"));
+        assert!(rendered.contains("      + properties.name: {\"type\":\"string\"}\n"));
+        assert!(rendered.contains("Document:\n  ~ info.version: \"1\" -> \"2\"\n"));
+    }
+}
@@ -0,0 +1,234 @@
+//! Error types: the responses an `impl IntoResponse for X` produces, attached
+//! to every handler that returns `Result<_, X>`.
+
+use std::collections::HashMap;
+use quote::ToTokens;
+use syn::{parse_file, visit::{self, Visit}, Expr, ImplItem, Item, Lit, Type};
+
+use crate::status::status_from_expr;
+
+/// A response produced by an error type, as found in its `IntoResponse` impl.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorResponse {
+    pub status: u16,
+    pub body: ResponseBody,
+}
+
+/// What an error response carries.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseBody {
+    None,
+    /// `Json(T { .. })` or `Json(T::new(..))`: the type string of `T`
+    Json(String),
+    /// `Json(json!(..))` or any other JSON value of unknown shape
+    JsonValue,
+    /// A string body such as `(StatusCode::NOT_FOUND, "not found")`
+    Text,
+}
+
+/// Parses every `impl IntoResponse for X` in `file_content` into the
+/// responses each one produces, keyed by the name of `X`.
+pub fn parse_error_responses(file_content: &str) -> HashMap<String, Vec<ErrorResponse>> {
+    let ast = match parse_file(file_content) {
+        Ok(ast) => ast,
+        Err(e) => {
+            eprintln!("Warning: Failed to parse error type file: {}", e);
+            return HashMap::new();
+        }
+    };
+    parse_error_items(&ast.items)
+        .into_iter()
+        .map(|(self_ty, responses)| (crate::schema::schema_name(&self_ty.to_token_stream().to_string()), responses))
+        .collect()
+}
+
+/// Collects the self type and responses of every `impl IntoResponse for X`
+/// among `items`.
+///
+/// Each `match` arm of `into_response` that names a `StatusCode` becomes one
+/// response per code, with the arm's JSON body or else the body built after
+/// the `match` (the usual `let (status, message) = match self { .. }` shape).
+/// Codes outside any arm use the outer body.
+pub(crate) fn parse_error_items(items: &[Item]) -> Vec<(Type, Vec<ErrorResponse>)> {
+    let mut found = Vec::new();
+    for item in items {
+        let Item::Impl(item_impl) = item else { continue };
+        let is_into_response = item_impl.trait_.as_ref()
+            .and_then(|(_, path, _)| path.segments.last())
+            .is_some_and(|segment| segment.ident == "IntoResponse");
+        if !is_into_response {
+            continue;
+        }
+
+        for impl_item in &item_impl.items {
+            let ImplItem::Fn(method) = impl_item else { continue };
+            if method.sig.ident != "into_response" {
+                continue;
+            }
+            let mut collector = ResponseCollector { arms: Some(Vec::new()), ..Default::default() };
+            collector.visit_block(&method.block);
+            found.push(((*item_impl.self_ty).clone(), collector.responses()));
+        }
+    }
+    found
+}
+
+// Status codes and body found in an expression. At the top level the arms of
+// `match` expressions are collected separately.
+#[derive(Default)]
+struct ResponseCollector {
+    statuses: Vec<u16>,
+    body: Option<ResponseBody>,
+    arms: Option<Vec<ResponseCollector>>,
+}
+
+impl ResponseCollector {
+    fn responses(self) -> Vec<ErrorResponse> {
+        let outer_body = self.body.unwrap_or(ResponseBody::None);
+        let mut responses: Vec<ErrorResponse> = Vec::new();
+        let mut push = |status: u16, body: ResponseBody| {
+            if !responses.iter().any(|r| r.status == status) {
+                responses.push(ErrorResponse { status, body });
+            }
+        };
+
+        for arm in self.arms.unwrap_or_default() {
+            // JSON 响应体优先；match 分支中的字符串通常只是后面 Json(..) 的参数
+            let body = match arm.body {
+                Some(body @ (ResponseBody::Json(_) | ResponseBody::JsonValue)) => body,
+                Some(body) if outer_body == ResponseBody::None => body,
+                _ => outer_body.clone(),
+            };
+            for status in arm.statuses {
+                push(status, body.clone());
+            }
+        }
+        for status in self.statuses {
+            push(status, outer_body.clone());
+        }
+        responses
+    }
+}
+
+impl<'ast> Visit<'ast> for ResponseCollector {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Some(code) = status_from_expr(expr) {
+            if !self.statuses.contains(&code) {
+                self.statuses.push(code);
+            }
+            return;
+        }
+        if self.body.is_none() {
+            self.body = body_of(expr);
+        }
+        visit::visit_expr(self, expr);
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        match &mut self.arms {
+            Some(arms) => {
+                let mut collector = ResponseCollector::default();
+                collector.visit_expr(&arm.body);
+                arms.push(collector);
+            }
+            None => visit::visit_arm(self, arm),
+        }
+    }
+
+    // Nested items are not part of `into_response`
+    fn visit_item(&mut self, _item: &'ast Item) {}
+}
+
+fn body_of(expr: &Expr) -> Option<ResponseBody> {
+    match expr {
+        Expr::Call(call) if call.args.len() == 1 && is_path_to(&call.func, "Json") => Some(json_body(&call.args[0])),
+        Expr::Tuple(tuple) if tuple.elems.last().is_some_and(is_text) => Some(ResponseBody::Text),
+        _ => None,
+    }
+}
+
+// Type of the value passed to `Json(..)`, when it is spelled out
+fn json_body(expr: &Expr) -> ResponseBody {
+    match expr {
+        Expr::Struct(expr_struct) => ResponseBody::Json(expr_struct.path.to_token_stream().to_string()),
+        Expr::Reference(reference) => json_body(&reference.expr),
+        Expr::Call(call) => match &*call.func {
+            // ErrorBody::new(..)
+            Expr::Path(func) if func.path.segments.len() >= 2 => {
+                let mut ty = func.path.clone();
+                ty.segments.pop();
+                ty.segments.pop_punct();
+                ResponseBody::Json(ty.to_token_stream().to_string())
+            }
+            _ => ResponseBody::JsonValue,
+        },
+        _ => ResponseBody::JsonValue,
+    }
+}
+
+fn is_text(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(lit) => matches!(lit.lit, Lit::Str(_)),
+        Expr::Macro(mac) => mac.mac.path.is_ident("format"),
+        Expr::MethodCall(call) => call.method == "to_string" || call.method == "to_owned",
+        Expr::Call(call) => matches!(&*call.func, Expr::Path(func) if func.path.to_token_stream().to_string() == "String :: from"),
+        Expr::Reference(reference) => is_text(&reference.expr),
+        _ => false,
+    }
+}
+
+fn is_path_to(expr: &Expr, name: &str) -> bool {
+    matches!(expr, Expr::Path(path) if path.path.segments.last().is_some_and(|segment| segment.ident == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_arms_with_shared_body() {
+        let registry = parse_error_responses(r#"
+            impl IntoResponse for AppError {
+                fn into_response(self) -> Response {
+                    let (status, message) = match self {
+                        AppError::NotFound => (StatusCode::NOT_FOUND, "not found"),
+                        AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "unauthorized"),
+                        AppError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal"),
+                    };
+                    (status, Json(ErrorBody { message: message.to_string() })).into_response()
+                }
+            }
+        "#);
+        let responses = &registry["AppError"];
+        let statuses: Vec<u16> = responses.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, vec![404, 401, 500]);
+        assert!(responses.iter().all(|r| r.body == ResponseBody::Json("ErrorBody".to_string())));
+    }
+
+    #[test]
+    fn test_match_arms_with_own_bodies() {
+        let registry = parse_error_responses(r#"
+            impl axum::response::IntoResponse for ApiError {
+                fn into_response(self) -> axum::response::Response {
+                    match self {
+                        ApiError::Validation(errors) => {
+                            (StatusCode::UNPROCESSABLE_ENTITY, Json(ValidationErrors::from(errors))).into_response()
+                        }
+                        ApiError::Conflict => (StatusCode::CONFLICT, Json(json!({"error": "conflict"}))).into_response(),
+                        ApiError::Gone => (StatusCode::GONE, format!("gone")).into_response(),
+                        ApiError::Other(inner) => inner.into_response(),
+                    }
+                }
+            }
+            impl Display for ApiError {
+                fn fmt(&self, f: &mut Formatter) -> fmt::Result { Ok(()) }
+            }
+        "#);
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry["ApiError"], vec![
+            ErrorResponse { status: 422, body: ResponseBody::Json("ValidationErrors".to_string()) },
+            ErrorResponse { status: 409, body: ResponseBody::JsonValue },
+            ErrorResponse { status: 410, body: ResponseBody::Text },
+        ]);
+    }
+}
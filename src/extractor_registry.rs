@@ -0,0 +1,436 @@
+//! Custom extractors: what the `FromRequest`/`FromRequestParts` impl of a type
+//! such as `ValidatedJson<T>` or `AuthUser` reads from the request, so
+//! handlers taking it document the input underneath.
+
+use std::collections::HashMap;
+use quote::ToTokens;
+use syn::{visit::{self, Visit}, visit_mut::VisitMut, Expr, GenericArgument, ImplItem, Item, Lit, PathArguments, Type};
+
+use crate::schema::path_name;
+
+// Upper bound when following extractors that run other custom extractors
+const MAX_DELEGATION_DEPTH: usize = 8;
+
+// Built-in extractors a custom extractor can delegate to
+const BUILTIN_INPUTS: [&str; 4] = ["Json", "Query", "Path", "Form"];
+
+/// A request input a custom extractor reads.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtractorInput {
+    /// A built-in extractor (`Json`, `Query`, `Path` or `Form`) and the type
+    /// string it extracts, which may name a type parameter of the custom
+    /// extractor. `None` stands for the extractor's first type argument.
+    Builtin { kind: String, ty: Option<String> },
+    /// A request header, by lowercase name
+    Header(String),
+    /// A bearer token from the `Authorization` header
+    Bearer,
+    /// Another custom extractor it runs first, by type name
+    Custom(String),
+}
+
+/// What one custom extractor reads.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomExtractor {
+    /// Type parameters of the implementing type, e.g. `["T"]` for `ValidatedJson<T>`
+    pub generics: Vec<String>,
+    pub inputs: Vec<ExtractorInput>,
+}
+
+/// Collects the self type and inputs of every `impl FromRequest<S> for X`
+/// and `impl FromRequestParts<S> for X` among `items`.
+///
+/// The body of `from_request`/`from_request_parts` is searched for the
+/// extractors it runs (`Json::<T>::from_request(..)`,
+/// `req.extract::<Query<T>>()`, `let Json(value): Json<T> = req.extract()..`,
+/// including other custom extractors), bearer tokens read through
+/// `TypedHeader<Authorization<Bearer>>` and literal header lookups
+/// (`parts.headers.get("x-api-key")`). Types and strings mentioned anywhere
+/// else, such as in the rejection path, are not inputs.
+pub(crate) fn parse_extractor_items(items: &[Item]) -> Vec<(Type, CustomExtractor)> {
+    let mut found = Vec::new();
+    for item in items {
+        let Item::Impl(item_impl) = item else { continue };
+        let is_extractor = item_impl.trait_.as_ref()
+            .and_then(|(_, path, _)| path.segments.last())
+            .is_some_and(|segment| segment.ident == "FromRequest" || segment.ident == "FromRequestParts");
+        if !is_extractor {
+            continue;
+        }
+
+        let generics = match &*item_impl.self_ty {
+            Type::Path(type_path) => type_arguments(type_path.path.segments.last())
+                .iter()
+                .map(|arg| arg.to_token_stream().to_string())
+                .collect(),
+            _ => Vec::new(),
+        };
+        for impl_item in &item_impl.items {
+            let ImplItem::Fn(method) = impl_item else { continue };
+            if method.sig.ident != "from_request" && method.sig.ident != "from_request_parts" {
+                continue;
+            }
+            let mut collector = InputCollector::default();
+            collector.visit_block(&method.block);
+            found.push(((*item_impl.self_ty).clone(), CustomExtractor { generics: generics.clone(), inputs: collector.inputs() }));
+        }
+    }
+    found
+}
+
+/// Inputs a handler argument of type `ty` reads through the custom extractor
+/// it names, with the extractor's type parameters replaced by `ty`'s type
+/// arguments and extractors it runs expanded. `None` if `ty` names no
+/// extractor in `registry`.
+pub fn resolve_custom_extractor(ty: &Type, registry: &HashMap<String, CustomExtractor>) -> Option<Vec<ExtractorInput>> {
+    resolve(ty, registry, 0)
+}
+
+fn resolve(ty: &Type, registry: &HashMap<String, CustomExtractor>, depth: usize) -> Option<Vec<ExtractorInput>> {
+    let Type::Path(type_path) = ty else { return None };
+    let segment = type_path.path.segments.last()?;
+    let extractor = registry.get(&path_name(&type_path.path))?;
+    let arguments = type_arguments(Some(segment));
+
+    let mut inputs = Vec::new();
+    for input in &extractor.inputs {
+        match input {
+            ExtractorInput::Builtin { kind, ty } => {
+                let ty = match ty {
+                    Some(ty) => syn::parse_str::<Type>(ty).ok().map(|ty| substitute(&ty, &extractor.generics, &arguments)),
+                    None => arguments.first().map(|arg| (*arg).clone()),
+                };
+                inputs.push(ExtractorInput::Builtin { kind: kind.clone(), ty: ty.map(|ty| ty.to_token_stream().to_string()) });
+            }
+            ExtractorInput::Custom(name) if depth < MAX_DELEGATION_DEPTH => {
+                let inner: Type = syn::parse_str(name).ok()?;
+                inputs.extend(resolve(&inner, registry, depth + 1).unwrap_or_default());
+            }
+            ExtractorInput::Custom(_) => {}
+            other => inputs.push(other.clone()),
+        }
+    }
+    Some(inputs)
+}
+
+fn type_arguments(segment: Option<&syn::PathSegment>) -> Vec<&Type> {
+    match segment.map(|segment| &segment.arguments) {
+        Some(PathArguments::AngleBracketed(args)) => args.args.iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Replaces the type parameters `generics` in `ty` by `arguments`
+fn substitute(ty: &Type, generics: &[String], arguments: &[&Type]) -> Type {
+    struct Substitute<'a> {
+        generics: &'a [String],
+        arguments: &'a [&'a Type],
+    }
+
+    impl VisitMut for Substitute<'_> {
+        fn visit_type_mut(&mut self, ty: &mut Type) {
+            if let Type::Path(type_path) = ty {
+                if let Some(ident) = type_path.path.get_ident() {
+                    let position = self.generics.iter().position(|generic| ident == generic);
+                    if let Some(argument) = position.and_then(|i| self.arguments.get(i)) {
+                        *ty = (*argument).clone();
+                        return;
+                    }
+                }
+            }
+            syn::visit_mut::visit_type_mut(self, ty);
+        }
+    }
+
+    let mut ty = ty.clone();
+    Substitute { generics, arguments }.visit_type_mut(&mut ty);
+    ty
+}
+
+// Inputs found in an extractor body, in source order
+#[derive(Default)]
+struct InputCollector {
+    builtin: Option<(String, Option<String>)>,
+    headers: Vec<String>,
+    bearer: bool,
+    custom: Vec<String>,
+}
+
+impl InputCollector {
+    fn inputs(self) -> Vec<ExtractorInput> {
+        let mut inputs = Vec::new();
+        if let Some((kind, ty)) = self.builtin {
+            inputs.push(ExtractorInput::Builtin { kind, ty });
+        }
+        for header in self.headers {
+            // The bearer token already documents the Authorization header
+            if !(self.bearer && header == "authorization") {
+                inputs.push(ExtractorInput::Header(header));
+            }
+        }
+        if self.bearer {
+            inputs.push(ExtractorInput::Bearer);
+        }
+        inputs.extend(self.custom.into_iter().map(ExtractorInput::Custom));
+        inputs
+    }
+}
+
+impl InputCollector {
+    // Records what running the extractor `ty` reads: `Json<T>`,
+    // `TypedHeader<Authorization<Bearer>>` or another custom extractor
+    fn extractor(&mut self, ty: &Type) {
+        let Type::Path(type_path) = ty else { return };
+        let Some(segment) = type_path.path.segments.last() else { return };
+        let ident = segment.ident.to_string();
+        let first = type_arguments(Some(segment)).first().copied();
+        match (ident.as_str(), first) {
+            ("Option" | "Result", Some(inner)) => self.extractor(inner),
+            (kind, _) if BUILTIN_INPUTS.contains(&kind) => {
+                let ty = first.map(|ty| ty.to_token_stream().to_string());
+                match &mut self.builtin {
+                    None => self.builtin = Some((ident, ty)),
+                    Some((kind, existing @ None)) if *kind == ident => *existing = ty,
+                    _ => {}
+                }
+            }
+            ("TypedHeader", Some(header)) => {
+                if is_bearer_authorization(header) {
+                    self.bearer = true;
+                }
+            }
+            ("Self", _) => {}
+            _ => {
+                if !self.custom.contains(&ident) {
+                    self.custom.push(ident);
+                }
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for InputCollector {
+    // `Json::<T>::from_request(req, state)`, `Claims::from_request_parts(..)`
+    // or `<Json<T> as FromRequest<S>>::from_request(..)`
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let Expr::Path(func) = &*call.func {
+            let segments = &func.path.segments;
+            let names_method = segments.last()
+                .is_some_and(|segment| segment.ident == "from_request" || segment.ident == "from_request_parts");
+            if names_method {
+                match &func.qself {
+                    Some(qself) => self.extractor(&qself.ty),
+                    None if segments.len() >= 2 => {
+                        let mut owner = func.path.clone();
+                        owner.segments.pop();
+                        owner.segments.pop_punct();
+                        self.extractor(&Type::Path(syn::TypePath { qself: None, path: owner }));
+                    }
+                    None => {}
+                }
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        // `parts.headers.get("x-api-key")` or `req.headers().get(AUTHORIZATION)`
+        if call.method == "get" && is_headers(&call.receiver) {
+            if let Some(name) = call.args.first().and_then(header_name) {
+                if !self.headers.contains(&name) {
+                    self.headers.push(name);
+                }
+            }
+        }
+
+        // `parts.extract::<AuthUser>()` runs another extractor
+        if is_extract(call) {
+            if let Some(GenericArgument::Type(ty)) = call.turbofish.as_ref().and_then(|turbofish| turbofish.args.first()) {
+                self.extractor(ty);
+            }
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    // `let Json(value): Json<T> = req.extract().await?` names the extractor
+    // in the binding's type instead of a turbofish
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let (syn::Pat::Type(pat_type), Some(init)) = (&local.pat, &local.init) {
+            if untyped_extract(&init.expr) {
+                self.extractor(&pat_type.ty);
+            }
+        }
+        visit::visit_local(self, local);
+    }
+
+    // Nested items are not part of the extractor body
+    fn visit_item(&mut self, _item: &'ast Item) {}
+}
+
+fn is_extract(call: &syn::ExprMethodCall) -> bool {
+    call.method == "extract" || call.method == "extract_with_state"
+}
+
+// Whether `expr` is an `extract()` call without a turbofish, possibly
+// awaited, `?`-ed or followed by `map_err(..)` and the like
+fn untyped_extract(expr: &Expr) -> bool {
+    match expr {
+        Expr::Try(try_expr) => untyped_extract(&try_expr.expr),
+        Expr::Await(await_expr) => untyped_extract(&await_expr.base),
+        Expr::Paren(paren) => untyped_extract(&paren.expr),
+        Expr::MethodCall(call) if is_extract(call) => call.turbofish.is_none(),
+        Expr::MethodCall(call) => untyped_extract(&call.receiver),
+        _ => false,
+    }
+}
+
+// `Authorization<Bearer>`
+fn is_bearer_authorization(header: &Type) -> bool {
+    let Type::Path(type_path) = header else { return false };
+    let Some(segment) = type_path.path.segments.last() else { return false };
+    segment.ident == "Authorization"
+        && matches!(type_arguments(Some(segment)).first(),
+            Some(Type::Path(scheme)) if scheme.path.segments.last().is_some_and(|s| s.ident == "Bearer"))
+}
+
+fn is_headers(expr: &Expr) -> bool {
+    match expr {
+        Expr::Field(field) => matches!(&field.member, syn::Member::Named(ident) if ident == "headers"),
+        Expr::MethodCall(call) => call.method == "headers",
+        Expr::Reference(reference) => is_headers(&reference.expr),
+        _ => false,
+    }
+}
+
+// `"X-Api-Key"` -> `x-api-key`, `header::AUTHORIZATION` -> `authorization`
+pub(crate) fn header_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => Some(s.value().to_lowercase()),
+            _ => None,
+        },
+        Expr::Path(path) => {
+            let ident = path.path.segments.last()?.ident.to_string();
+            let is_const = ident.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+            is_const.then(|| ident.to_lowercase().replace('_', "-"))
+        }
+        Expr::Reference(reference) => header_name(&reference.expr),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(code: &str) -> HashMap<String, CustomExtractor> {
+        let ast = syn::parse_file(code).unwrap();
+        parse_extractor_items(&ast.items).into_iter()
+            .map(|(self_ty, extractor)| match self_ty {
+                Type::Path(type_path) => (type_path.path.segments.last().unwrap().ident.to_string(), extractor),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delegating_extractors() {
+        let registry = registry(r#"
+            pub struct ValidatedJson<T>(pub T);
+
+            #[async_trait]
+            impl<T, S> FromRequest<S> for ValidatedJson<T>
+            where T: DeserializeOwned + Validate, S: Send + Sync {
+                type Rejection = ApiError;
+
+                async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+                    let Json(value) = Json::<T>::from_request(req, state).await?;
+                    value.validate()?;
+                    Ok(ValidatedJson(value))
+                }
+            }
+
+            impl<S: Send + Sync> FromRequestParts<S> for Claims {
+                type Rejection = ApiError;
+
+                async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+                    let TypedHeader(Authorization(bearer)) = parts
+                        .extract::<TypedHeader<Authorization<Bearer>>>()
+                        .await?;
+                    decode(bearer.token())
+                }
+            }
+
+            impl<S: Send + Sync> FromRequestParts<S> for AuthUser {
+                type Rejection = ApiError;
+
+                async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+                    let tenant = parts.headers.get("X-Tenant-Id").ok_or(ApiError::NoTenant)?;
+                    let claims = Claims::from_request_parts(parts, state).await?;
+                    Ok(AuthUser { claims, tenant })
+                }
+            }
+        "#);
+
+        let inputs = resolve_custom_extractor(&syn::parse_quote!(ValidatedJson<CreateUser>), &registry);
+        assert_eq!(inputs, Some(vec![ExtractorInput::Builtin { kind: "Json".into(), ty: Some("CreateUser".into()) }]));
+
+        let inputs = resolve_custom_extractor(&syn::parse_quote!(AuthUser), &registry);
+        assert_eq!(inputs, Some(vec![ExtractorInput::Header("x-tenant-id".into()), ExtractorInput::Bearer]));
+
+        assert_eq!(resolve_custom_extractor(&syn::parse_quote!(Unknown), &registry), None);
+    }
+
+    #[test]
+    fn test_only_extractor_calls_are_inputs() {
+        let registry = registry(r#"
+            impl<S: Send + Sync> FromRequestParts<S> for ApiKey {
+                type Rejection = Response;
+
+                async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+                    let key = parts.headers.get("x-api-key").ok_or_else(|| {
+                        let body = Json(ErrorBody { message: "Bearer tokens are not accepted here".into() });
+                        (StatusCode::UNAUTHORIZED, body).into_response()
+                    })?;
+                    Ok(ApiKey(key.to_str().unwrap().to_string()))
+                }
+            }
+
+            impl<S: Send + Sync> FromRequest<S> for Signup {
+                type Rejection = Response;
+
+                async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+                    let Form(signup): Form<NewUser> = req.extract().await.map_err(IntoResponse::into_response)?;
+                    Ok(Signup(signup))
+                }
+            }
+
+            impl<S: Send + Sync> FromRequestParts<S> for Session {
+                type Rejection = Response;
+
+                async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+                    let auth = parts.extract::<Option<TypedHeader<Authorization<Bearer>>>>().await?;
+                    let page = <Query<Paging> as FromRequestParts<S>>::from_request_parts(parts, state).await?;
+                    Ok(Session { auth, page })
+                }
+            }
+        "#);
+
+        let inputs = resolve_custom_extractor(&syn::parse_quote!(ApiKey), &registry);
+        assert_eq!(inputs, Some(vec![ExtractorInput::Header("x-api-key".into())]));
+
+        let inputs = resolve_custom_extractor(&syn::parse_quote!(Signup), &registry);
+        assert_eq!(inputs, Some(vec![ExtractorInput::Builtin { kind: "Form".into(), ty: Some("NewUser".into()) }]));
+
+        let inputs = resolve_custom_extractor(&syn::parse_quote!(Session), &registry);
+        assert_eq!(inputs, Some(vec![
+            ExtractorInput::Builtin { kind: "Query".into(), ty: Some("Paging".into()) },
+            ExtractorInput::Bearer,
+        ]));
+    }
+}
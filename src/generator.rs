@@ -0,0 +1,434 @@
+//! Builder-style entry point tying router discovery, handler parsing and
+//! model parsing together.
+
+use std::{collections::{HashMap, HashSet}, error::Error, path::PathBuf, rc::Rc};
+use once_cell::sync::Lazy;
+use quote::ToTokens;
+use regex::Regex;
+use syn::visit::Visit;
+
+use crate::config::{Config, CONFIG_FILE};
+use crate::crate_index::CrateIndex;
+use crate::error_registry::{ErrorResponse, ResponseBody};
+use crate::extractor_registry::{resolve_custom_extractor, CustomExtractor, ExtractorInput};
+use crate::handler::{parse_handler_items, push_param, Extractor, HandlerInfo};
+use crate::models::{parse_model_items, StructInfo};
+use crate::openapi::{generate_openapi, split_result, OpenApi};
+use crate::router::RouterVisitor;
+use crate::schema::{path_name, schema_name};
+use crate::sources::{SourceCache, SourceError};
+use crate::version::SpecVersion;
+
+// Identifiers, and the `crate::` paths of clashing type names as a whole
+static IDENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"crate\s*::\s*(?:[A-Za-z_][A-Za-z0-9_]*\s*::\s*)*[A-Za-z_][A-Za-z0-9_]*|[A-Za-z_][A-Za-z0-9_]*"#).unwrap()
+});
+
+/// Generates an OpenAPI document for an Axum project.
+///
+/// ```no_run
+/// let spec = axum_doc::Generator::new(".")
+///     .entry_file("src/main.rs")
+///     .model_files(["src/form.rs", "src/response.rs"])
+///     .generate()?;
+/// println!("{}", spec.to_json_pretty()?);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Generator {
+    base_dir: PathBuf,
+    entry_file: PathBuf,
+    model_files: Vec<PathBuf>,
+    config: Config,
+}
+
+impl Generator {
+    /// Creates a generator for the project rooted at `base_dir`, using
+    /// `src/main.rs` as the entry file and no model files.
+    pub fn new(base_dir: impl Into<PathBuf>) -> Self {
+        Generator {
+            base_dir: base_dir.into(),
+            entry_file: PathBuf::from("src/main.rs"),
+            model_files: Vec::new(),
+            config: Config::default(),
+        }
+    }
+
+    /// Applies a project configuration: its entry file and model files are
+    /// used as if set through the builder, and its info, servers, tags and
+    /// type overrides are applied to the generated document.
+    ///
+    /// ```no_run
+    /// use axum_doc::{Config, Generator};
+    ///
+    /// let config = Config::load(".".as_ref())?;
+    /// let spec = Generator::new(".").config(config).generate()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn config(mut self, config: Config) -> Self {
+        if let Some(entry_file) = &config.entry_file {
+            self.entry_file = entry_file.clone();
+        }
+        self.model_files.extend(config.model_files.iter().cloned());
+        self.config = config;
+        self
+    }
+
+    /// Sets the file holding the top-level router, relative to the base directory.
+    pub fn entry_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.entry_file = path.into();
+        self
+    }
+
+    /// Adds a model source file, relative to the base directory.
+    ///
+    /// Models reachable through `mod` declarations from the crate root are
+    /// discovered automatically; explicit model files are only needed for
+    /// sources outside the module tree, and every struct in them is emitted
+    /// whether or not a handler references it.
+    pub fn model_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.model_files.push(path.into());
+        self
+    }
+
+    /// Adds several model source files, relative to the base directory.
+    pub fn model_files<I, P>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.model_files.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the OpenAPI version of the generated document (3.0 by default).
+    pub fn spec_version(mut self, version: SpecVersion) -> Self {
+        self.config.output.spec_version = Some(version);
+        self
+    }
+
+    /// Parses the project and builds the OpenAPI document.
+    ///
+    /// Models are collected from every module reachable from the crate root
+    /// and entry file; only those referenced by an operation (directly or
+    /// through other models) end up in `components.schemas`, plus everything
+    /// from explicit model files.
+    ///
+    /// Missing model files and unresolved handlers are reported as warnings on
+    /// stderr; only a missing base directory or entry file, or an entry file
+    /// that fails to parse, is an error.
+    pub fn generate(&self) -> Result<OpenApi, Box<dyn Error>> {
+        self.generate_with(&SourceCache::new())
+    }
+
+    /// Like [`Generator::generate`], reading source files through `sources`.
+    /// Files whose content is unchanged since an earlier generation with the
+    /// same cache are not parsed again, and [`SourceCache::reads`] lists
+    /// every file the document was built from.
+    pub fn generate_with(&self, sources: &SourceCache) -> Result<OpenApi, Box<dyn Error>> {
+        let base_path = self.base_dir.as_path();
+        if !base_path.exists() {
+            return Err(format!("Base directory does not exist: {}", base_path.display()).into());
+        }
+
+        let handler_path = base_path.join(&self.entry_file);
+        if !handler_path.exists() {
+            return Err(format!("Handler file does not exist: {}", handler_path.display()).into());
+        }
+
+        // 1. 解析路由文件
+        let router_ast = sources.parse(&handler_path)?;
+
+        let index = Rc::new(CrateIndex::build_with(base_path, &handler_path, sources)?);
+
+        let mut visitor = RouterVisitor::new(base_path, &handler_path)
+            .with_sources(sources)
+            .with_index(index.clone());
+        visitor.visit_file(&router_ast);
+
+        // 2. 解析处理器函数
+        let mut handlers = HashMap::new();
+
+        for route in visitor.routes.iter().filter(|route| !route.service) {
+            let handler_id = route.handler_id();
+            if handlers.contains_key(&handler_id) {
+                continue;
+            }
+
+            // Handlers resolved through imports are parsed from their defining module
+            let resolved = route.handler_path.as_ref().and_then(|path| index.function(path));
+            if let Some((module, func)) = resolved {
+                // 参数类型先经模块导入规范化，别名导入的提取器（如 AxumPath）才能按原名识别
+                let mut func = func.clone();
+                for input in &mut func.sig.inputs {
+                    if let syn::FnArg::Typed(pat_type) = input {
+                        *pat_type.ty = index.normalize_type(&module.path, &pat_type.ty);
+                    }
+                }
+                let name = func.sig.ident.to_string();
+                if let Some(mut handler) = parse_handler_items(&[syn::Item::Fn(func)], &name) {
+                    normalize_handler(&index, &module.path, &mut handler);
+                    handlers.insert(handler_id, handler);
+                    continue;
+                }
+            }
+
+            // 未能通过模块树解析的 handler 已由 RouterVisitor 报告
+            if let Some(path) = &route.handler_path {
+                eprintln!("Warning: Handler '{}' has no parseable definition, skipping", path.join("::"));
+            }
+        }
+
+        // 自定义提取器按其 FromRequest/FromRequestParts 实现读取的输入文档化
+        let mut extractors = index.custom_extractors();
+        let mut configured = HashSet::new();
+        for (name, entry) in &self.config.extractors {
+            let extractor = entry.to_extractor()
+                .map_err(|e| format!("Invalid [extractors] entry '{}': {}", name, e))?;
+            // 以路径配置的提取器按模块树解析，与 custom_extractors 的键一致
+            let segments: Vec<String> = name.split("::").map(str::to_string).collect();
+            let name = match index.resolve_path(&[], &segments) {
+                Some(path) => index.model_name(&path),
+                None => segments.last().cloned().unwrap_or_default(),
+            };
+            configured.insert(name.clone());
+            extractors.insert(name, extractor);
+        }
+        for handler in handlers.values_mut() {
+            apply_custom_extractors(&extractors, &configured, handler)?;
+        }
+
+        let mut handler_ids: Vec<&String> = handlers.keys().collect();
+        handler_ids.sort();
+        for id in handler_ids {
+            for ty in &handlers[id].unknown_extractors {
+                eprintln!("Warning: Handler '{}' takes unrecognized extractor `{}`, leaving it undocumented; \
+                           map it under [extractors] in {} to document it",
+                         id, ty.to_token_stream(), CONFIG_FILE);
+            }
+        }
+
+        // 为返回 Result<_, E> 的 handler 附加 E 的 IntoResponse 错误响应
+        let error_registry = index.error_responses();
+        for handler in handlers.values_mut() {
+            attach_error_responses(&error_registry, handler);
+        }
+
+        // 3. 解析模型：先从模块树收集，再合并显式指定的模型文件
+        let mut all_models = index.models();
+        let mut explicit_models = HashSet::new();
+        for file in &self.model_files {
+            let path = base_path.join(file);
+            if path.exists() {
+                let models = match sources.parse(&path) {
+                    Ok(ast) => parse_model_items(&ast.items),
+                    Err(SourceError::Parse(e)) => {
+                        eprintln!("Warning: Failed to parse model file: {}", e);
+                        HashMap::new()
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                match index.module_for_file(&path) {
+                    // 模块树中的文件已被索引，按索引中的名称保留
+                    Some(module) => explicit_models.extend(models.keys()
+                        .map(|name| index.model_name(&[module.path.as_slice(), std::slice::from_ref(name)].concat()))),
+                    None => {
+                        explicit_models.extend(models.keys().cloned());
+                        all_models.extend(models);
+                    }
+                }
+            } else {
+                eprintln!("Warning: Model file not found: {}. Use --model-files to specify correct paths, or skip if models are defined inline in handlers.", path.display());
+            }
+        }
+
+        // 配置中的类型覆盖优先于解析出的模型
+        for (type_name, schema) in &self.config.types {
+            let name = schema_name(type_name);
+            all_models.insert(name.clone(), StructInfo::from_schema(&name, schema.clone()));
+        }
+
+        // 4. 生成OpenAPI，只保留被引用的模块树模型
+        let models = referenced_models(&handlers, all_models, &explicit_models);
+        let mut spec = generate_openapi(&visitor.routes, &handlers, &models);
+        self.config.apply(&mut spec);
+        spec.convert_to(self.config.output.spec_version.unwrap_or_default());
+        Ok(spec)
+    }
+}
+
+// Rewrites the handler's types so imported aliases and qualified paths match model names
+fn normalize_handler(index: &CrateIndex, module: &[String], handler: &mut HandlerInfo) {
+    for param in &mut handler.params {
+        param.inner_type = index.normalize_type(module, &param.inner_type);
+    }
+    if let Some(return_type) = &handler.return_type {
+        handler.return_type = Some(index.normalize_type(module, return_type));
+    }
+    for ty in &mut handler.unknown_extractors {
+        *ty = index.normalize_type(module, ty);
+    }
+    for response in &mut handler.overrides.responses {
+        if let Some(body) = &mut response.body {
+            if let Ok(ty) = syn::parse_str::<syn::Type>(body) {
+                *body = index.normalize_type(module, &ty).to_token_stream().to_string();
+            }
+        }
+    }
+}
+
+// Replaces the handler's custom extractors by the inputs they read; those
+// that are not in `registry` stay unknown. A `configured` extractor whose
+// body type would come from a type argument the handler's type lacks is a
+// configuration error.
+fn apply_custom_extractors(
+    registry: &HashMap<String, CustomExtractor>,
+    configured: &HashSet<String>,
+    handler: &mut HandlerInfo,
+) -> Result<(), String> {
+    let mut unknown = Vec::new();
+    for ty in std::mem::take(&mut handler.unknown_extractors) {
+        let (extractor_type, required) = unwrap_optional(&ty);
+        let Some(inputs) = resolve_custom_extractor(extractor_type, registry) else {
+            unknown.push(ty);
+            continue;
+        };
+        for input in inputs {
+            match input {
+                ExtractorInput::Builtin { kind, ty: None } if is_configured(configured, extractor_type) => {
+                    return Err(format!(
+                        "Invalid [extractors] entry '{}': kind = \"{}\" needs a `type`, since `{}` has no type argument",
+                        extractor_type.to_token_stream(), kind.to_lowercase(), extractor_type.to_token_stream(),
+                    ));
+                }
+                ExtractorInput::Builtin { kind, ty: Some(inner) } => {
+                    if let Ok(inner_type) = syn::parse_str::<syn::Type>(&inner) {
+                        push_param(&mut handler.params, Extractor { kind, inner_type, required, name: None });
+                    }
+                }
+                ExtractorInput::Header(name) => push_param(&mut handler.params, Extractor {
+                    kind: "Header".to_string(),
+                    inner_type: syn::parse_quote!(String),
+                    required,
+                    name: Some(name),
+                }),
+                ExtractorInput::Bearer => handler.overrides.add_security("bearer", required),
+                ExtractorInput::Builtin { ty: None, .. } | ExtractorInput::Custom(_) => {}
+            }
+        }
+    }
+    handler.unknown_extractors = unknown;
+    Ok(())
+}
+
+fn is_configured(configured: &HashSet<String>, ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(type_path) if configured.contains(&path_name(&type_path.path)))
+}
+
+// `Option<E>` -> (`E`, not required); `Result<E, _>` -> `E`
+fn unwrap_optional(ty: &syn::Type) -> (&syn::Type, bool) {
+    if let syn::Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                    if segment.ident == "Option" {
+                        return (unwrap_optional(inner).0, false);
+                    }
+                    if segment.ident == "Result" {
+                        return unwrap_optional(inner);
+                    }
+                }
+            }
+        }
+    }
+    (ty, true)
+}
+
+// Copies the registered responses of the handler's `Result` error type
+fn attach_error_responses(registry: &HashMap<String, Vec<ErrorResponse>>, handler: &mut HandlerInfo) {
+    let Some(return_type) = &handler.return_type else { return };
+    if let (_, Some(error_type)) = split_result(return_type) {
+        let name = schema_name(&error_type.to_token_stream().to_string());
+        if let Some(responses) = registry.get(&name) {
+            handler.error_responses = responses.clone();
+        }
+    }
+}
+
+// `T` of a `Json<T>` response type, also as the last element of a tuple
+fn json_payload(ty: &syn::Type) -> Option<&syn::Type> {
+    match ty {
+        syn::Type::Reference(reference) => json_payload(&reference.elem),
+        syn::Type::Paren(paren) => json_payload(&paren.elem),
+        syn::Type::Tuple(tuple) => tuple.elems.last().and_then(json_payload),
+        syn::Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) if segment.ident == "Json" => {
+                    args.args.iter().find_map(|arg| match arg {
+                        syn::GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// Keeps the models a handler references, directly or through other models'
+// fields, plus every name in `keep`
+fn referenced_models(
+    handlers: &HashMap<String, HandlerInfo>,
+    mut models: HashMap<String, StructInfo>,
+    keep: &HashSet<String>,
+) -> HashMap<String, StructInfo> {
+    let mut pending: Vec<String> = keep.iter().cloned().collect();
+    let unit: syn::Type = syn::parse_quote!(());
+    for handler in handlers.values() {
+        // 错误类型的响应体来自其 IntoResponse 实现，错误类型本身只有以
+        // `Json<T>` 直接返回时才引用模型
+        let (success_type, error_type) = match &handler.return_type {
+            Some(return_type) => split_result(return_type),
+            None => (&unit, None),
+        };
+        let types = handler.params.iter()
+            .map(|param| &param.inner_type)
+            .chain([success_type])
+            .chain(error_type.and_then(json_payload));
+        for ty in types {
+            let tokens = ty.to_token_stream().to_string();
+            pending.extend(model_names(&tokens));
+        }
+        for response in &handler.error_responses {
+            if let ResponseBody::Json(ty) = &response.body {
+                pending.extend(model_names(ty));
+            }
+        }
+        for body in handler.overrides.responses.iter().filter_map(|response| response.body.as_ref()) {
+            pending.extend(model_names(body));
+        }
+    }
+
+    let mut reachable = HashSet::new();
+    while let Some(name) = pending.pop() {
+        if let Some(model) = models.get(&name) {
+            if reachable.insert(name) {
+                for ty in model.field_types() {
+                    pending.extend(model_names(ty));
+                }
+            }
+        }
+    }
+
+    models.retain(|name, _| reachable.contains(name));
+    models
+}
+
+// Model names a type string may refer to, see `schema_name`
+fn model_names(ty: &str) -> impl Iterator<Item = String> + '_ {
+    IDENT_RE.find_iter(ty).map(|m| {
+        if m.as_str().contains("::") { schema_name(m.as_str()) } else { m.as_str().to_string() }
+    })
+}
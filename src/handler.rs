@@ -0,0 +1,644 @@
+//! Handler signature parsing: extractors, return type and doc comments.
+
+use syn::{parse_file, visit::{self, Visit}, Expr, FnArg, Pat, Type, Item, PathArguments, GenericArgument};
+
+use crate::annotations::OperationOverrides;
+use crate::error_registry::ErrorResponse;
+use crate::extractor_registry::header_name;
+use crate::status::status_from_expr;
+
+/// Signature and documentation of a single handler function.
+pub struct HandlerInfo {
+    pub params: Vec<Extractor>,
+    pub return_type: Option<Type>,
+    pub summary: Option<String>,    // Summary from first line of doc comments
+    pub description: Option<String>, // Description from remaining lines
+    /// `StatusCode` constants the body returns on success, in source order
+    pub success_statuses: Vec<u16>,
+    /// `StatusCode` constants the body returns on failure: inside `Err(..)`,
+    /// `map_err`/`ok_or`/`ok_or_else`, or any 4xx/5xx code
+    pub error_statuses: Vec<u16>,
+    /// Responses of the `Result` error type's `IntoResponse` impl, filled in
+    /// by [`crate::Generator`] from the crate's error registry
+    pub error_responses: Vec<ErrorResponse>,
+    /// Metadata set through `#[axum_doc(...)]` or `@axum_doc(...)` doc lines
+    pub overrides: OperationOverrides,
+    /// Argument types that are neither a documented input nor a known
+    /// server-only extractor
+    pub unknown_extractors: Vec<Type>,
+}
+
+/// A documented extractor argument such as `Json<T>` or `Query<T>`.
+pub struct Extractor {
+    pub kind: String, // "Json", "Query", etc.
+    pub inner_type: Type,
+    /// `false` when wrapped in `Option`
+    pub required: bool,
+    /// Header name, for `Header` inputs
+    pub name: Option<String>,
+}
+
+/// Parses the handler named `handler_name` out of `file_content`.
+/// Returns `None` if the file does not parse or has no such function.
+pub fn parse_handler(file_content: &str, handler_name: &str) -> Option<HandlerInfo> {
+    let ast = parse_file(file_content).ok()?;
+    parse_handler_items(&ast.items, handler_name)
+}
+
+/// Parses the handler named `handler_name` out of a file's `items`.
+pub(crate) fn parse_handler_items(items: &[Item], handler_name: &str) -> Option<HandlerInfo> {
+    let mut handler_info = HandlerInfo {
+        params: Vec::new(),
+        return_type: None,
+        summary: None,
+        description: None,
+        success_statuses: Vec::new(),
+        error_statuses: Vec::new(),
+        error_responses: Vec::new(),
+        overrides: OperationOverrides::default(),
+        unknown_extractors: Vec::new(),
+    };
+
+    for item in items {
+        if let Item::Fn(func) = item {
+            if func.sig.ident == handler_name {
+                // Extract documentation comments
+                let mut doc_comments = Vec::new();
+
+                for attr in &func.attrs {
+                    if attr.path().is_ident("doc") {
+                        // Handle Meta::NameValue (most common for /// comments)
+                        if let syn::Meta::NameValue(nv) = &attr.meta {
+                            if let syn::Expr::Lit(expr_lit) = &nv.value {
+                                if let syn::Lit::Str(lit_str) = &expr_lit.lit {
+                                    let content = lit_str.value().trim().to_string();
+                                    if !content.is_empty() {
+                                        doc_comments.push(content);
+                                    }
+                                }
+                            }
+                        }
+                        // Handle Meta::List (for #![doc = "..."] style)
+                        else if let syn::Meta::List(meta_list) = &attr.meta {
+                            let tokens = meta_list.tokens.to_string();
+                            if let Some(start) = tokens.find('"') {
+                                if let Some(end) = tokens.rfind('"') {
+                                    if start < end {
+                                        let content = &tokens[start + 1..end];
+                                        if !content.is_empty() {
+                                            doc_comments.push(content.trim().to_string());
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // @axum_doc(...) 指令行不属于文档内容
+                handler_info.overrides = OperationOverrides::parse(&func.attrs, handler_name);
+                doc_comments.retain(|line| !handler_info.overrides.apply_doc_line(line, handler_name));
+
+                // Split into summary (first line) and description (rest)
+                if !doc_comments.is_empty() {
+                    handler_info.summary = Some(doc_comments[0].clone());
+                    if doc_comments.len() > 1 {
+                        // Filter out empty lines for description
+                        let non_empty: Vec<String> = doc_comments[1..].iter()
+                            .filter(|s| !s.is_empty())
+                            .cloned()
+                            .collect();
+                        if !non_empty.is_empty() {
+                            handler_info.description = Some(non_empty.join("\n"));
+                        }
+                    }
+                }
+
+                // 提取参数：文档化输入、仅服务端使用的提取器，其余报告为未知
+                let mut lookups = LookupVisitor::default();
+                for input in &func.sig.inputs {
+                    if let FnArg::Typed(pat_type) = input {
+                        match classify_extractor(&pat_type.ty) {
+                            ExtractorClass::Input(extractor) => push_param(&mut handler_info.params, *extractor),
+                            ExtractorClass::Security { scheme, required } => {
+                                handler_info.overrides.add_security(&scheme, required);
+                            }
+                            ExtractorClass::Lookup(kind) => {
+                                if let Pat::Ident(binding) = &*pat_type.pat {
+                                    lookups.bindings.push((binding.ident.to_string(), kind));
+                                }
+                            }
+                            ExtractorClass::ServerOnly => {}
+                            ExtractorClass::Unknown => handler_info.unknown_extractors.push((*pat_type.ty).clone()),
+                        }
+                    }
+                }
+
+                // `headers.get("x-request-id")` 与 `jar.get("session")` 读取的请求头和 cookie
+                if !lookups.bindings.is_empty() {
+                    lookups.visit_block(&func.block);
+                    for extractor in lookups.found {
+                        push_param(&mut handler_info.params, extractor);
+                    }
+                }
+
+                // 提取返回类型
+                if let syn::ReturnType::Type(_, ty) = &func.sig.output {
+                    handler_info.return_type = Some((**ty).clone());
+                }
+
+                // 从函数体中收集状态码
+                let mut statuses = StatusVisitor::default();
+                statuses.visit_block(&func.block);
+                handler_info.success_statuses = statuses.success;
+                handler_info.error_statuses = statuses.errors;
+                return Some(handler_info);
+            }
+        }
+    }
+    None
+}
+
+/// How a handler argument's extractor shows up in the documentation.
+pub(crate) enum ExtractorClass {
+    /// Request input the operation documents
+    Input(Box<Extractor>),
+    /// An authentication scheme, such as `TypedHeader<Authorization<Bearer>>`;
+    /// not required when the extractor is wrapped in `Option`
+    Security { scheme: String, required: bool },
+    /// A header map or cookie jar; the `Header` or `Cookie` parameters are
+    /// the names the handler body looks up in it
+    Lookup(&'static str),
+    /// Resolved on the server (state, extensions, connection details or the
+    /// raw request) and not part of the API
+    ServerOnly,
+    /// Not an extractor axum_doc knows about
+    Unknown,
+}
+
+// Extractors documenting the type in their first generic argument
+const INPUT_EXTRACTORS: [&str; 4] = ["Json", "Query", "Path", "Form"];
+
+// Extractors whose own type is the request body
+const BODY_EXTRACTORS: [&str; 3] = ["Bytes", "String", "Multipart"];
+
+// Cookie jars from `axum_extra::extract`
+const COOKIE_JARS: [&str; 3] = ["CookieJar", "SignedCookieJar", "PrivateCookieJar"];
+
+// Extractors that never reach the API surface
+const SERVER_ONLY_EXTRACTORS: [&str; 16] = [
+    "State", "Extension", "Extensions", "ConnectInfo", "Method", "Uri", "OriginalUri", "MatchedPath",
+    "NestedPath", "Version", "Request", "Parts", "RawQuery", "RawForm", "RawPathParams", "WebSocketUpgrade",
+];
+
+/// Classifies the extractor a handler argument of type `ty` uses.
+/// `Option<E>` makes the input optional and `Result<E, _>` is classified as `E`.
+pub(crate) fn classify_extractor(ty: &Type) -> ExtractorClass {
+    let Type::Path(type_path) = ty else {
+        return ExtractorClass::Unknown;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return ExtractorClass::Unknown;
+    };
+    let ident = segment.ident.to_string();
+    let first_type = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(inner_type)) => Some(inner_type),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match (ident.as_str(), first_type) {
+        ("Option", Some(inner)) => match classify_extractor(inner) {
+            ExtractorClass::Input(extractor) => ExtractorClass::Input(Box::new(Extractor { required: false, ..*extractor })),
+            ExtractorClass::Security { scheme, .. } => ExtractorClass::Security { scheme, required: false },
+            class => class,
+        },
+        ("Result", Some(inner)) => classify_extractor(inner),
+        (kind, Some(inner)) if INPUT_EXTRACTORS.contains(&kind) => ExtractorClass::Input(Box::new(Extractor {
+            kind: ident,
+            inner_type: inner.clone(),
+            required: true,
+            name: None,
+        })),
+        (kind, _) if BODY_EXTRACTORS.contains(&kind) => ExtractorClass::Input(Box::new(Extractor {
+            kind: ident,
+            inner_type: ty.clone(),
+            required: true,
+            name: None,
+        })),
+        ("TypedHeader", Some(header)) => typed_header(header),
+        ("HeaderMap", _) => ExtractorClass::Lookup("Header"),
+        (kind, _) if COOKIE_JARS.contains(&kind) => ExtractorClass::Lookup("Cookie"),
+        (kind, _) if SERVER_ONLY_EXTRACTORS.contains(&kind) => ExtractorClass::ServerOnly,
+        _ => ExtractorClass::Unknown,
+    }
+}
+
+// Collects the `StatusCode`s a handler body mentions, split into success
+// and error codes
+#[derive(Default)]
+struct StatusVisitor {
+    error_depth: usize,
+    success: Vec<u16>,
+    errors: Vec<u16>,
+}
+
+impl StatusVisitor {
+    fn record(&mut self, code: u16) {
+        let list = if self.error_depth > 0 || code >= 400 { &mut self.errors } else { &mut self.success };
+        if !list.contains(&code) {
+            list.push(code);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for StatusVisitor {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        match status_from_expr(expr) {
+            Some(code) => self.record(code),
+            None => visit::visit_expr(self, expr),
+        }
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        let is_err = matches!(&*call.func, Expr::Path(func) if func.path.is_ident("Err"));
+        self.error_depth += is_err as usize;
+        visit::visit_expr_call(self, call);
+        self.error_depth -= is_err as usize;
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.visit_expr(&call.receiver);
+        let is_err = ["map_err", "ok_or", "ok_or_else"].iter().any(|method| call.method == method);
+        self.error_depth += is_err as usize;
+        for arg in &call.args {
+            self.visit_expr(arg);
+        }
+        self.error_depth -= is_err as usize;
+    }
+
+    // Nested items are not part of the handler's control flow
+    fn visit_item(&mut self, _item: &'ast Item) {}
+}
+
+// `Authorization<Bearer>` -> the `bearer` scheme, `UserAgent` -> the `user-agent` header
+fn typed_header(header: &Type) -> ExtractorClass {
+    let Type::Path(type_path) = header else {
+        return ExtractorClass::Unknown;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return ExtractorClass::Unknown;
+    };
+    if segment.ident == "Authorization" {
+        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+            if let Some(GenericArgument::Type(Type::Path(scheme))) = args.args.first() {
+                let scheme = scheme.path.segments.last().map(|s| s.ident.to_string().to_lowercase());
+                if let Some(scheme @ ("bearer" | "basic")) = scheme.as_deref() {
+                    return ExtractorClass::Security { scheme: scheme.to_string(), required: true };
+                }
+            }
+        }
+    }
+    ExtractorClass::Input(Box::new(Extractor {
+        kind: "Header".to_string(),
+        inner_type: syn::parse_quote!(String),
+        required: true,
+        name: Some(header_name_of(&segment.ident.to_string())),
+    }))
+}
+
+// Wire name of a `headers` crate type: `UserAgent` -> `user-agent`
+fn header_name_of(type_name: &str) -> String {
+    if type_name == "ETag" {
+        return "etag".to_string();
+    }
+    let mut name = String::new();
+    for (i, c) in type_name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            name.push('-');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
+// Header parameters OpenAPI ignores: they are described by the security
+// schemes, the request body's media type and the response content
+const IGNORED_HEADERS: [&str; 3] = ["authorization", "content-type", "accept"];
+
+/// Adds a parameter unless a header or cookie of the same name is already
+/// documented, or it is a header OpenAPI doesn't allow as a parameter.
+pub(crate) fn push_param(params: &mut Vec<Extractor>, extractor: Extractor) {
+    if let ("Header", Some(name)) = (extractor.kind.as_str(), &extractor.name) {
+        if IGNORED_HEADERS.contains(&name.to_lowercase().as_str()) {
+            return;
+        }
+    }
+    let duplicate = extractor.name.is_some()
+        && params.iter().any(|p| p.kind == extractor.kind && p.name == extractor.name);
+    if !duplicate {
+        params.push(extractor);
+    }
+}
+
+// Header and cookie names looked up with literal keys on header map and
+// cookie jar arguments, e.g. `headers.get("x-request-id")`
+#[derive(Default)]
+struct LookupVisitor {
+    /// Argument bindings and the parameter kind they hold
+    bindings: Vec<(String, &'static str)>,
+    found: Vec<Extractor>,
+}
+
+impl<'ast> Visit<'ast> for LookupVisitor {
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let binding = match &*call.receiver {
+            Expr::Path(path) => path.path.get_ident()
+                .and_then(|ident| self.bindings.iter().find(|(name, _)| ident == name)),
+            _ => None,
+        };
+        let is_lookup = ["get", "get_all", "contains_key"].iter().any(|method| call.method == method);
+        if let (Some((_, kind)), true) = (binding, is_lookup) {
+            // Cookie names are case-sensitive; header names are not
+            let name = match (*kind, call.args.first()) {
+                ("Cookie", Some(Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }))) => Some(s.value()),
+                ("Cookie", _) => None,
+                (_, arg) => arg.and_then(header_name),
+            };
+            if let Some(name) = name {
+                // 查找结果是 Option，因此参数是可选的
+                self.found.push(Extractor {
+                    kind: kind.to_string(),
+                    inner_type: syn::parse_quote!(String),
+                    required: false,
+                    name: Some(name),
+                });
+            }
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    // Nested items are not part of the handler body
+    fn visit_item(&mut self, _item: &'ast Item) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    #[test]
+    fn test_extract_doc_comments_from_attrs() {
+        // This test verifies that doc comment extraction logic works
+        // We create attributes that syn would produce from /// comments
+
+        let code = r#"
+        /// User login endpoint
+        ///
+        /// This endpoint handles user authentication and returns a JWT token.
+        async fn test_handler() -> &'static str {
+            "ok"
+        }
+        "#;
+
+        let ast = parse_file(code).unwrap();
+        if let syn::Item::Fn(func) = &ast.items[0] {
+            let mut doc_comments = Vec::new();
+
+            for attr in &func.attrs {
+                if attr.path().is_ident("doc") {
+                    if let syn::Meta::NameValue(nv) = &attr.meta {
+                        if let syn::Expr::Lit(expr_lit) = &nv.value {
+                            if let syn::Lit::Str(lit_str) = &expr_lit.lit {
+                                let content = lit_str.value().trim().to_string();
+                                if !content.is_empty() {
+                                    doc_comments.push(content);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Blank lines are filtered out after trimming
+            assert_eq!(doc_comments.len(), 2);
+            assert_eq!(doc_comments[0], "User login endpoint");
+            assert_eq!(doc_comments[1], "This endpoint handles user authentication and returns a JWT token.");
+        }
+    }
+
+    #[test]
+    fn test_doc_comment_splitting() {
+        // Test splitting doc comments into summary and description
+
+        let code = r#"
+        /// Summary line
+        ///
+        /// Detailed description
+        /// Second line of description
+        async fn test_handler() -> &'static str {
+            "ok"
+        }
+        "#;
+
+        let ast = parse_file(code).unwrap();
+        if let syn::Item::Fn(func) = &ast.items[0] {
+            let mut doc_comments = Vec::new();
+
+            for attr in &func.attrs {
+                if attr.path().is_ident("doc") {
+                    if let syn::Meta::NameValue(nv) = &attr.meta {
+                        if let syn::Expr::Lit(expr_lit) = &nv.value {
+                            if let syn::Lit::Str(lit_str) = &expr_lit.lit {
+                                let content = lit_str.value().trim().to_string();
+                                if !content.is_empty() {
+                                    doc_comments.push(content);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Simulate the splitting logic
+            let summary = if !doc_comments.is_empty() {
+                Some(doc_comments[0].clone())
+            } else {
+                None
+            };
+
+            let description = if doc_comments.len() > 1 {
+                // Filter out empty lines and join
+                let non_empty: Vec<String> = doc_comments[1..].iter()
+                    .filter(|s| !s.is_empty())
+                    .cloned()
+                    .collect();
+                if non_empty.is_empty() {
+                    None
+                } else {
+                    Some(non_empty.join("\n"))
+                }
+            } else {
+                None
+            };
+
+            assert_eq!(summary, Some("Summary line".to_string()));
+            assert_eq!(description, Some("Detailed description\nSecond line of description".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_single_doc_comment() {
+        // Test handler with only summary, no description
+
+        let code = r#"
+        /// Single line comment
+        async fn test_handler() -> &'static str {
+            "ok"
+        }
+        "#;
+
+        let ast = parse_file(code).unwrap();
+        if let syn::Item::Fn(func) = &ast.items[0] {
+            let mut doc_comments = Vec::new();
+
+            for attr in &func.attrs {
+                if attr.path().is_ident("doc") {
+                    if let syn::Meta::NameValue(nv) = &attr.meta {
+                        if let syn::Expr::Lit(expr_lit) = &nv.value {
+                            if let syn::Lit::Str(lit_str) = &expr_lit.lit {
+                                let content = lit_str.value().trim().to_string();
+                                if !content.is_empty() {
+                                    doc_comments.push(content);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            assert_eq!(doc_comments.len(), 1);
+            // Doc comments are now trimmed
+            assert_eq!(doc_comments[0], "Single line comment");
+        }
+    }
+
+    #[test]
+    fn test_handler_status_codes() {
+        let code = r#"
+        async fn create(Json(user): Json<User>) -> Result<(StatusCode, Json<User>), (StatusCode, String)> {
+            let id = lookup(&user).ok_or((StatusCode::BAD_REQUEST, "bad".to_string()))?;
+            if id == 0 {
+                return Err((StatusCode::CONFLICT, "exists".to_string()));
+            }
+            fn helper() -> StatusCode { StatusCode::GONE }
+            Ok((StatusCode::CREATED, Json(user)))
+        }
+        "#;
+        let handler = parse_handler(code, "create").unwrap();
+        assert_eq!(handler.success_statuses, vec![201]);
+        assert_eq!(handler.error_statuses, vec![400, 409]);
+
+        let code = r#"
+        async fn remove() -> impl IntoResponse {
+            if missing() { return StatusCode::NOT_FOUND; }
+            StatusCode::from_u16(204).unwrap()
+        }
+        "#;
+        let handler = parse_handler(code, "remove").unwrap();
+        assert_eq!(handler.success_statuses, vec![204]);
+        assert_eq!(handler.error_statuses, vec![404]);
+    }
+
+    #[test]
+    fn test_classify_extractors() {
+        let code = r#"
+        async fn update(
+            State(state): State<AppState>,
+            Extension(user): Extension<CurrentUser>,
+            ConnectInfo(addr): ConnectInfo<SocketAddr>,
+            headers: HeaderMap,
+            method: Method,
+            Path(id): Path<UserId>,
+            query: Option<Query<Paging>>,
+            AuthUser(user): AuthUser,
+            payload: Result<Json<UpdateUser>, JsonRejection>,
+        ) -> Json<User> {
+            todo!()
+        }
+        "#;
+        let handler = parse_handler(code, "update").unwrap();
+        let params: Vec<(&str, String, bool)> = handler.params.iter()
+            .map(|p| (p.kind.as_str(), p.inner_type.to_token_stream().to_string(), p.required))
+            .collect();
+        assert_eq!(params, vec![
+            ("Path", "UserId".to_string(), true),
+            ("Query", "Paging".to_string(), false),
+            ("Json", "UpdateUser".to_string(), true),
+        ]);
+        let unknown: Vec<String> = handler.unknown_extractors.iter()
+            .map(|ty| ty.to_token_stream().to_string())
+            .collect();
+        assert_eq!(unknown, vec!["AuthUser"]);
+    }
+
+    #[test]
+    fn test_header_and_cookie_params() {
+        let code = r#"
+        async fn profile(
+            TypedHeader(agent): TypedHeader<UserAgent>,
+            TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+            headers: HeaderMap,
+            jar: CookieJar,
+        ) -> Json<Profile> {
+            let request_id = headers.get("x-request-id");
+            let language = headers.get(header::ACCEPT_LANGUAGE);
+            let agent_again = headers.get("User-Agent");
+            let session = jar.get("sessionId");
+            todo!()
+        }
+        "#;
+        let handler = parse_handler(code, "profile").unwrap();
+        let params: Vec<(&str, Option<&str>, bool)> = handler.params.iter()
+            .map(|p| (p.kind.as_str(), p.name.as_deref(), p.required))
+            .collect();
+        assert_eq!(params, vec![
+            ("Header", Some("user-agent"), true),
+            ("Header", Some("x-request-id"), false),
+            ("Header", Some("accept-language"), false),
+            ("Cookie", Some("sessionId"), false),
+        ]);
+        assert_eq!(handler.overrides.security, vec!["bearer"]);
+        assert!(handler.unknown_extractors.is_empty());
+    }
+
+    #[test]
+    fn test_optional_auth_and_ignored_headers() {
+        let code = r#"
+        async fn feed(
+            auth: Option<TypedHeader<Authorization<Bearer>>>,
+            TypedHeader(content_type): TypedHeader<ContentType>,
+            headers: HeaderMap,
+        ) -> Json<Feed> {
+            let accept = headers.get("Accept");
+            let raw_auth = headers.get(header::AUTHORIZATION);
+            let locale = headers.get("x-locale");
+            todo!()
+        }
+        "#;
+        let handler = parse_handler(code, "feed").unwrap();
+        let names: Vec<Option<&str>> = handler.params.iter().map(|p| p.name.as_deref()).collect();
+        assert_eq!(names, vec![Some("x-locale")]);
+        assert_eq!(handler.overrides.security, vec!["bearer"]);
+        assert!(handler.overrides.security_optional);
+
+        let code = r#"
+        async fn me(TypedHeader(auth): TypedHeader<Authorization<Bearer>>) -> Json<User> { todo!() }
+        "#;
+        let handler = parse_handler(code, "me").unwrap();
+        assert_eq!(handler.overrides.security, vec!["bearer"]);
+        assert!(!handler.overrides.security_optional);
+    }
+}
@@ -0,0 +1,78 @@
+//! Generate OpenAPI 3.0 documents from Axum projects by static analysis.
+//!
+//! The [`Generator`] builder is the main entry point and is what the
+//! `axum_doc` binary uses. It can equally be called from a `build.rs` or
+//! other tooling:
+//!
+//! ```no_run
+//! let spec = axum_doc::Generator::new(".")
+//!     .entry_file("src/main.rs")
+//!     .model_file("src/types.rs")
+//!     .generate()?;
+//! std::fs::write("openapi.json", spec.to_json_pretty()?)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! The lower-level building blocks ([`RouterVisitor`], [`parse_handler`],
+//! [`parse_models`], [`generate_openapi`]) are exported for callers that
+//! need to drive the individual stages themselves.
+//!
+//! # Features
+//!
+//! - `serve` (default): the `axum_doc serve` preview server, with Swagger UI
+//!   and Redoc compiled in. The binary requires it; library and `build.rs`
+//!   users that only generate specs can turn it off with
+//!   `default-features = false` and skip axum, tokio and the bundled UI.
+//! - `runtime`: `runtime::router_from_embedded`, serving a spec from the documented
+//!   application itself.
+
+pub mod annotations;
+pub mod compat;
+pub mod config;
+pub mod crate_index;
+pub mod diff;
+pub mod error_registry;
+pub mod extractor_registry;
+pub mod generator;
+pub mod handler;
+pub mod markdown;
+pub mod models;
+pub mod openapi;
+mod reference;
+pub mod router;
+#[cfg(feature = "runtime")]
+pub mod runtime;
+pub mod schema;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod serde_attrs;
+pub mod site;
+pub mod sources;
+mod status;
+#[cfg(any(feature = "serve", feature = "runtime"))]
+mod ui;
+pub mod version;
+pub mod watch;
+
+pub use annotations::{OperationOverrides, ResponseOverride};
+pub use compat::{compare_specs, ApiChange, ChangeReport};
+pub use config::Config;
+pub use crate_index::{CrateIndex, ModuleInfo};
+pub use diff::{diff_specs, load_spec, FieldChange, ItemChange, ItemDiff, SpecDiff};
+pub use error_registry::{parse_error_responses, ErrorResponse, ResponseBody};
+pub use extractor_registry::{resolve_custom_extractor, CustomExtractor, ExtractorInput};
+pub use generator::Generator;
+pub use handler::{parse_handler, Extractor, HandlerInfo};
+pub use markdown::{render_markdown, render_markdown_pages, write_markdown_pages};
+pub use models::{parse_models, EnumInfo, EnumTagging, FieldInfo, StructInfo, VariantInfo, VariantKind};
+pub use openapi::{generate_openapi, Components, Contact, Info, License, OpenApi, Server, Tag};
+pub use router::{RouteInfo, RouterVisitor};
+#[cfg(feature = "runtime")]
+pub use runtime::{router_from_embedded, EmbeddedDocs};
+pub use schema::{generate_schemas, instantiate_generics, rust_type_to_openapi, schema_name};
+#[cfg(feature = "serve")]
+pub use serve::{serve, ServeOptions};
+pub use site::{render_site, write_site};
+pub use sources::{SourceCache, SourceError};
+pub use version::{OutputFormat, SpecVersion};
+pub use watch::{Update, Watcher};
@@ -0,0 +1,418 @@
+//! Markdown API reference: one section per tag with every operation's
+//! parameters, request and response bodies and JSON examples, followed by
+//! the component schemas.
+//!
+//! The reference is either a single document ([`render_markdown`]) or split
+//! into one file per tag ([`render_markdown_pages`]): `README.md` lists the
+//! tags, `tag-{tag}.md` holds the operations of a tag and `schemas.md` the
+//! schemas. Links use explicit `<a id>` anchors, so they work on any
+//! renderer that allows inline HTML.
+
+use std::{collections::BTreeMap, error::Error, fs, path::Path};
+use serde_json::Value;
+
+use crate::openapi::OpenApi;
+use crate::reference::{example, is_json, notes, ref_name, sections, shape, Content, Note, Operation, Section, Shape, TypeName};
+
+/// Renders the reference as a single document.
+pub fn render_markdown(spec: &OpenApi) -> String {
+    let sections = sections(spec);
+    let writer = Writer { spec, level: 2, schemas_file: "", split: false };
+    let mut md = writer.overview(&sections);
+    for section in &sections {
+        md.push_str(&writer.section(section));
+    }
+    md.push_str(&writer.schemas());
+    md
+}
+
+/// Renders the reference as one file per tag, as file names and contents.
+pub fn render_markdown_pages(spec: &OpenApi) -> BTreeMap<String, String> {
+    let sections = sections(spec);
+    let writer = Writer { spec, level: 1, schemas_file: "schemas.md", split: true };
+    let mut files = BTreeMap::new();
+    files.insert("README.md".to_string(), writer.overview(&sections));
+    for section in &sections {
+        files.insert(section_file(section), writer.section(section));
+    }
+    files.insert("schemas.md".to_string(), writer.schemas());
+    files
+}
+
+/// Writes the split reference into `dir`, creating it if needed.
+pub fn write_markdown_pages(spec: &OpenApi, dir: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    for (name, content) in render_markdown_pages(spec) {
+        let path = dir.join(name);
+        fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn section_file(section: &Section) -> String {
+    format!("tag-{}.md", section.slug)
+}
+
+// Renders the parts of the reference. A single document nests tags under the
+// title (`level` 2); split files start each tag at the top level and link to
+// schemas in `schemas_file`.
+struct Writer<'a> {
+    spec: &'a OpenApi,
+    level: usize,
+    schemas_file: &'static str,
+    split: bool,
+}
+
+impl Writer<'_> {
+    fn heading(&self, depth: usize, text: &str, anchor: Option<&str>) -> String {
+        let anchor = anchor.map(|id| format!(" <a id=\"{}\"></a>", id)).unwrap_or_default();
+        format!("{} {}{}\n\n", "#".repeat(self.level + depth), text, anchor)
+    }
+
+    fn schema_link(&self, name: &str) -> String {
+        format!("[{}]({}#schema-{})", name, self.schemas_file, name)
+    }
+
+    fn type_md(&self, schema: &Value) -> String {
+        TypeName::of(schema).render(&|name| self.schema_link(name))
+    }
+
+    fn section_link(&self, section: &Section) -> String {
+        if self.split {
+            section_file(section)
+        } else {
+            format!("#tag-{}", section.slug)
+        }
+    }
+
+    fn operation_link(&self, section: &Section, operation: &Operation) -> String {
+        if self.split {
+            format!("{}#{}", section_file(section), operation.anchor())
+        } else {
+            format!("#{}", operation.anchor())
+        }
+    }
+
+    fn overview(&self, sections: &[Section]) -> String {
+        let info = &self.spec.info;
+        let mut md = format!("# {}\n\nVersion {}\n\n", info.title, info.version);
+        if let Some(description) = &info.description {
+            md.push_str(&format!("{}\n\n", description.trim()));
+        }
+        if !self.spec.servers.is_empty() {
+            md.push_str("## Servers\n\n");
+            for server in &self.spec.servers {
+                match &server.description {
+                    Some(description) => md.push_str(&format!("- `{}` — {}\n", server.url, description)),
+                    None => md.push_str(&format!("- `{}`\n", server.url)),
+                }
+            }
+            md.push('\n');
+        }
+
+        md.push_str("## Contents\n\n");
+        for section in sections {
+            md.push_str(&format!("- [{}]({})\n", section.name, self.section_link(section)));
+            for operation in &section.operations {
+                md.push_str(&format!(
+                    "  - [`{} {}`]({})",
+                    operation.method.to_uppercase(),
+                    operation.path,
+                    self.operation_link(section, operation),
+                ));
+                if let Some(summary) = operation.str_field("summary") {
+                    md.push_str(&format!(" — {}", summary));
+                }
+                md.push('\n');
+            }
+        }
+        if !self.spec.components.schemas.is_empty() {
+            let target = if self.split { "schemas.md" } else { "#schemas" };
+            md.push_str(&format!("- [Schemas]({})\n", target));
+        }
+        md.push('\n');
+        md
+    }
+
+    fn section(&self, section: &Section) -> String {
+        let anchor = format!("tag-{}", section.slug);
+        let mut md = self.heading(0, &section.name, (!self.split).then_some(anchor.as_str()));
+        if let Some(description) = section.description {
+            md.push_str(&format!("{}\n\n", description.trim()));
+        }
+        for operation in &section.operations {
+            md.push_str(&self.operation(operation));
+        }
+        md
+    }
+
+    fn operation(&self, operation: &Operation) -> String {
+        let title = format!("`{} {}`", operation.method.to_uppercase(), operation.path);
+        let mut md = self.heading(1, &title, Some(&operation.anchor()));
+        if operation.deprecated() {
+            md.push_str("> **Deprecated**\n\n");
+        }
+        if let Some(summary) = operation.str_field("summary") {
+            md.push_str(&format!("**{}**\n\n", summary.trim()));
+        }
+        if let Some(description) = operation.str_field("description") {
+            md.push_str(&format!("{}\n\n", description.trim()));
+        }
+
+        let parameters = operation.parameters();
+        if !parameters.is_empty() {
+            md.push_str("**Parameters**\n\n| Name | In | Type | Required | Description |\n|------|----|------|----------|-------------|\n");
+            for parameter in parameters {
+                md.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} |\n",
+                    cell(parameter.name),
+                    parameter.location,
+                    cell(&self.type_md(parameter.schema)),
+                    if parameter.required { "yes" } else { "no" },
+                    self.notes_md(parameter.schema, parameter.description),
+                ));
+            }
+            md.push('\n');
+        }
+
+        let body = operation.request_body();
+        if !body.is_empty() {
+            md.push_str("**Request body**\n\n");
+            md.push_str(&self.contents(&body));
+        }
+
+        let responses = operation.responses();
+        if !responses.is_empty() {
+            md.push_str("**Responses**\n\n| Status | Description | Body |\n|--------|-------------|------|\n");
+            for response in &responses {
+                let body: Vec<String> = response.content.iter()
+                    .map(|content| format!("{} (`{}`)", self.type_md(content.schema), content.media_type))
+                    .collect();
+                md.push_str(&format!("| {} | {} | {} |\n", response.status, cell(response.description), cell(&body.join(", "))));
+            }
+            md.push('\n');
+            for (statuses, schema) in operation.response_examples() {
+                md.push_str(&format!("Response {}: {}\n\n", statuses.join(", "), self.type_md(schema)));
+                md.push_str(&self.fields(schema));
+                md.push_str(&self.example(schema));
+            }
+        }
+        md
+    }
+
+    // Request body: its type, the fields of the referenced schema and an example
+    fn contents(&self, contents: &[Content]) -> String {
+        let mut md = String::new();
+        for content in contents {
+            md.push_str(&format!("`{}`: {}\n\n", content.media_type, self.type_md(content.schema)));
+            if is_json(content.media_type) {
+                md.push_str(&self.fields(content.schema));
+                md.push_str(&self.example(content.schema));
+            }
+        }
+        md
+    }
+
+    // Field table of a body whose schema is a reference to an object schema
+    fn fields(&self, schema: &Value) -> String {
+        let target = ref_name(schema).and_then(|name| self.spec.components.schemas.get(name));
+        match target.map(shape) {
+            Some(Shape::Object { properties, .. }) if !properties.is_empty() => self.properties(&properties),
+            _ => String::new(),
+        }
+    }
+
+    fn properties(&self, properties: &[crate::reference::Property]) -> String {
+        let mut md = "| Field | Type | Required | Description |\n|-------|------|----------|-------------|\n".to_string();
+        for property in properties {
+            md.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                cell(property.name),
+                cell(&self.type_md(property.schema)),
+                if property.required { "yes" } else { "no" },
+                self.notes_md(property.schema, None),
+            ));
+        }
+        md.push('\n');
+        md
+    }
+
+    fn notes_md(&self, schema: &Value, description: Option<&str>) -> String {
+        let notes: Vec<String> = notes(schema, description).into_iter()
+            .map(|note| match note {
+                Note::Text(text) => cell(text),
+                Note::Values(values) => format!("One of {}", values_md(&values)),
+                Note::Default(value) => format!("Default `{}`", cell(&value)),
+                Note::Deprecated => "Deprecated".to_string(),
+            })
+            .collect();
+        notes.join("<br>")
+    }
+
+    fn example(&self, schema: &Value) -> String {
+        let example = example(schema, &self.spec.components.schemas);
+        let json = serde_json::to_string_pretty(&example).expect("examples always serialize");
+        format!("```json\n{}\n```\n\n", json)
+    }
+
+    fn schemas(&self) -> String {
+        let schemas = &self.spec.components.schemas;
+        if schemas.is_empty() {
+            return String::new();
+        }
+        let mut md = self.heading(0, "Schemas", (!self.split).then_some("schemas"));
+        for (name, schema) in schemas {
+            md.push_str(&self.heading(1, name, Some(&format!("schema-{}", name))));
+            if let Some(description) = schema.get("description").and_then(Value::as_str) {
+                md.push_str(&format!("{}\n\n", description.trim()));
+            }
+            match shape(schema) {
+                Shape::Object { properties, includes } => {
+                    for include in includes {
+                        md.push_str(&format!("Includes all fields of {}.\n\n", self.schema_link(include)));
+                    }
+                    if !properties.is_empty() {
+                        md.push_str(&self.properties(&properties));
+                    }
+                }
+                Shape::Enum(values) => {
+                    let values: Vec<String> = values.iter().map(Value::to_string).collect();
+                    md.push_str(&format!("One of {}.\n\n", values_md(&values)));
+                }
+                Shape::Variants { variants, discriminator } => {
+                    match discriminator {
+                        Some(property) => md.push_str(&format!("One of the following, told apart by `{}`:\n\n", property)),
+                        None => md.push_str("One of the following:\n\n"),
+                    }
+                    for variant in variants {
+                        md.push_str(&format!("- {}\n", self.variant(variant)));
+                    }
+                    md.push('\n');
+                }
+                Shape::Other => md.push_str(&format!("Type: {}\n\n", self.type_md(schema))),
+            }
+            md.push_str(&self.example(schema));
+        }
+        md
+    }
+
+    fn variant(&self, variant: &Value) -> String {
+        match shape(variant) {
+            Shape::Object { properties, includes } => {
+                let fields: Vec<String> = includes.into_iter().map(|name| self.schema_link(name))
+                    .chain(properties.iter().map(|property| format!("`{}`: {}", property.name, self.type_md(property.schema))))
+                    .collect();
+                format!("object with {}", fields.join(", "))
+            }
+            Shape::Enum(values) => values_md(&values.iter().map(Value::to_string).collect::<Vec<_>>()),
+            _ => self.type_md(variant),
+        }
+    }
+}
+
+fn values_md(values: &[String]) -> String {
+    values.iter().map(|value| format!("`{}`", cell(value))).collect::<Vec<_>>().join(", ")
+}
+
+// Text safe to put in a table cell
+fn cell(text: &str) -> String {
+    text.trim().replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> OpenApi {
+        serde_json::from_value(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Shop API", "version": "1.2.0"},
+            "paths": {
+                "/users": {
+                    "post": {
+                        "tags": ["modules::user"],
+                        "operationId": "create_user",
+                        "summary": "Create a user",
+                        "description": "Registers a new account.",
+                        "requestBody": {"required": true, "content": {"application/json": {"schema": {"$ref": "#/components/schemas/NewUser"}}}},
+                        "responses": {
+                            "201": {"description": "Created", "content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}}},
+                            "409": {"description": "Conflict"}
+                        }
+                    },
+                    "get": {
+                        "tags": ["modules::user"],
+                        "parameters": [{"name": "role", "in": "query", "required": false, "schema": {"type": "string", "enum": ["admin", "member"]}}],
+                        "responses": {"200": {"description": "OK", "content": {"application/json": {"schema": {"type": "array", "items": {"$ref": "#/components/schemas/User"}}}}}}
+                    }
+                }
+            },
+            "components": {"schemas": {
+                "NewUser": {"type": "object", "required": ["name"], "properties": {"name": {"type": "string"}}},
+                "User": {"type": "object", "required": ["id", "name"], "properties": {
+                    "id": {"type": "integer", "format": "int64"},
+                    "name": {"type": "string"}
+                }}
+            }}
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let md = render_markdown(&spec());
+        assert!(md.starts_with("# Shop API\n\nVersion 1.2.0\n\n## Contents\n\n- [modules::user](#tag-modules-user)\n"));
+        assert!(md.contains("  - [`POST /users`](#create-user) — Create a user\n"));
+        assert!(md.contains("## modules::user <a id=\"tag-modules-user\"></a>\n"));
+        assert!(md.contains("### `POST /users` <a id=\"create-user\"></a>\n\n**Create a user**\n\nRegisters a new account.\n"));
+        assert!(md.contains("| `role` | query | string | no | One of `\"admin\"`, `\"member\"` |\n"));
+        assert!(md.contains("`application/json`: [NewUser](#schema-NewUser)\n\n| Field | Type | Required | Description |"));
+        assert!(md.contains("| 200 | OK | array of [User](#schema-User) (`application/json`) |\n"));
+        assert!(md.contains("Response 201: [User](#schema-User)\n\n| Field | Type | Required | Description |\n"));
+        assert!(md.contains("| `id` | integer (int64) | yes |  |\n| `name` | string | yes |  |\n\n```json\n{\n  \"id\": 0,\n  \"name\": \"string\"\n}\n```\n"));
+        assert!(md.contains("### User <a id=\"schema-User\"></a>\n\n| Field | Type | Required | Description |"));
+    }
+
+    #[test]
+    fn test_render_markdown_pages() {
+        let files = render_markdown_pages(&spec());
+        let names: Vec<&str> = files.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["README.md", "schemas.md", "tag-modules-user.md"]);
+        assert!(files["README.md"].contains("## Contents\n\n- [modules::user](tag-modules-user.md)\n  - [`GET /users`](tag-modules-user.md#get-users)\n"));
+        let users = &files["tag-modules-user.md"];
+        assert!(users.starts_with("# modules::user\n\n## `GET /users` <a id=\"get-users\"></a>\n"));
+        assert!(users.contains("array of [User](schemas.md#schema-User)"));
+        assert!(files["schemas.md"].starts_with("# Schemas\n\n## NewUser <a id=\"schema-NewUser\"></a>\n"));
+    }
+
+    #[test]
+    fn test_unicode_and_clashing_tags() {
+        let spec: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Shop", "version": "1"},
+            "paths": {
+                "/users": {"get": {"tags": ["用户"], "operationId": "list_users", "responses": {}}},
+                "/orders": {"get": {"tags": ["订单"], "operationId": "list_orders", "responses": {}}},
+                "/admin": {"get": {"tags": ["Admin"], "operationId": "admin", "responses": {}}},
+                "/admin2": {"get": {"tags": ["admin"], "operationId": "admin2", "responses": {}}}
+            },
+            "components": {"schemas": {}}
+        })).unwrap();
+
+        let md = render_markdown(&spec);
+        assert!(md.contains("- [用户](#tag-用户)\n"));
+        assert!(md.contains("- [订单](#tag-订单)\n"));
+        assert!(md.contains("- [admin](#tag-admin-2)\n"));
+        assert!(md.contains("## 用户 <a id=\"tag-用户\"></a>\n"));
+        assert!(md.contains("## 订单 <a id=\"tag-订单\"></a>\n"));
+        assert!(md.contains("## Admin <a id=\"tag-admin\"></a>\n"));
+        assert!(md.contains("## admin <a id=\"tag-admin-2\"></a>\n"));
+
+        let files = render_markdown_pages(&spec);
+        let names: Vec<&str> = files.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["README.md", "schemas.md", "tag-admin-2.md", "tag-admin.md", "tag-用户.md", "tag-订单.md"]);
+        assert!(files["tag-用户.md"].starts_with("# 用户\n"));
+        assert!(files["tag-订单.md"].starts_with("# 订单\n"));
+        assert!(files["README.md"].contains("- [订单](tag-订单.md)\n  - [`GET /orders`](tag-订单.md#list-orders)\n"));
+    }
+}
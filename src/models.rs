@@ -0,0 +1,324 @@
+//! Model parsing: collects struct and enum definitions used as request/response bodies.
+
+use std::collections::HashMap;
+use syn::{parse_file, visit_mut::{self, VisitMut}, Item, Type};
+use quote::ToTokens;
+
+use crate::serde_attrs::{apply_rename_rule, ContainerAttrs, FieldAttrs};
+
+/// A model type that can be referenced from `components.schemas`.
+#[derive(Debug, Clone)]
+pub struct StructInfo {
+    pub name: String,
+    /// Type parameter names of a generic model (`["T"]` for `Page<T>`).
+    /// Generic models are only emitted through their instantiations.
+    pub generics: Vec<String>,
+    pub fields: Vec<FieldInfo>,
+    /// Set when the model is an enum; `fields` is empty in that case.
+    pub enum_info: Option<EnumInfo>,
+    pub serde: ContainerAttrs,
+    /// Fixed schema from a configured type override, used instead of the
+    /// fields; see [`StructInfo::from_schema`].
+    pub schema: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldInfo {
+    pub name: String,
+    pub ty: String,
+    pub serde: FieldAttrs,
+}
+
+/// Variants of an enum model and how serde represents them.
+#[derive(Debug, Clone)]
+pub struct EnumInfo {
+    pub tagging: EnumTagging,
+    pub variants: Vec<VariantInfo>,
+}
+
+/// serde's enum representations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnumTagging {
+    /// Default: `{"Variant": content}`, unit variants as `"Variant"`.
+    External,
+    /// `#[serde(tag = "t")]`: `{"t": "Variant", ...fields}`.
+    Internal { tag: String },
+    /// `#[serde(tag = "t", content = "c")]`: `{"t": "Variant", "c": content}`.
+    Adjacent { tag: String, content: String },
+    /// `#[serde(untagged)]`: just the content.
+    Untagged,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantInfo {
+    pub name: String,
+    pub kind: VariantKind,
+    pub serde: FieldAttrs,
+}
+
+#[derive(Debug, Clone)]
+pub enum VariantKind {
+    Unit,
+    /// `Variant(T)`
+    Newtype(String),
+    /// `Variant(A, B, ..)`
+    Tuple(Vec<String>),
+    /// `Variant { a: A, .. }`
+    Struct(Vec<FieldInfo>),
+}
+
+impl FieldInfo {
+    /// Name of the field on the wire, after `rename`/`rename_all`.
+    pub fn wire_name(&self) -> &str {
+        self.serde.rename.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Whether the field must be present: not an `Option`, and neither
+    /// defaulted on input nor skippable on output.
+    pub fn is_required(&self) -> bool {
+        !is_option_type(&self.ty) && !self.serde.default && !self.serde.skip_serializing_if
+    }
+}
+
+impl VariantInfo {
+    /// Name of the variant on the wire, after `rename`/`rename_all`.
+    pub fn wire_name(&self) -> &str {
+        self.serde.rename.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl StructInfo {
+    /// A model whose component schema is `schema` as given, for types the
+    /// parser cannot see (external crates) or should not infer.
+    pub fn from_schema(name: &str, schema: serde_json::Value) -> Self {
+        StructInfo {
+            name: name.to_string(),
+            generics: Vec::new(),
+            fields: Vec::new(),
+            enum_info: None,
+            serde: ContainerAttrs::default(),
+            schema: Some(schema),
+        }
+    }
+
+    /// Whether serde serializes the model as its single field's value: a
+    /// newtype struct or `#[serde(transparent)]`.
+    pub fn is_transparent(&self) -> bool {
+        self.enum_info.is_none()
+            && self.fields.len() == 1
+            && (self.serde.transparent || self.is_tuple())
+    }
+
+    /// Whether the model is a tuple struct, serialized as an array.
+    pub fn is_tuple(&self) -> bool {
+        self.enum_info.is_none()
+            && !self.fields.is_empty()
+            && self.fields.iter().all(|f| f.name.starts_with('_') && f.name[1..].parse::<usize>().is_ok())
+    }
+
+    /// Every type string the model mentions, across fields and enum variants.
+    pub fn field_types(&self) -> Vec<&str> {
+        let mut types: Vec<&str> = self.fields.iter().map(|f| f.ty.as_str()).collect();
+        if let Some(enum_info) = &self.enum_info {
+            for variant in &enum_info.variants {
+                match &variant.kind {
+                    VariantKind::Unit => {}
+                    VariantKind::Newtype(ty) => types.push(ty),
+                    VariantKind::Tuple(tys) => types.extend(tys.iter().map(String::as_str)),
+                    VariantKind::Struct(fields) => types.extend(fields.iter().map(|f| f.ty.as_str())),
+                }
+            }
+        }
+        types
+    }
+
+    /// Concrete copy of a generic model named `name`, with `args` substituted
+    /// for its type parameters in every field and variant type.
+    pub fn instantiate(&self, name: &str, args: &[Type]) -> StructInfo {
+        let mut substitute = Substitute { params: &self.generics, args };
+        let mut instance = self.clone();
+        instance.name = name.to_string();
+        instance.generics = Vec::new();
+        for field in &mut instance.fields {
+            field.ty = substitute.apply(&field.ty);
+        }
+        if let Some(enum_info) = &mut instance.enum_info {
+            for variant in &mut enum_info.variants {
+                match &mut variant.kind {
+                    VariantKind::Unit => {}
+                    VariantKind::Newtype(ty) => *ty = substitute.apply(ty),
+                    VariantKind::Tuple(tys) => tys.iter_mut().for_each(|ty| *ty = substitute.apply(ty)),
+                    VariantKind::Struct(fields) => {
+                        fields.iter_mut().for_each(|field| field.ty = substitute.apply(&field.ty))
+                    }
+                }
+            }
+        }
+        instance
+    }
+}
+
+// Replaces bare type parameter paths (`T`) with the matching argument
+struct Substitute<'a> {
+    params: &'a [String],
+    args: &'a [Type],
+}
+
+impl Substitute<'_> {
+    fn apply(&mut self, ty: &str) -> String {
+        match syn::parse_str::<Type>(ty) {
+            Ok(mut parsed) => {
+                self.visit_type_mut(&mut parsed);
+                parsed.to_token_stream().to_string()
+            }
+            Err(_) => ty.to_string(),
+        }
+    }
+}
+
+impl VisitMut for Substitute<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(type_path) = ty {
+            if type_path.qself.is_none() {
+                let position = type_path.path.get_ident()
+                    .and_then(|ident| self.params.iter().position(|param| ident == param));
+                if let Some(arg) = position.and_then(|i| self.args.get(i)) {
+                    *ty = arg.clone();
+                    return;
+                }
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+}
+
+/// Parses every struct and enum in `file_content` into a map keyed by type name.
+pub fn parse_models(file_content: &str) -> HashMap<String, StructInfo> {
+    let ast = match parse_file(file_content) {
+        Ok(ast) => ast,
+        Err(e) => {
+            eprintln!("Warning: Failed to parse model file: {}", e);
+            return HashMap::new();
+        }
+    };
+    parse_model_items(&ast.items)
+}
+
+/// Collects every struct and enum among `items` into a map keyed by type name.
+pub(crate) fn parse_model_items(items: &[Item]) -> HashMap<String, StructInfo> {
+    let mut structs = HashMap::new();
+
+    for item in items {
+        match item {
+            Item::Struct(item_struct) => {
+                let attrs = ContainerAttrs::parse(&item_struct.attrs);
+                // Unit structs (Fields::Unit) 没有字段
+                structs.insert(
+                    item_struct.ident.to_string(),
+                    StructInfo {
+                        name: item_struct.ident.to_string(),
+                        generics: type_params(&item_struct.generics),
+                        fields: parse_fields(&item_struct.fields, attrs.rename_all.as_deref(), attrs.default),
+                        enum_info: None,
+                        serde: attrs,
+                        schema: None,
+                    },
+                );
+            }
+            Item::Enum(item_enum) => {
+                let attrs = ContainerAttrs::parse(&item_enum.attrs);
+                let tagging = match (attrs.untagged, attrs.tag.clone(), attrs.content.clone()) {
+                    (true, _, _) => EnumTagging::Untagged,
+                    (false, Some(tag), Some(content)) => EnumTagging::Adjacent { tag, content },
+                    (false, Some(tag), None) => EnumTagging::Internal { tag },
+                    (false, None, _) => EnumTagging::External,
+                };
+
+                let variants = item_enum.variants.iter()
+                    .map(|variant| {
+                        let mut serde = FieldAttrs::parse(&variant.attrs);
+                        if serde.rename.is_none() {
+                            serde.rename = attrs.rename_all.as_deref()
+                                .map(|rule| apply_rename_rule(rule, &variant.ident.to_string(), true));
+                        }
+                        let fields_rule = serde.rename_all.clone().or_else(|| attrs.rename_all_fields.clone());
+                        (variant, serde, fields_rule)
+                    })
+                    .filter(|(_, serde, _)| !serde.skip)
+                    .map(|(variant, serde, fields_rule)| VariantInfo {
+                        name: variant.ident.to_string(),
+                        serde,
+                        kind: match &variant.fields {
+                            syn::Fields::Unit => VariantKind::Unit,
+                            syn::Fields::Named(_) => VariantKind::Struct(parse_fields(&variant.fields, fields_rule.as_deref(), false)),
+                            syn::Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                                VariantKind::Newtype(unnamed.unnamed[0].ty.to_token_stream().to_string())
+                            }
+                            syn::Fields::Unnamed(unnamed) => VariantKind::Tuple(
+                                unnamed.unnamed.iter()
+                                    .map(|field| field.ty.to_token_stream().to_string())
+                                    .collect(),
+                            ),
+                        },
+                    })
+                    .collect();
+
+                structs.insert(
+                    item_enum.ident.to_string(),
+                    StructInfo {
+                        name: item_enum.ident.to_string(),
+                        generics: type_params(&item_enum.generics),
+                        fields: Vec::new(),
+                        enum_info: Some(EnumInfo { tagging, variants }),
+                        serde: attrs,
+                        schema: None,
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+    structs
+}
+
+fn type_params(generics: &syn::Generics) -> Vec<String> {
+    generics.type_params().map(|param| param.ident.to_string()).collect()
+}
+
+// `rename_all` and container-level `default` are folded into each field's attributes
+fn parse_fields(fields: &syn::Fields, rename_all: Option<&str>, container_default: bool) -> Vec<FieldInfo> {
+    let mut parsed = Vec::new();
+    if let syn::Fields::Named(named) = fields {
+        for field in &named.named {
+            let name = field.ident.as_ref()
+                .expect("Named fields always have identifiers")
+                .to_string()
+                .trim_start_matches("r#")
+                .to_string();
+            let ty_string = field.ty.to_token_stream().to_string();
+            let mut serde = FieldAttrs::parse(&field.attrs);
+            if serde.rename.is_none() {
+                serde.rename = rename_all.map(|rule| apply_rename_rule(rule, &name, false));
+            }
+            serde.default |= container_default;
+            parsed.push(FieldInfo { name, ty: ty_string, serde });
+        }
+    } else if let syn::Fields::Unnamed(unnamed) = fields {
+        // 处理元组结构体
+        for (index, field) in unnamed.unnamed.iter().enumerate() {
+            let ty_string = field.ty.to_token_stream().to_string();
+            parsed.push(FieldInfo {
+                name: format!("_{}", index),
+                ty: ty_string,
+                serde: FieldAttrs::parse(&field.attrs),
+            });
+        }
+    }
+    parsed
+}
+
+/// Whether a type string (as produced by `to_token_stream`) is an `Option`.
+pub(crate) fn is_option_type(ty: &str) -> bool {
+    let ty = ty.replace(' ', "");
+    ty.starts_with("Option<") || ty.starts_with("std::option::Option<") || ty.starts_with("core::option::Option<")
+}
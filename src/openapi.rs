@@ -0,0 +1,975 @@
+//! OpenAPI document assembly from discovered routes, handlers and models.
+
+use std::{collections::{BTreeMap, HashMap, HashSet}, fs, path::Path};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use syn::{GenericArgument, PathArguments, Type};
+
+use crate::error_registry::ResponseBody;
+use crate::handler::HandlerInfo;
+use crate::markdown::render_markdown;
+use crate::models::{FieldInfo, StructInfo};
+use crate::router::RouteInfo;
+use crate::schema::{generate_schemas, instantiate_generics, rust_type_to_openapi, schema_name};
+use crate::site::write_site;
+use crate::status::reason_phrase;
+use crate::version::{upgrade_to_3_1, OutputFormat, SpecVersion};
+
+// Precompiled regex for path parameter extraction
+static COLON_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#":([a-zA-Z0-9_]+)"#).unwrap()
+});
+
+static BRACE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\{([a-zA-Z0-9_]+)\}"#).unwrap()
+});
+
+/// A generated OpenAPI document.
+///
+/// Serializes to exactly the JSON the `axum_doc` binary writes, so it can be
+/// written out with `serde_json` or inspected field by field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenApi {
+    pub openapi: String,
+    /// JSON Schema dialect of the schemas; set for OpenAPI 3.1
+    #[serde(rename = "jsonSchemaDialect", default, skip_serializing_if = "Option::is_none")]
+    pub json_schema_dialect: Option<String>,
+    pub info: Info,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
+    pub paths: Map<String, Value>,
+    pub components: Components,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Info {
+    pub title: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "termsOfService", default, skip_serializing_if = "Option::is_none")]
+    pub terms_of_service: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact: Option<Contact>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<License>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Contact {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct License {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Server {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tag {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Components {
+    #[serde(default)]
+    pub schemas: Map<String, Value>,
+    #[serde(rename = "securitySchemes", default, skip_serializing_if = "Map::is_empty")]
+    pub security_schemes: Map<String, Value>,
+}
+
+impl OpenApi {
+    /// Returns the document as an untyped JSON value.
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).expect("OpenApi always serializes to JSON")
+    }
+
+    /// Renders the document as pretty-printed JSON.
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the document as YAML.
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    /// Renders the document in `format`. HTML is a directory of pages and
+    /// can only be written with [`OpenApi::write`].
+    pub fn render(&self, format: OutputFormat) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(match format {
+            OutputFormat::Json => self.to_json_pretty()?,
+            OutputFormat::Yaml => self.to_yaml()?,
+            OutputFormat::Markdown => render_markdown(self),
+            OutputFormat::Html => return Err("HTML output is a directory of pages, not a single document".into()),
+        })
+    }
+
+    /// Writes the document to `path` in `format`; HTML is written as a
+    /// documentation site in the directory `path`.
+    pub fn write(&self, format: OutputFormat, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        match format {
+            OutputFormat::Html => write_site(self, path),
+            _ => Ok(fs::write(path, self.render(format)?)?),
+        }
+    }
+
+    /// Converts a generated (3.0) document to `version`. Converting to 3.1
+    /// rewrites every schema to JSON Schema 2020-12: `nullable` becomes a
+    /// `"null"` type and `example` becomes `examples`.
+    pub fn convert_to(&mut self, version: SpecVersion) {
+        if version == SpecVersion::V3_1 && self.openapi.starts_with("3.0") {
+            upgrade_to_3_1(self);
+        }
+    }
+}
+
+/// Extract path parameters from route path (supports both :id and {id} styles)
+pub(crate) fn extract_path_params(route_path: &str) -> Vec<Value> {
+    let mut path_params = vec![];
+    let mut param_names = vec![];
+
+    // Support both /:id and /{id} styles
+    for cap in COLON_RE.captures_iter(route_path) {
+        param_names.push(cap[1].to_string());
+    }
+    for cap in BRACE_RE.captures_iter(route_path) {
+        param_names.push(cap[1].to_string());
+    }
+
+    for name in param_names {
+        path_params.push(json!({
+            "name": name,
+            "in": "path",
+            "required": true,
+            "schema": { "type": "string" }
+        }));
+    }
+
+    path_params
+}
+
+/// Process handler extractor parameters (Path, Query, Json, Form)
+pub(crate) fn process_handler_params(
+    handler: &HandlerInfo,
+    models: &HashMap<String, StructInfo>,
+) -> (Vec<Value>, Option<Value>) {
+    let mut parameters = vec![];
+    let mut request_body = None;
+
+    for extractor in &handler.params {
+        let type_name = schema_name(&extractor.inner_type.to_token_stream().to_string());
+
+        match extractor.kind.as_str() {
+            "Path" | "Query" => {
+                if let Some(struct_info) = models.get(&type_name) {
+                    let location = extractor.kind.to_lowercase();
+                    for field in parameter_fields(struct_info, models) {
+                        parameters.push(json!({
+                            "name": field.wire_name(),
+                            "in": location,
+                            // OpenAPI 要求路径参数必须为 required
+                            "required": location == "path" || (extractor.required && field.is_required()),
+                            "schema": rust_type_to_openapi(&field.ty, models)
+                        }));
+                    }
+                }
+            }
+            "Json" | "Form" => {
+                let media_type = if extractor.kind == "Form" { "application/x-www-form-urlencoded" } else { "application/json" };
+                // 与响应类型一样映射，Vec<User> 等非模型类型也有 schema
+                let schema = rust_type_to_openapi(&extractor.inner_type.to_token_stream().to_string(), models);
+                request_body = Some(body_json(media_type, schema, extractor.required));
+            }
+            "Header" | "Cookie" => {
+                if let Some(name) = &extractor.name {
+                    parameters.push(json!({
+                        "name": name,
+                        "in": extractor.kind.to_lowercase(),
+                        "required": extractor.required,
+                        "schema": rust_type_to_openapi(&extractor.inner_type.to_token_stream().to_string(), models)
+                    }));
+                }
+            }
+            "Bytes" => {
+                let schema = json!({"type": "string", "format": "binary"});
+                request_body = Some(body_json("application/octet-stream", schema, extractor.required));
+            }
+            "String" => {
+                request_body = Some(body_json("text/plain", json!({"type": "string"}), extractor.required));
+            }
+            "Multipart" => {
+                request_body = Some(body_json("multipart/form-data", json!({"type": "object"}), extractor.required));
+            }
+            _ => {}
+        }
+    }
+
+    (parameters, request_body)
+}
+
+fn body_json(media_type: &str, schema: Value, required: bool) -> Value {
+    json!({
+        "required": required,
+        "content": { media_type: { "schema": schema } }
+    })
+}
+
+// Fields a `Query`/`Path` struct binds, skipping `#[serde(skip)]` fields and
+// expanding `#[serde(flatten)]`ed structs into their own fields
+fn parameter_fields<'a>(info: &'a StructInfo, models: &'a HashMap<String, StructInfo>) -> Vec<&'a FieldInfo> {
+    let mut fields = Vec::new();
+    for field in &info.fields {
+        if field.serde.skip || field.serde.skip_deserializing {
+            continue;
+        }
+        match models.get(&schema_name(&field.ty)) {
+            Some(inner) if field.serde.flatten && inner.enum_info.is_none() => {
+                fields.extend(parameter_fields(inner, models));
+            }
+            _ => fields.push(field),
+        }
+    }
+    fields
+}
+
+/// Generate responses from the handler's return type and the status codes
+/// its body uses.
+///
+/// `Result<T, E>` is split into success responses built from `T` and error
+/// responses built from `E`. Success codes default to `200`; error codes
+/// without an explicit status in the body are documented as `default`.
+pub(crate) fn generate_response(
+    handler: &HandlerInfo,
+    models: &HashMap<String, StructInfo>,
+) -> Value {
+    let mut responses = Map::new();
+
+    let unit: Type = syn::parse_quote!(());
+    let return_type = handler.return_type.as_ref().unwrap_or(&unit);
+    let (success_type, error_type) = split_result(return_type);
+
+    let success_body = response_body(success_type, models);
+    let success_codes = if let Some(status) = handler.overrides.status {
+        vec![status]
+    } else if handler.success_statuses.is_empty() {
+        vec![200]
+    } else {
+        handler.success_statuses.clone()
+    };
+    for code in success_codes {
+        responses.insert(code.to_string(), response_object(&reason_phrase(code), &success_body));
+    }
+
+    let error_body = error_type.and_then(|ty| response_body(ty, models));
+    for code in &handler.error_statuses {
+        responses.insert(code.to_string(), response_object(&reason_phrase(*code), &error_body));
+    }
+    // 错误类型 IntoResponse 实现中的响应，不覆盖已有响应体的状态码
+    for response in &handler.error_responses {
+        let key = response.status.to_string();
+        if responses.get(&key).is_some_and(|existing| existing.get("content").is_some()) {
+            continue;
+        }
+        let body = error_response_body(&response.body, models);
+        responses.insert(key, response_object(&reason_phrase(response.status), &body));
+    }
+    if error_type.is_some() && handler.error_statuses.is_empty() && handler.error_responses.is_empty() {
+        responses.insert("default".to_string(), response_object("Error response", &error_body));
+    }
+
+    // #[axum_doc(response(..))] 覆盖推断结果；未指定响应体时保留已推断的响应体
+    for response in &handler.overrides.responses {
+        let key = response.status.to_string();
+        let description = response.description.clone().unwrap_or_else(|| reason_phrase(response.status));
+        let body = response.body.as_ref()
+            .map(|ty| ("application/json", rust_type_to_openapi(ty, models)));
+        match (body, responses.get_mut(&key)) {
+            (None, Some(existing)) => existing["description"] = json!(description),
+            (body, _) => {
+                responses.insert(key, response_object(&description, &body));
+            }
+        }
+    }
+
+    Value::Object(responses)
+}
+
+/// `(T, Some(E))` for `Result<T, E>`, otherwise `(ty, None)`.
+pub(crate) fn split_result(ty: &Type) -> (&Type, Option<&Type>) {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Result" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    let mut types = args.args.iter().filter_map(|arg| match arg {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    });
+                    if let (Some(ok), Some(err)) = (types.next(), types.next()) {
+                        return (ok, Some(err));
+                    }
+                }
+            }
+        }
+    }
+    (ty, None)
+}
+
+// Media type and schema a response type serializes to; `None` when it
+// carries no body we can describe (`()`, `StatusCode`, `impl IntoResponse`, ..)
+fn response_body(ty: &Type, models: &HashMap<String, StructInfo>) -> Option<(&'static str, Value)> {
+    match ty {
+        Type::Reference(reference) => response_body(&reference.elem, models),
+        Type::Paren(paren) => response_body(&paren.elem, models),
+        // (StatusCode, HeaderMap, Json<T>): the body is the last element
+        Type::Tuple(tuple) => tuple.elems.last().and_then(|last| response_body(last, models)),
+        Type::ImplTrait(_) => None,
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            let inner = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                }),
+                _ => None,
+            };
+            match (segment.ident.to_string().as_str(), inner) {
+                ("Json", Some(inner)) => {
+                    let inner = inner.to_token_stream().to_string();
+                    Some(("application/json", rust_type_to_openapi(&inner, models)))
+                }
+                ("Html", _) => Some(("text/html", json!({"type": "string"}))),
+                ("String" | "str" | "Cow", _) => Some(("text/plain", json!({"type": "string"}))),
+                ("Bytes", _) => Some(("application/octet-stream", json!({"type": "string", "format": "binary"}))),
+                ("Vec", Some(Type::Path(inner))) if inner.path.is_ident("u8") => {
+                    Some(("application/octet-stream", json!({"type": "string", "format": "binary"})))
+                }
+                ("StatusCode" | "Response" | "Redirect" | "HeaderMap" | "Body" | "Sse", _) => None,
+                // 其他 IntoResponse 类型只有是已知模型时才能描述响应体
+                _ => {
+                    let type_str = ty.to_token_stream().to_string();
+                    models.contains_key(&schema_name(&type_str))
+                        .then(|| ("application/json", rust_type_to_openapi(&type_str, models)))
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
+fn error_response_body(body: &ResponseBody, models: &HashMap<String, StructInfo>) -> Option<(&'static str, Value)> {
+    match body {
+        ResponseBody::None => None,
+        ResponseBody::Json(ty) => Some(("application/json", rust_type_to_openapi(ty, models))),
+        ResponseBody::JsonValue => Some(("application/json", json!({"type": "object"}))),
+        ResponseBody::Text => Some(("text/plain", json!({"type": "string"}))),
+    }
+}
+
+fn response_object(description: &str, body: &Option<(&'static str, Value)>) -> Value {
+    let mut response = json!({ "description": description });
+    if let Some((media_type, schema)) = body {
+        response["content"] = json!({ *media_type: { "schema": schema } });
+    }
+    response
+}
+
+/// Build OpenAPI operation object for a route
+pub(crate) fn build_operation(
+    route: &RouteInfo,
+    handler: &HandlerInfo,
+    models: &HashMap<String, StructInfo>,
+) -> Value {
+    // Extract path parameters automatically
+    let path_params = extract_path_params(&route.path);
+
+    // Process handler parameters
+    let (mut parameters, request_body) = process_handler_params(handler, models);
+
+    // Generate response
+    let responses = generate_response(handler, models);
+
+    // Merge path params and extractor params, avoiding duplicates
+    let existing_names: std::collections::HashSet<String> = parameters
+        .iter()
+        .filter_map(|p| p.get("name").and_then(|n| n.as_str()).map(|s| s.to_string()))
+        .collect();
+
+    for p in path_params {
+        if let Some(name) = p.get("name").and_then(|n| n.as_str()) {
+            if !existing_names.contains(name) {
+                parameters.push(p);
+            }
+        }
+    }
+
+    let overrides = &handler.overrides;
+
+    // Use summary from doc comments if available
+    let summary = overrides.summary.as_ref()
+        .or(handler.summary.as_ref())
+        .cloned()
+        .unwrap_or_else(|| format!("{} {}", route.method.to_uppercase(), route.handler));
+
+    // Build operation object
+    let mut operation = json!({
+        "summary": summary,
+        "operationId": overrides.operation_id.as_ref().unwrap_or(&route.handler),
+        "responses": responses
+    });
+
+    // Add description if exists
+    if let Some(description) = overrides.description.as_ref().or(handler.description.as_ref()) {
+        operation["description"] = json!(description);
+    }
+
+    // Add parameters if not empty
+    if !parameters.is_empty() {
+        operation["parameters"] = json!(parameters);
+    }
+
+    // Add request body if exists
+    if let Some(rb) = request_body {
+        operation["requestBody"] = rb;
+    }
+
+    // Add tags for grouping
+    if !overrides.tags.is_empty() {
+        operation["tags"] = json!(overrides.tags);
+    } else if let Some(module_path) = &route.module {
+        operation["tags"] = json!([module_path.join("::")]);
+    }
+
+    if overrides.deprecated {
+        operation["deprecated"] = json!(true);
+    }
+
+    if !overrides.security.is_empty() {
+        let mut requirements: Vec<Value> = overrides.security.iter()
+            .map(|scheme| json!({ scheme: [] }))
+            .collect();
+        // 可选认证：空的安全需求表示也可以不认证
+        if overrides.security_optional {
+            requirements.push(json!({}));
+        }
+        operation["security"] = json!(requirements);
+    }
+
+    operation
+}
+
+/// Operation ids for handlers sharing a bare name, keyed by handler id: the
+/// shortest trailing part of each crate path that tells them apart, e.g.
+/// `users_list` and `orders_list` for `api::users::list` and `api::orders::list`.
+fn qualified_operation_ids(routes: &[RouteInfo]) -> HashMap<String, String> {
+    let mut by_name: BTreeMap<&str, Vec<(String, Vec<String>)>> = BTreeMap::new();
+    for route in routes.iter().filter(|route| !route.service) {
+        let handlers = by_name.entry(route.handler.as_str()).or_default();
+        let id = route.handler_id();
+        if !handlers.iter().any(|(existing, _)| *existing == id) {
+            let path = route.handler_path.clone().unwrap_or_else(|| vec![route.handler.clone()]);
+            handlers.push((id, path));
+        }
+    }
+
+    let mut ids = HashMap::new();
+    for handlers in by_name.into_values().filter(|handlers| handlers.len() > 1) {
+        for (id, path) in &handlers {
+            let suffix = |path: &[String], len: usize| path[path.len().saturating_sub(len)..].to_vec();
+            let len = (2..path.len())
+                .find(|&len| handlers.iter().all(|(other, other_path)| other == id || suffix(other_path, len) != suffix(path, len)))
+                .unwrap_or(path.len());
+            ids.insert(id.clone(), suffix(path, len).join("_"));
+        }
+    }
+    ids
+}
+
+// `id`, or `id_2`, `id_3`, ... when an earlier operation already uses it
+fn unique_operation_id(id: &str, used: &mut HashSet<String>) -> String {
+    let unique = (1..)
+        .map(|n| if n == 1 { id.to_string() } else { format!("{}_{}", id, n) })
+        .find(|candidate| !used.contains(candidate))
+        .expect("some suffix is free");
+    used.insert(unique.clone());
+    unique
+}
+
+// A tower service has no handler signature, so only its route is documented
+fn service_operation(route: &RouteInfo) -> Value {
+    let mut operation = json!({
+        "summary": format!("{} {}", route.method.to_uppercase(), route.handler),
+        "responses": { "200": { "description": format!("Response from {}", route.handler) } }
+    });
+    if let Some(module_path) = &route.module {
+        operation["tags"] = json!([module_path.join("::")]);
+    }
+    operation
+}
+
+fn insert_operation(paths: &mut Map<String, Value>, route: &RouteInfo, operation: Value) {
+    let path_entry = paths
+        .entry(route.path.clone())
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .expect("path entries should always be objects");
+
+    path_entry.insert(
+        route.method.to_lowercase(),
+        operation,
+    );
+}
+
+// Definitions for the security scheme names commonly used in annotations
+fn security_scheme(name: &str) -> Option<Value> {
+    match name {
+        "bearer" => Some(json!({"type": "http", "scheme": "bearer"})),
+        "basic" => Some(json!({"type": "http", "scheme": "basic"})),
+        "api_key" => Some(json!({"type": "apiKey", "in": "header", "name": "X-API-Key"})),
+        _ => None,
+    }
+}
+
+/// Assembles the OpenAPI document for `routes`, looking each handler up by
+/// its resolved crate path (`RouteInfo::handler_id`). Operation ids are
+/// unique across the document: a handler mounted at several paths gets
+/// `_2`, `_3`, ... appended after its first operation.
+pub fn generate_openapi(
+    routes: &[RouteInfo],
+    handlers: &HashMap<String, HandlerInfo>,
+    models: &HashMap<String, StructInfo>,
+) -> OpenApi {
+    let mut paths = Map::new();
+
+    // Concrete models for every generic model instantiation the handlers use
+    let handler_types = handlers.values().flat_map(|handler| {
+        handler.params.iter()
+            .map(|param| &param.inner_type)
+            .chain(handler.return_type.as_ref())
+            .map(|ty| ty.to_token_stream().to_string())
+            .chain(handler.error_responses.iter().filter_map(|response| match &response.body {
+                ResponseBody::Json(ty) => Some(ty.clone()),
+                _ => None,
+            }))
+            .chain(handler.overrides.responses.iter().filter_map(|response| response.body.clone()))
+    });
+    let models = &instantiate_generics(handler_types, models);
+
+    // Generate schema definitions
+    let schemas = match generate_schemas(models) {
+        Value::Object(schemas) => schemas,
+        _ => Map::new(),
+    };
+
+    let mut security_schemes = Map::new();
+
+    let operation_ids = qualified_operation_ids(routes);
+    // 同一路径下以多个方法注册的 handler，如 on(PUT.or(PATCH), h)
+    let mut method_counts: HashMap<(&str, String), usize> = HashMap::new();
+    for route in routes.iter().filter(|route| !route.service) {
+        *method_counts.entry((route.path.as_str(), route.handler_id())).or_default() += 1;
+    }
+    let mut used_ids = HashSet::new();
+
+    // Generate path definitions for each route
+    for route in routes {
+        let handler_id = route.handler_id();
+        if route.service {
+            let mut operation = service_operation(route);
+            if route.any_method {
+                operation["x-any-method"] = json!(true);
+            }
+            insert_operation(&mut paths, route, operation);
+        } else if let Some(handler) = handlers.get(&handler_id) {
+            if handler.overrides.hidden {
+                continue;
+            }
+            for scheme in &handler.overrides.security {
+                if !security_schemes.contains_key(scheme) {
+                    match security_scheme(scheme) {
+                        Some(definition) => {
+                            security_schemes.insert(scheme.clone(), definition);
+                        }
+                        None => eprintln!("Warning: Security scheme '{}' used by '{}' has no known definition",
+                                         scheme, route.handler),
+                    }
+                }
+            }
+
+            // Build operation for this route
+            let mut operation = build_operation(route, handler, models);
+            if let (None, Some(id)) = (&handler.overrides.operation_id, operation_ids.get(&handler_id)) {
+                operation["operationId"] = json!(id);
+            }
+            // 处理函数出现在多个方法下时，operationId 需按方法区分
+            if route.any_method || method_counts[&(route.path.as_str(), handler_id.clone())] > 1 {
+                if let Some(id) = operation["operationId"].as_str() {
+                    operation["operationId"] = json!(format!("{}_{}", id, route.method.to_lowercase()));
+                }
+            }
+            if route.any_method {
+                operation["x-any-method"] = json!(true);
+            }
+            // 同一 handler 挂载在多个路径下时，后出现的加序号
+            if let Some(id) = operation["operationId"].as_str() {
+                operation["operationId"] = json!(unique_operation_id(id, &mut used_ids));
+            }
+            insert_operation(&mut paths, route, operation);
+        } else {
+            eprintln!("Warning: Route '{}' has no parsed handler '{}', skipping", route.path, route.handler);
+        }
+    }
+
+    OpenApi {
+        openapi: SpecVersion::V3_0.to_string(),
+        json_schema_dialect: None,
+        info: Info {
+            title: "Generated API".to_string(),
+            version: "1.0.0".to_string(),
+            description: Some("Auto-generated OpenAPI specification from Axum routes".to_string()),
+            terms_of_service: None,
+            contact: None,
+            license: None,
+        },
+        servers: Vec::new(),
+        paths,
+        components: Components { schemas, security_schemes },
+        tags: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // P1-2: Helper function tests
+    #[test]
+    fn test_extract_path_params_colon_style() {
+        let params = extract_path_params("/users/:id");
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0]["name"], "id");
+        assert_eq!(params[0]["in"], "path");
+        assert_eq!(params[0]["required"], true);
+    }
+
+    #[test]
+    fn test_extract_path_params_brace_style() {
+        let params = extract_path_params("/users/{id}");
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0]["name"], "id");
+        assert_eq!(params[0]["in"], "path");
+        assert_eq!(params[0]["required"], true);
+    }
+
+    #[test]
+    fn test_extract_path_params_multiple() {
+        let params = extract_path_params("/users/:user_id/posts/:post_id");
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0]["name"], "user_id");
+        assert_eq!(params[1]["name"], "post_id");
+    }
+
+    #[test]
+    fn test_extract_path_params_mixed_styles() {
+        let params = extract_path_params("/users/:id/posts/{post_id}");
+        assert_eq!(params.len(), 2);
+    }
+
+    #[test]
+    fn test_extract_path_params_none() {
+        let params = extract_path_params("/users/all");
+        assert_eq!(params.len(), 0);
+    }
+
+    fn handler_with(kind: &str, inner_type: syn::Type) -> HandlerInfo {
+        HandlerInfo {
+            params: vec![crate::handler::Extractor { kind: kind.to_string(), inner_type, required: true, name: None }],
+            return_type: None,
+            summary: None,
+            description: None,
+            success_statuses: Vec::new(),
+            error_statuses: Vec::new(),
+            error_responses: Vec::new(),
+            overrides: Default::default(),
+            unknown_extractors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_process_handler_params_query_required() {
+        let models = crate::models::parse_models(r#"
+            #[serde(rename_all = "camelCase")]
+            struct Filter {
+                page_size: u32,
+                cursor: Option<String>,
+                #[serde(default)]
+                sort: String,
+                #[serde(skip)]
+                internal: bool,
+                #[serde(flatten)]
+                range: Range,
+            }
+            struct Range { from: u64 }
+        "#);
+        let (params, body) = process_handler_params(&handler_with("Query", syn::parse_quote!(Filter)), &models);
+        assert!(body.is_none());
+
+        let names: Vec<&str> = params.iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["pageSize", "cursor", "sort", "from"]);
+        let required: Vec<bool> = params.iter().map(|p| p["required"].as_bool().unwrap()).collect();
+        assert_eq!(required, vec![true, false, false, true]);
+        assert!(params.iter().all(|p| p["in"] == "query"));
+    }
+
+    #[test]
+    fn test_process_handler_params_path_always_required() {
+        let models = crate::models::parse_models("struct Ids { id: Option<u64> }");
+        let (params, _) = process_handler_params(&handler_with("Path", syn::parse_quote!(Ids)), &models);
+        assert_eq!(params[0]["in"], "path");
+        assert_eq!(params[0]["required"], true);
+    }
+
+    #[test]
+    fn test_process_handler_params_json_body_required() {
+        let models = crate::models::parse_models("struct Login { user: String }");
+        let (_, body) = process_handler_params(&handler_with("Json", syn::parse_quote!(Login)), &models);
+        let body = body.unwrap();
+        assert_eq!(body["required"], true);
+        assert_eq!(body["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Login");
+    }
+
+    #[test]
+    fn test_process_handler_params_json_body_non_model() {
+        let models = crate::models::parse_models("struct User { id: u64 }");
+        let (_, body) = process_handler_params(&handler_with("Json", syn::parse_quote!(Vec<User>)), &models);
+        let schema = &body.unwrap()["content"]["application/json"]["schema"];
+        assert_eq!(schema["type"], "array");
+        assert_eq!(schema["items"]["$ref"], "#/components/schemas/User");
+
+        let (_, body) = process_handler_params(&handler_with("Json", syn::parse_quote!(HashMap<String, u32>)), &models);
+        let schema = &body.unwrap()["content"]["application/json"]["schema"];
+        assert_eq!(schema["additionalProperties"]["format"], "int32");
+    }
+
+    #[test]
+    fn test_generate_openapi_generic_models() {
+        let models = crate::models::parse_models(r#"
+            struct Page<T> { items: Vec<T>, total: u64 }
+            struct User { id: u64 }
+            struct Filter { q: String }
+        "#);
+        let mut handler = handler_with("Query", syn::parse_quote!(Page<Filter>));
+        handler.return_type = Some(syn::parse_quote!(Json<Page<User>>));
+        let handlers = HashMap::from([("list".to_string(), handler)]);
+        let routes = vec![RouteInfo {
+            path: "/users".to_string(),
+            method: "GET".to_string(),
+            handler: "list".to_string(),
+            module: None,
+            service: false,
+            handler_path: None,
+            any_method: false,
+        }];
+
+        let spec = generate_openapi(&routes, &handlers, &models).to_value();
+        let operation = &spec["paths"]["/users"]["get"];
+        assert_eq!(operation["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+                   "#/components/schemas/Page_User");
+        let names: Vec<&str> = operation["parameters"].as_array().unwrap().iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["items", "total"]);
+
+        let schemas = &spec["components"]["schemas"];
+        assert!(schemas.get("Page").is_none());
+        assert_eq!(schemas["Page_User"]["properties"]["items"]["items"]["$ref"], "#/components/schemas/User");
+        assert_eq!(schemas["Page_Filter"]["properties"]["items"]["items"]["$ref"], "#/components/schemas/Filter");
+    }
+
+    fn responses_for(code: &str, models: &HashMap<String, StructInfo>) -> Value {
+        let handler = crate::handler::parse_handler(code, "handler").unwrap();
+        generate_response(&handler, models)
+    }
+
+    #[test]
+    fn test_generate_response_result() {
+        let models = crate::models::parse_models("struct User { id: u64 } struct ApiError { message: String }");
+
+        let responses = responses_for(r#"
+            async fn handler() -> Result<Json<User>, Json<ApiError>> { todo!() }
+        "#, &models);
+        assert_eq!(responses["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/User");
+        assert_eq!(responses["default"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/ApiError");
+
+        let responses = responses_for(r#"
+            async fn handler() -> Result<(StatusCode, Json<User>), (StatusCode, Json<ApiError>)> {
+                let user = find().ok_or((StatusCode::NOT_FOUND, Json(ApiError::new())))?;
+                Ok((StatusCode::CREATED, Json(user)))
+            }
+        "#, &models);
+        let codes: Vec<&String> = responses.as_object().unwrap().keys().collect();
+        assert_eq!(codes, vec!["201", "404"]);
+        assert_eq!(responses["201"]["description"], "Created");
+        assert_eq!(responses["201"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/User");
+        assert_eq!(responses["404"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/ApiError");
+    }
+
+    #[test]
+    fn test_generate_response_bodyless() {
+        let models = HashMap::new();
+
+        let responses = responses_for("async fn handler() -> StatusCode { StatusCode::NO_CONTENT }", &models);
+        assert_eq!(responses, json!({"204": {"description": "No Content"}}));
+
+        let responses = responses_for("async fn handler() {}", &models);
+        assert_eq!(responses, json!({"200": {"description": "OK"}}));
+
+        let responses = responses_for("async fn handler() -> impl IntoResponse { Redirect::to(\"/\") }", &models);
+        assert_eq!(responses, json!({"200": {"description": "OK"}}));
+
+        let responses = responses_for("async fn handler() -> Result<(), AppError> { Ok(()) }", &models);
+        assert_eq!(responses, json!({"200": {"description": "OK"}, "default": {"description": "Error response"}}));
+
+        let responses = responses_for("async fn handler() -> Html<String> { todo!() }", &models);
+        assert_eq!(responses["200"]["content"]["text/html"]["schema"]["type"], "string");
+    }
+
+    #[test]
+    fn test_build_operation_overrides() {
+        let models = crate::models::parse_models("struct User { id: u64 } struct Problem { detail: String }");
+        let handler = crate::handler::parse_handler(r#"
+            /// Create a user
+            #[axum_doc(status = 201, response(400, Problem, description = "Invalid user"), response(500))]
+            async fn create(Json(user): Json<User>) -> Json<User> { todo!() }
+        "#, "create").unwrap();
+        let route = RouteInfo {
+            path: "/users".to_string(),
+            method: "POST".to_string(),
+            handler: "create".to_string(),
+            module: None,
+            service: false,
+            handler_path: None,
+            any_method: false,
+        };
+
+        let operation = build_operation(&route, &handler, &models);
+        let responses = &operation["responses"];
+        assert!(responses.get("200").is_none());
+        assert_eq!(responses["201"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/User");
+        assert_eq!(responses["400"]["description"], "Invalid user");
+        assert_eq!(responses["400"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Problem");
+        assert_eq!(responses["500"], json!({"description": "Internal Server Error"}));
+        assert_eq!(operation["operationId"], "create");
+        assert!(operation.get("deprecated").is_none());
+    }
+
+    #[test]
+    fn test_generate_openapi_service_route() {
+        let routes = vec![RouteInfo {
+            path: "/assets".to_string(),
+            method: "get".to_string(),
+            handler: "ServeDir".to_string(),
+            module: None,
+            service: true,
+            handler_path: None,
+            any_method: false,
+        }];
+
+        let spec = generate_openapi(&routes, &HashMap::new(), &HashMap::new()).to_value();
+        let operation = &spec["paths"]["/assets"]["get"];
+        assert_eq!(operation["summary"], "GET ServeDir");
+        assert_eq!(operation["responses"]["200"]["description"], "Response from ServeDir");
+        assert!(operation.get("operationId").is_none());
+    }
+
+    #[test]
+    fn test_process_handler_params_body_media_types() {
+        let models = crate::models::parse_models("struct Signup { email: String }");
+        let mut handler = handler_with("Form", syn::parse_quote!(Signup));
+        handler.params[0].required = false;
+        let (_, body) = process_handler_params(&handler, &models);
+        let body = body.unwrap();
+        assert_eq!(body["required"], false);
+        assert_eq!(body["content"]["application/x-www-form-urlencoded"]["schema"]["$ref"], "#/components/schemas/Signup");
+
+        let handler = handler_with("Bytes", syn::parse_quote!(Bytes));
+        let (_, body) = process_handler_params(&handler, &models);
+        assert_eq!(body.unwrap()["content"]["application/octet-stream"]["schema"]["format"], "binary");
+    }
+
+    #[test]
+    fn test_process_handler_params_cookie() {
+        let mut handler = handler_with("Cookie", syn::parse_quote!(String));
+        handler.params[0].name = Some("session".to_string());
+        handler.params[0].required = false;
+        let (params, body) = process_handler_params(&handler, &HashMap::new());
+        assert!(body.is_none());
+        assert_eq!(params[0]["name"], "session");
+        assert_eq!(params[0]["in"], "cookie");
+        assert_eq!(params[0]["required"], false);
+        assert_eq!(params[0]["schema"]["type"], "string");
+    }
+
+    #[test]
+    fn test_build_operation_optional_security() {
+        let handler = crate::handler::parse_handler(r#"
+            async fn feed(auth: Option<TypedHeader<Authorization<Bearer>>>) -> Json<Feed> { todo!() }
+        "#, "feed").unwrap();
+        let route = RouteInfo {
+            path: "/feed".to_string(),
+            method: "GET".to_string(),
+            handler: "feed".to_string(),
+            module: None,
+            service: false,
+            handler_path: None,
+            any_method: false,
+        };
+        let operation = build_operation(&route, &handler, &HashMap::new());
+        assert_eq!(operation["security"], json!([{"bearer": []}, {}]));
+    }
+
+    #[test]
+    fn test_generate_openapi_shared_handler_ids() {
+        let handler = crate::handler::parse_handler("async fn show(Path(id): Path<u64>) -> String { todo!() }", "show").unwrap();
+        let route = |path: &str| RouteInfo {
+            path: path.to_string(),
+            method: "get".to_string(),
+            handler: "show".to_string(),
+            module: None,
+            service: false,
+            handler_path: None,
+            any_method: false,
+        };
+        let routes = vec![route("/users/:id"), route("/v2/users/:id")];
+        let handlers = HashMap::from([("show".to_string(), handler)]);
+
+        let spec = generate_openapi(&routes, &handlers, &HashMap::new()).to_value();
+        assert_eq!(spec["paths"]["/users/:id"]["get"]["operationId"], "show");
+        assert_eq!(spec["paths"]["/v2/users/:id"]["get"]["operationId"], "show_2");
+    }
+}
@@ -0,0 +1,563 @@
+//! Reader's view of a generated document, shared by the documentation
+//! renderers: operations grouped by tag, type names that link to schemas,
+//! property tables and example values.
+
+use std::collections::HashSet;
+use serde_json::{json, Map, Value};
+
+use crate::openapi::OpenApi;
+
+/// Section of the operations that have no tag.
+pub(crate) const UNTAGGED: &str = "default";
+
+// Methods in the order operations are listed under a path
+const METHODS: [&str; 8] = ["get", "post", "put", "patch", "delete", "head", "options", "trace"];
+
+// How deep examples expand nested schemas before stopping
+const MAX_EXAMPLE_DEPTH: usize = 8;
+
+/// Operations sharing a tag.
+pub(crate) struct Section<'a> {
+    pub name: String,
+    /// File-name and anchor friendly form of the tag, unique in the document
+    pub slug: String,
+    pub description: Option<&'a str>,
+    pub operations: Vec<Operation<'a>>,
+}
+
+/// An operation and where it is mounted.
+pub(crate) struct Operation<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub value: &'a Value,
+}
+
+pub(crate) struct Parameter<'a> {
+    pub name: &'a str,
+    pub location: &'a str,
+    pub required: bool,
+    pub schema: &'a Value,
+    pub description: Option<&'a str>,
+}
+
+/// A request or response body in one media type.
+pub(crate) struct Content<'a> {
+    pub media_type: &'a str,
+    pub schema: &'a Value,
+}
+
+pub(crate) struct Response<'a> {
+    pub status: &'a str,
+    pub description: &'a str,
+    pub content: Vec<Content<'a>>,
+}
+
+/// A property of an object schema.
+pub(crate) struct Property<'a> {
+    pub name: &'a str,
+    pub schema: &'a Value,
+    pub required: bool,
+}
+
+/// Detail listed next to a parameter or property.
+pub(crate) enum Note<'a> {
+    Text(&'a str),
+    /// Allowed values, as JSON
+    Values(Vec<String>),
+    /// Default value, as JSON
+    Default(String),
+    Deprecated,
+}
+
+/// How a component schema is presented.
+pub(crate) enum Shape<'a> {
+    /// Properties, plus the schemas whose fields are included (`allOf`)
+    Object { properties: Vec<Property<'a>>, includes: Vec<&'a str> },
+    /// Enumerated values
+    Enum(&'a [Value]),
+    /// Alternatives (`oneOf`/`anyOf`), with the property telling them apart
+    Variants { variants: Vec<&'a Value>, discriminator: Option<&'a str> },
+    /// Anything else, described by its type alone
+    Other,
+}
+
+/// The type of a schema, with references to component schemas kept apart so
+/// renderers can link them.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TypeName {
+    Schema(String),
+    Primitive(String),
+    Array(Box<TypeName>),
+    Map(Box<TypeName>),
+    Union(Vec<TypeName>),
+}
+
+impl<'a> Section<'a> {
+    fn new(name: &str, description: Option<&'a str>) -> Section<'a> {
+        Section { name: name.to_string(), slug: String::new(), description, operations: Vec::new() }
+    }
+}
+
+impl<'a> Operation<'a> {
+    pub fn anchor(&self) -> String {
+        match self.str_field("operationId") {
+            Some(id) => slug(id),
+            None => slug(&format!("{} {}", self.method, self.path)),
+        }
+    }
+
+    pub fn str_field(&self, key: &str) -> Option<&'a str> {
+        self.value.get(key).and_then(Value::as_str)
+    }
+
+    pub fn deprecated(&self) -> bool {
+        self.value.get("deprecated") == Some(&Value::Bool(true))
+    }
+
+    pub fn parameters(&self) -> Vec<Parameter<'a>> {
+        let Some(parameters) = self.value.get("parameters").and_then(Value::as_array) else { return Vec::new() };
+        parameters.iter()
+            .map(|parameter| Parameter {
+                name: parameter.get("name").and_then(Value::as_str).unwrap_or_default(),
+                location: parameter.get("in").and_then(Value::as_str).unwrap_or_default(),
+                required: parameter.get("required") == Some(&Value::Bool(true)),
+                schema: parameter.get("schema").unwrap_or(&Value::Null),
+                description: parameter.get("description").and_then(Value::as_str),
+            })
+            .collect()
+    }
+
+    pub fn request_body(&self) -> Vec<Content<'a>> {
+        self.value.get("requestBody").map(contents).unwrap_or_default()
+    }
+
+    /// Schemas of the JSON responses, each with the statuses that return it.
+    pub fn response_examples(&self) -> Vec<(Vec<&'a str>, &'a Value)> {
+        // 响应体相同的状态码共用一个示例
+        let mut examples: Vec<(Vec<&str>, &Value)> = Vec::new();
+        for response in self.responses() {
+            for content in response.content.iter().filter(|content| is_json(content.media_type)) {
+                match examples.iter_mut().find(|(_, schema)| *schema == content.schema) {
+                    Some((statuses, _)) => statuses.push(response.status),
+                    None => examples.push((vec![response.status], content.schema)),
+                }
+            }
+        }
+        examples
+    }
+
+    pub fn responses(&self) -> Vec<Response<'a>> {
+        let Some(responses) = self.value.get("responses").and_then(Value::as_object) else { return Vec::new() };
+        responses.iter()
+            .map(|(status, response)| Response {
+                status,
+                description: response.get("description").and_then(Value::as_str).unwrap_or_default(),
+                content: contents(response),
+            })
+            .collect()
+    }
+}
+
+fn contents(holder: &Value) -> Vec<Content<'_>> {
+    let Some(content) = holder.get("content").and_then(Value::as_object) else { return Vec::new() };
+    content.iter()
+        .map(|(media_type, media)| Content { media_type, schema: media.get("schema").unwrap_or(&Value::Null) })
+        .collect()
+}
+
+pub(crate) fn is_json(media_type: &str) -> bool {
+    media_type == "application/json" || media_type.ends_with("+json")
+}
+
+/// Description, allowed values, default and deprecation of a parameter or
+/// property; `description` takes precedence over the schema's own.
+pub(crate) fn notes<'a>(schema: &'a Value, description: Option<&'a str>) -> Vec<Note<'a>> {
+    let mut notes = Vec::new();
+    if let Some(description) = description.or_else(|| schema.get("description").and_then(Value::as_str)) {
+        notes.push(Note::Text(description));
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        notes.push(Note::Values(values.iter().map(Value::to_string).collect()));
+    }
+    if let Some(default) = schema.get("default") {
+        notes.push(Note::Default(default.to_string()));
+    }
+    if schema.get("deprecated") == Some(&Value::Bool(true)) {
+        notes.push(Note::Deprecated);
+    }
+    notes
+}
+
+/// Operations grouped by tag: the document's declared tags first, then the
+/// remaining ones alphabetically, then untagged operations. An operation
+/// with several tags is listed under each.
+pub(crate) fn sections(spec: &OpenApi) -> Vec<Section<'_>> {
+    let mut sections: Vec<Section> = spec.tags.iter()
+        .map(|tag| Section::new(&tag.name, tag.description.as_deref()))
+        .collect();
+    let mut undeclared: Vec<Section> = Vec::new();
+    let mut untagged = Section::new(UNTAGGED, None);
+
+    for (path, item) in &spec.paths {
+        for method in METHODS {
+            let Some(value) = item.get(method) else { continue };
+            let tags: Vec<&str> = value.get("tags").and_then(Value::as_array)
+                .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            if tags.is_empty() {
+                untagged.operations.push(Operation { method, path, value });
+            }
+            for tag in tags {
+                let section = match sections.iter().position(|section| section.name == tag) {
+                    Some(index) => &mut sections[index],
+                    None => match undeclared.iter().position(|section| section.name == tag) {
+                        Some(index) => &mut undeclared[index],
+                        None => {
+                            undeclared.push(Section::new(tag, None));
+                            undeclared.last_mut().expect("just pushed")
+                        }
+                    },
+                };
+                section.operations.push(Operation { method, path, value });
+            }
+        }
+    }
+
+    undeclared.sort_by(|a, b| a.name.cmp(&b.name));
+    sections.extend(undeclared);
+    sections.push(untagged);
+    sections.retain(|section| !section.operations.is_empty());
+
+    // Tags that differ only in punctuation or case share a slug; later ones
+    // get `-2`, `-3`, ... so their pages and anchors stay apart
+    let mut used = HashSet::new();
+    for section in &mut sections {
+        let base = match slug(&section.name) {
+            base if base.is_empty() => "section".to_string(),
+            base => base,
+        };
+        let mut candidate = base.clone();
+        let mut n = 2;
+        while !used.insert(candidate.clone()) {
+            candidate = format!("{}-{}", base, n);
+            n += 1;
+        }
+        section.slug = candidate;
+    }
+    sections
+}
+
+/// Name of the component schema `schema` refers to, if it is a reference.
+pub(crate) fn ref_name(schema: &Value) -> Option<&str> {
+    schema.get("$ref").and_then(Value::as_str)?.strip_prefix("#/components/schemas/")
+}
+
+impl TypeName {
+    pub fn of(schema: &Value) -> TypeName {
+        if let Some(name) = ref_name(schema) {
+            return TypeName::Schema(name.to_string());
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+                return TypeName::Union(variants.iter().map(TypeName::of).collect());
+            }
+        }
+        if let Some([only]) = schema.get("allOf").and_then(Value::as_array).map(Vec::as_slice) {
+            return TypeName::of(only);
+        }
+
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(ty)) => vec![ty],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let mut names: Vec<TypeName> = types.iter().map(|ty| match *ty {
+            "array" => TypeName::Array(Box::new(TypeName::of(schema.get("items").unwrap_or(&Value::Null)))),
+            "object" => match schema.get("additionalProperties") {
+                Some(values) if values.is_object() && schema.get("properties").is_none() => {
+                    TypeName::Map(Box::new(TypeName::of(values)))
+                }
+                _ => TypeName::Primitive("object".to_string()),
+            },
+            ty => match schema.get("format").and_then(Value::as_str) {
+                Some(format) => TypeName::Primitive(format!("{} ({})", ty, format)),
+                None => TypeName::Primitive(ty.to_string()),
+            },
+        }).collect();
+        if schema.get("nullable") == Some(&Value::Bool(true)) {
+            names.push(TypeName::Primitive("null".to_string()));
+        }
+        match names.len() {
+            0 => TypeName::Primitive("any".to_string()),
+            1 => names.remove(0),
+            _ => TypeName::Union(names),
+        }
+    }
+
+    /// Renders the type, formatting each referenced schema with `link`.
+    pub fn render(&self, link: &dyn Fn(&str) -> String) -> String {
+        match self {
+            TypeName::Schema(name) => link(name),
+            TypeName::Primitive(name) => name.clone(),
+            TypeName::Array(items) => format!("array of {}", items.render(link)),
+            TypeName::Map(values) => format!("map of {}", values.render(link)),
+            TypeName::Union(types) => types.iter().map(|ty| ty.render(link)).collect::<Vec<_>>().join(" | "),
+        }
+    }
+}
+
+pub(crate) fn shape(schema: &Value) -> Shape<'_> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return Shape::Enum(values);
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+            return Shape::Variants {
+                variants: variants.iter().collect(),
+                discriminator: schema.pointer("/discriminator/propertyName").and_then(Value::as_str),
+            };
+        }
+    }
+
+    let mut properties = Vec::new();
+    let mut includes = Vec::new();
+    collect_properties(schema, &mut properties, &mut includes);
+    if properties.is_empty() && includes.is_empty() {
+        Shape::Other
+    } else {
+        Shape::Object { properties, includes }
+    }
+}
+
+// Properties of an object schema, following inline `allOf` parts
+fn collect_properties<'a>(schema: &'a Value, properties: &mut Vec<Property<'a>>, includes: &mut Vec<&'a str>) {
+    let required: Vec<&str> = schema.get("required").and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if let Some(object) = schema.get("properties").and_then(Value::as_object) {
+        properties.extend(object.iter().map(|(name, schema)| Property { name, schema, required: required.contains(&name.as_str()) }));
+    }
+    for part in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+        match ref_name(part) {
+            Some(name) => includes.push(name),
+            None => collect_properties(part, properties, includes),
+        }
+    }
+}
+
+/// An example value for `schema`: its own `example`/`examples`/`default` if
+/// it has one, otherwise a value built from its type, with references
+/// resolved in `schemas`.
+pub(crate) fn example(schema: &Value, schemas: &Map<String, Value>) -> Value {
+    example_at(schema, schemas, &mut Vec::new())
+}
+
+fn example_at<'a>(schema: &Value, schemas: &'a Map<String, Value>, visiting: &mut Vec<&'a str>) -> Value {
+    if let Some(example) = schema.get("example").or_else(|| schema.pointer("/examples/0")).or_else(|| schema.get("default")) {
+        return example.clone();
+    }
+    if let Some(name) = ref_name(schema) {
+        // 递归类型展开到第一次重复为止
+        let Some((name, target)) = schemas.get_key_value(name) else { return Value::Null };
+        let name = name.as_str();
+        if visiting.contains(&name) || visiting.len() >= MAX_EXAMPLE_DEPTH {
+            return if target.get("properties").is_some() { json!({}) } else { Value::Null };
+        }
+        visiting.push(name);
+        let value = example_at(target, schemas, visiting);
+        visiting.pop();
+        return value;
+    }
+    if let Some(first) = schema.pointer("/enum/0") {
+        return first.clone();
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+            let variant = variants.iter().find(|variant| variant.get("type") != Some(&json!("null")));
+            return variant.map(|variant| example_at(variant, schemas, visiting)).unwrap_or(Value::Null);
+        }
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for part in parts {
+            match example_at(part, schemas, visiting) {
+                Value::Object(fields) => merged.extend(fields),
+                other if parts.len() == 1 => return other,
+                _ => {}
+            }
+        }
+        if let Value::Object(fields) = example_at(&without(schema, "allOf"), schemas, visiting) {
+            merged.extend(fields);
+        }
+        return Value::Object(merged);
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).find(|ty| *ty != "null"),
+        Some(ty) => ty.as_str(),
+        None if schema.get("properties").is_some() => Some("object"),
+        None => None,
+    };
+    match ty {
+        Some("object") => {
+            let mut object = Map::new();
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (name, property) in properties {
+                    object.insert(name.clone(), example_at(property, schemas, visiting));
+                }
+            } else if let Some(values) = schema.get("additionalProperties").filter(|values| values.is_object()) {
+                object.insert("key".to_string(), example_at(values, schemas, visiting));
+            }
+            Value::Object(object)
+        }
+        Some("array") => match schema.get("items") {
+            Some(items) => json!([example_at(items, schemas, visiting)]),
+            None => json!([]),
+        },
+        Some("string") => json!(match schema.get("format").and_then(Value::as_str) {
+            Some("date-time") => "2024-01-01T00:00:00Z",
+            Some("date") => "2024-01-01",
+            Some("time") => "12:00:00",
+            Some("uuid") => "550e8400-e29b-41d4-a716-446655440000",
+            Some("email") => "user@example.com",
+            Some("uri") => "https://example.com",
+            Some("duration") => "PT1S",
+            Some("binary" | "byte") => "",
+            _ => "string",
+        }),
+        Some("integer") => json!(0),
+        Some("number") => json!(0.0),
+        Some("boolean") => json!(true),
+        _ => Value::Null,
+    }
+}
+
+fn without(schema: &Value, key: &str) -> Value {
+    let mut schema = schema.clone();
+    if let Some(object) = schema.as_object_mut() {
+        object.remove(key);
+    }
+    schema
+}
+
+/// Lowercase letters, digits (any script) and single dashes.
+pub(crate) fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(value: Value) -> OpenApi {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_sections_by_tag() {
+        let spec = spec(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Shop", "version": "1"},
+            "tags": [{"name": "users", "description": "User accounts"}],
+            "paths": {
+                "/orders": {"get": {"tags": ["orders"], "responses": {}}},
+                "/users": {
+                    "post": {"tags": ["users"], "operationId": "create_user", "responses": {}},
+                    "get": {"tags": ["users", "admin"], "responses": {}}
+                },
+                "/health": {"get": {"responses": {}}}
+            },
+            "components": {"schemas": {}}
+        }));
+        let sections = sections(&spec);
+        let names: Vec<&str> = sections.iter().map(|section| section.name.as_str()).collect();
+        assert_eq!(names, vec!["users", "admin", "orders", "default"]);
+        assert_eq!(sections[0].description, Some("User accounts"));
+
+        let users: Vec<String> = sections[0].operations.iter().map(Operation::anchor).collect();
+        assert_eq!(users, vec!["get-users", "create-user"]);
+    }
+
+    #[test]
+    fn test_type_names() {
+        let link = |name: &str| format!("[{}]", name);
+        let name = |schema: Value| TypeName::of(&schema).render(&link);
+        assert_eq!(name(json!({"$ref": "#/components/schemas/User"})), "[User]");
+        assert_eq!(name(json!({"type": "array", "items": {"$ref": "#/components/schemas/User"}})), "array of [User]");
+        assert_eq!(name(json!({"type": "integer", "format": "int64", "nullable": true})), "integer (int64) | null");
+        assert_eq!(name(json!({"type": ["string", "null"]})), "string | null");
+        assert_eq!(name(json!({"type": "object", "additionalProperties": {"type": "integer"}})), "map of integer");
+        assert_eq!(name(json!({"oneOf": [{"$ref": "#/components/schemas/A"}, {"type": "string"}]})), "[A] | string");
+    }
+
+    #[test]
+    fn test_examples() {
+        let schemas = json!({
+            "User": {
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string", "format": "uuid", "example": "550e8400-e29b-41d4-a716-446655440000"},
+                    "age": {"type": "integer"},
+                    "role": {"type": "string", "enum": ["admin", "member"]},
+                    "manager": {"$ref": "#/components/schemas/User", "nullable": true},
+                    "tags": {"type": "array", "items": {"type": "string"}}
+                }
+            },
+            "Admin": {"allOf": [{"$ref": "#/components/schemas/Audit"}, {"type": "object", "properties": {"level": {"type": "integer"}}}]},
+            "Audit": {"type": "object", "properties": {"at": {"type": "string", "format": "date-time"}}}
+        });
+        let schemas = schemas.as_object().unwrap();
+
+        let user = example(&json!({"$ref": "#/components/schemas/User"}), schemas);
+        assert_eq!(user, json!({
+            "id": "550e8400-e29b-41d4-a716-446655440000",
+            "age": 0,
+            "role": "admin",
+            "manager": {},
+            "tags": ["string"]
+        }));
+        let admin = example(&json!({"$ref": "#/components/schemas/Admin"}), schemas);
+        assert_eq!(admin, json!({"at": "2024-01-01T00:00:00Z", "level": 0}));
+
+        match shape(&schemas["Admin"]) {
+            Shape::Object { properties, includes } => {
+                assert_eq!(properties[0].name, "level");
+                assert_eq!(includes, vec!["Audit"]);
+            }
+            _ => panic!("expected an object"),
+        }
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("modules::user"), "modules-user");
+        assert_eq!(slug("GET /users/{id}"), "get-users-id");
+        assert_eq!(slug("用户 管理"), "用户-管理");
+    }
+
+    #[test]
+    fn test_section_slugs_are_unique() {
+        let spec = spec(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Shop", "version": "1"},
+            "paths": {
+                "/a": {"get": {"tags": ["用户"], "responses": {}}},
+                "/b": {"get": {"tags": ["订单"], "responses": {}}},
+                "/c": {"get": {"tags": ["User Admin"], "responses": {}}},
+                "/d": {"get": {"tags": ["user-admin"], "responses": {}}},
+                "/e": {"get": {"tags": ["!!"], "responses": {}}}
+            },
+            "components": {"schemas": {}}
+        }));
+        let slugs: Vec<String> = sections(&spec).into_iter().map(|section| section.slug).collect();
+        assert_eq!(slugs, vec!["section", "user-admin", "user-admin-2", "用户", "订单"]);
+    }
+}
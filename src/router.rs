@@ -0,0 +1,1128 @@
+//! Router discovery: walks `Router::new()` chains and follows `nest`/`merge`
+//! and router function calls, resolved through the crate's imports and module
+//! tree, to collect every registered route. Within a file, routers bound with
+//! `let`, reassigned or returned from local functions are followed to where
+//! they are used.
+
+use std::{cell::Cell, collections::{HashMap, HashSet}, path::{Path as StdPath, PathBuf}, rc::Rc};
+use syn::visit::Visit;
+use quote::ToTokens;
+
+use crate::crate_index::CrateIndex;
+use crate::sources::SourceCache;
+
+// Methods a `MethodRouter` can route, in `MethodFilter` order
+const METHODS: [&str; 8] = ["delete", "get", "head", "options", "patch", "post", "put", "trace"];
+
+// Methods `any(..)` and `.fallback(..)` are documented under; HEAD, OPTIONS
+// and TRACE would only repeat the same catch-all operation
+const ANY_METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+/// A single method of a `.route()` registration with its fully prefixed path.
+#[derive(Debug, Clone)]
+pub struct RouteInfo {
+    pub path: String,
+    pub method: String,
+    /// Handler function, or for a service route the service's type
+    pub handler: String,
+    pub module: Option<Vec<String>>, // 模块路径，如 ["modules", "auth"]
+    /// Routed to a tower service (`get_service(..)`) rather than a handler
+    pub service: bool,
+    /// Crate path of the handler function, when resolved through the
+    /// module tree, e.g. `["api", "users", "list"]`
+    pub handler_path: Option<Vec<String>>,
+    /// Registered through `any(..)` or `.fallback(..)` rather than for this
+    /// method specifically
+    pub any_method: bool,
+}
+
+impl RouteInfo {
+    /// Key identifying the route's handler: its crate path such as
+    /// `api::users::list`, or the bare name when the path is unresolved.
+    pub fn handler_id(&self) -> String {
+        match &self.handler_path {
+            Some(path) => path.join("::"),
+            None => self.handler.clone(),
+        }
+    }
+}
+
+/// The handler (or service) a `MethodRouter` registers for one method.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MethodRoute {
+    pub method: String,
+    pub handler: String,
+    /// Handler path as written, e.g. `["users", "list"]`
+    pub path: Vec<String>,
+    pub service: bool,
+    /// Comes from `any(..)` or `.fallback(..)`
+    pub any: bool,
+}
+
+/// A `let`-bound router, whose routes are collected where the binding is used.
+struct RouterBinding {
+    expr: syn::Expr,
+    /// Bindings in scope where `expr` was bound, so `app = app.route(..)`
+    /// refers to the previous `app`
+    scope: HashMap<String, Rc<RouterBinding>>,
+    used: Cell<bool>,
+}
+
+/// Syntax visitor that collects routes from a router file and the module
+/// router files it references.
+pub struct RouterVisitor {
+    pub routes: Vec<RouteInfo>,
+    state_stack: Vec<(String, Option<String>)>, // (base_path, module_name)
+    base_path: PathBuf, // 添加基础路径用于构建模块文件路径
+    current_module: Vec<String>, // Track current file's module path (e.g., ["modules", "user"])
+    sources: SourceCache,
+    /// Functions of the current file returning a `Router`
+    local_routers: HashMap<String, Rc<syn::ItemFn>>,
+    /// Router bindings in scope
+    bindings: HashMap<String, Rc<RouterBinding>>,
+    /// Router functions being expanded, by crate path, to stop at recursive calls
+    expanding: Vec<Vec<String>>,
+    entry_file: PathBuf,
+    /// Module tree router function paths are resolved through
+    index: Option<Rc<CrateIndex>>,
+}
+
+impl RouterVisitor {
+    /// Creates a visitor for the project rooted at `base_path`, starting in
+    /// the module that `entry_file` defines.
+    pub fn new(base_path: &StdPath, entry_file: &StdPath) -> Self {
+        RouterVisitor {
+            routes: Vec::new(),
+            state_stack: Vec::new(),
+            base_path: base_path.to_path_buf(),
+            current_module: extract_module_from_path(base_path, entry_file),
+            sources: SourceCache::new(),
+            local_routers: HashMap::new(),
+            bindings: HashMap::new(),
+            expanding: Vec::new(),
+            entry_file: entry_file.to_path_buf(),
+            index: None,
+        }
+    }
+
+    /// Resolves router functions referenced by `nest`, `merge` and calls
+    /// through the imports and module tree of `index`, instead of guessing
+    /// module router files by name. Targets the index cannot resolve are
+    /// reported and skipped.
+    pub fn with_index(mut self, index: Rc<CrateIndex>) -> Self {
+        if let Some(module) = index.module_for_file(&self.entry_file) {
+            self.current_module = module.path.clone();
+        }
+        self.index = Some(index);
+        self
+    }
+
+    /// Reads module router files through `sources`, so unchanged files are
+    /// not parsed again.
+    pub fn with_sources(mut self, sources: &SourceCache) -> Self {
+        self.sources = sources.clone();
+        self
+    }
+
+    /// Visits a module router file and extracts routes from it.
+    /// This is a shared method used by both nest and merge handlers when
+    /// no [`CrateIndex`] is attached.
+    ///
+    /// # Arguments
+    /// * `module_name` - The name of the module to visit
+    /// * `module_path_str` - The full module path as a string (e.g., "modules/user")
+    ///
+    /// # Returns
+    /// * `true` if the module file was found and visited successfully
+    /// * `false` if the module file was not found
+    fn visit_module_router(&mut self, module_name: &str, module_path_str: &str) -> bool {
+        // Try multiple file patterns for the module
+        let module_file_paths = vec![
+            self.base_path.join(format!("src/{}/handlers.rs", module_path_str)),
+            self.base_path.join(format!("src/{}/mod.rs", module_path_str)),
+            self.base_path.join(format!("src/{}.rs", module_path_str)),
+        ];
+
+        let mut found = false;
+        for module_file_path in &module_file_paths {
+            if module_file_path.exists() {
+                // Update current_module to reflect the nested module
+                let old_current_module = self.current_module.clone();
+                self.current_module = extract_module_from_path(&self.base_path, module_file_path);
+                let old_local_routers = std::mem::take(&mut self.local_routers);
+                let old_bindings = std::mem::take(&mut self.bindings);
+
+                if let Ok(module_ast) = self.sources.parse(module_file_path) {
+                    self.local_routers = local_router_fns(&module_ast.items);
+                    for item in &module_ast.items {
+                        if let syn::Item::Fn(func) = item {
+                            if func.sig.ident == "router" {
+                                if let Some(syn::Stmt::Expr(expr, _)) = func.block.stmts.last() {
+                                    // Check if this is a router() call like handler::router()
+                                    if let Some(target_module) = extract_router_module_call(expr) {
+                                        // Calculate the target module path
+                                        let target_module_path = {
+                                            let mut path = self.current_module.clone();
+                                            path.push(target_module.clone());
+                                            path.join("/")
+                                        };
+
+                                        // Recursively visit the target module
+                                        if self.visit_module_router(&target_module, &target_module_path) {
+                                            found = true;
+                                        } else {
+                                            // If recursive visit failed, fall back to visiting the expression
+                                            self.visit_expr(expr);
+                                            found = true;
+                                        }
+                                    } else {
+                                        // Not a recursive router call, visit the body with its bindings
+                                        self.visit_block(&func.block);
+                                        found = true;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Restore current_module
+                self.current_module = old_current_module;
+                self.local_routers = old_local_routers;
+                self.bindings = old_bindings;
+                break;
+            }
+        }
+
+        if !found {
+            eprintln!("Warning: Module file not found for {}: {} (tried paths: {:?})",
+                     if module_name.contains("nest") { "nest" } else { "merge" },
+                     module_name, module_file_paths);
+        }
+
+        found
+    }
+
+    /// Whether `expr` builds a router the visitor can follow: a chain
+    /// starting from `Router::new()`, a router function or a router binding.
+    fn is_router_expr(&self, expr: &syn::Expr) -> bool {
+        match expr {
+            syn::Expr::MethodCall(call) => self.is_router_expr(&call.receiver),
+            syn::Expr::Call(call) => match &*call.func {
+                syn::Expr::Path(func) => {
+                    let segments = &func.path.segments;
+                    let local = func.path.get_ident()
+                        .is_some_and(|ident| self.local_routers.contains_key(&ident.to_string()));
+                    local
+                        || (segments.len() > 1 && segments[segments.len() - 2].ident == "Router")
+                        || self.resolve_router_fn(&func.path).is_some()
+                }
+                _ => false,
+            },
+            syn::Expr::Path(path) => path.path.get_ident()
+                .is_some_and(|ident| self.bindings.contains_key(&ident.to_string())),
+            syn::Expr::Paren(paren) => self.is_router_expr(&paren.expr),
+            syn::Expr::Reference(reference) => self.is_router_expr(&reference.expr),
+            _ => false,
+        }
+    }
+
+    // Collects the routes of a binding in the scope it was bound in
+    fn expand_binding(&mut self, binding: &RouterBinding) {
+        binding.used.set(true);
+        let scope = std::mem::replace(&mut self.bindings, binding.scope.clone());
+        self.visit_expr(&binding.expr);
+        self.bindings = scope;
+    }
+
+    /// Collects the routes of the local router function `name`, returning
+    /// `false` if there is no such function.
+    fn expand_local_router(&mut self, name: &str) -> bool {
+        let Some(func) = self.local_routers.get(name).cloned() else {
+            return false;
+        };
+        let path = calculate_module_path(&self.current_module, name);
+        if !self.expanding.contains(&path) {
+            self.expanding.push(path);
+            let scope = std::mem::take(&mut self.bindings);
+            self.visit_block(&func.block);
+            self.bindings = scope;
+            self.expanding.pop();
+        }
+        true
+    }
+
+    /// Crate path of the `Router`-returning function `path` names in the
+    /// current module, resolved through its imports.
+    fn resolve_router_fn(&self, path: &syn::Path) -> Option<Vec<String>> {
+        let index = self.index.as_ref()?;
+        let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+        let resolved = index.resolve_fn(&self.current_module, &segments)?;
+        let (_, func) = index.function(&resolved)?;
+        match &func.sig.output {
+            syn::ReturnType::Type(_, ty) if is_router_type(ty) => Some(resolved),
+            _ => None,
+        }
+    }
+
+    // Collects the routes of a router function defined anywhere in the crate,
+    // in the context of its own module
+    fn expand_router_fn(&mut self, path: Vec<String>) {
+        let Some(index) = self.index.clone() else { return };
+        let Some((module, func)) = index.function(&path) else { return };
+        if self.expanding.contains(&path) {
+            return;
+        }
+        self.expanding.push(path);
+        let current_module = std::mem::replace(&mut self.current_module, module.path.clone());
+        let local_routers = std::mem::replace(&mut self.local_routers, local_router_fns(&module.items));
+        let bindings = std::mem::take(&mut self.bindings);
+
+        self.visit_block(&func.block);
+
+        self.current_module = current_module;
+        self.local_routers = local_routers;
+        self.bindings = bindings;
+        self.expanding.pop();
+    }
+
+    // A `nest`/`merge` target that is not a router the module tree resolves
+    fn report_unresolved(&self, method: &str, expr: &syn::Expr) {
+        let module = if self.current_module.is_empty() { "crate".to_string() } else { self.current_module.join("::") };
+        eprintln!("Warning: Router `{}` passed to {}() in module '{}' could not be resolved in the module tree, skipping its routes",
+                 expr.to_token_stream(), method, module);
+    }
+
+    // Records `name = expr` as a router binding if `expr` builds a router
+    fn bind_router(&mut self, name: String, expr: &syn::Expr, typed: bool, bound: &mut Vec<Rc<RouterBinding>>) -> bool {
+        if !typed && !self.is_router_expr(expr) {
+            return false;
+        }
+        let binding = Rc::new(RouterBinding {
+            expr: expr.clone(),
+            scope: self.bindings.clone(),
+            used: Cell::new(false),
+        });
+        self.bindings.insert(name, binding.clone());
+        bound.push(binding);
+        true
+    }
+}
+
+impl<'ast> Visit<'ast> for RouterVisitor {
+    fn visit_file(&mut self, file: &'ast syn::File) {
+        self.local_routers = local_router_fns(&file.items);
+
+        // Router functions called elsewhere in the file are collected at their call sites
+        let mut called = HashSet::new();
+        for item in &file.items {
+            let mut calls = LocalCalls::default();
+            calls.visit_item(item);
+            if let syn::Item::Fn(func) = item {
+                calls.names.remove(&func.sig.ident.to_string());
+            }
+            called.extend(calls.names);
+        }
+
+        for item in &file.items {
+            match item {
+                syn::Item::Fn(func) if self.local_routers.contains_key(&func.sig.ident.to_string()) => {
+                    let name = func.sig.ident.to_string();
+                    if !called.contains(&name) {
+                        self.expand_local_router(&name);
+                    }
+                }
+                // 内联模块与文件模块一样，只在其 router 函数被调用处收集
+                syn::Item::Mod(_) => {}
+                _ => self.visit_item(item),
+            }
+        }
+    }
+
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        let scope = self.bindings.clone();
+        let mut bound = Vec::new();
+
+        for stmt in &block.stmts {
+            match stmt {
+                syn::Stmt::Local(local) => {
+                    let binding = local_binding_name(&local.pat);
+                    if let (Some((name, typed)), Some(init)) = (&binding, &local.init) {
+                        if init.diverge.is_none() && self.bind_router(name.clone(), &init.expr, *typed, &mut bound) {
+                            continue;
+                        }
+                    }
+                    self.visit_local(local);
+                    // A non-router binding shadows a router of the same name
+                    if let Some((name, _)) = binding {
+                        self.bindings.remove(&name);
+                    }
+                }
+                syn::Stmt::Expr(syn::Expr::Assign(assign), _) => {
+                    // `app = app.route(..)` rebinds the router
+                    let name = match &*assign.left {
+                        syn::Expr::Path(path) => path.path.get_ident()
+                            .map(|ident| ident.to_string())
+                            .filter(|name| self.bindings.contains_key(name)),
+                        _ => None,
+                    };
+                    if !name.is_some_and(|name| self.bind_router(name, &assign.right, false, &mut bound)) {
+                        self.visit_stmt(stmt);
+                    }
+                }
+                _ => self.visit_stmt(stmt),
+            }
+        }
+
+        // Routers bound but never used, such as `let app = router();` before
+        // `axum::serve` in a macro, still contribute their routes
+        for binding in bound.iter().rev() {
+            if !binding.used.get() {
+                self.expand_binding(binding);
+            }
+        }
+        self.bindings = scope;
+    }
+
+    fn visit_expr_path(&mut self, path: &'ast syn::ExprPath) {
+        let binding = path.path.get_ident().and_then(|ident| self.bindings.get(&ident.to_string()).cloned());
+        if let Some(binding) = binding {
+            self.expand_binding(&binding);
+        }
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(func) = &*call.func {
+            if let Some(ident) = func.path.get_ident() {
+                if self.expand_local_router(&ident.to_string()) {
+                    return;
+                }
+            }
+            if let Some(path) = self.resolve_router_fn(&func.path) {
+                self.expand_router_fn(path);
+                return;
+            }
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        // 先递归访问receiver（链式调用的左侧）
+        syn::visit::visit_expr(self, &call.receiver);
+        
+        // 处理当前方法调用
+        match call.method.to_string().as_str() {
+            "route" => {
+                // 处理 .route() 调用，每个方法生成一个路由
+                if let Some(path) = call.args.first().and_then(parse_string_arg) {
+                    let method_routes = call.args.get(1).map(parse_method_router).unwrap_or_default();
+                    // 获取当前状态
+                    let current_base_path = self.state_stack.last()
+                        .map(|(bp, _)| bp.clone())
+                        .unwrap_or_default();
+
+                    // 构建完整路径
+                    let full_path = if current_base_path.is_empty() {
+                        path.to_string()
+                    } else if path.starts_with('/') {
+                        format!("{}{}", current_base_path, path)
+                    } else {
+                        format!("{}/{}", current_base_path, path)
+                    };
+
+                    //println!("DEBUG: Found route - path: {}, method: {}, handler: {}, module: {:?}",
+                    //          full_path, method, handler, self.current_module);
+
+                    for route in method_routes {
+                        let handler_path = match &self.index {
+                            Some(index) if !route.service => {
+                                let resolved = index.resolve_fn(&self.current_module, &route.path);
+                                if resolved.is_none() {
+                                    eprintln!("Warning: Handler '{}' routed at '{}' could not be resolved in the module tree, leaving it undocumented",
+                                             route.path.join("::"), full_path);
+                                }
+                                resolved
+                            }
+                            _ => None,
+                        };
+                        self.routes.push(RouteInfo {
+                            path: full_path.clone(),
+                            method: route.method,
+                            handler: route.handler,
+                            module: if self.current_module.is_empty() { None } else { Some(self.current_module.clone()) },
+                            service: route.service,
+                            handler_path,
+                            any_method: route.any,
+                        });
+                    }
+                }
+            }
+            "nest" => {
+                // 处理 .nest() 调用
+                if let Some(path_prefix) = parse_string_arg(&call.args[0]) {
+                    // 获取当前状态
+                    let current_base_path = self.state_stack.last()
+                        .map(|(bp, _)| bp.clone())
+                        .unwrap_or_default();
+
+                    let new_base_path = nested_base_path(&current_base_path, &path_prefix);
+
+                    if self.is_router_expr(&call.args[1]) {
+                        // e.g. nest("/api", api), nest("/api", api_routes()) or nest("/api", crate::api::v1::routes())
+                        self.state_stack.push((new_base_path, None));
+                        self.visit_expr(&call.args[1]);
+                        self.state_stack.pop();
+                    } else if self.index.is_some() {
+                        // 模块树无法解析的目标不再按文件名猜测
+                        if parse_nest_handler(&call.args[1]).is_some() {
+                            self.report_unresolved("nest", &call.args[1]);
+                        }
+                    } else if let Some(module_name) = parse_nest_handler(&call.args[1]) {
+                        // 将新状态压入栈
+                        self.state_stack.push((new_base_path, Some(module_name.clone())));
+
+                        // Calculate the full module path based on current_module
+                        let module_path = calculate_module_path(&self.current_module, &module_name);
+                        let module_path_str = module_path.join("/");
+
+                        // Visit the module router file using the shared method
+                        self.visit_module_router(&module_name, &module_path_str);
+
+                        // 恢复状态
+                        self.state_stack.pop();
+                    }
+                }
+            }
+            "merge" => {
+                // 处理 .merge() 调用
+                // merge() 不添加路径前缀，只是合并另一个路由
+                if self.is_router_expr(&call.args[0]) {
+                    self.visit_expr(&call.args[0]);
+                } else if self.index.is_some() && parse_merge_handler(&call.args[0]).is_some() {
+                    self.report_unresolved("merge", &call.args[0]);
+                } else if let Some(module_name) = parse_merge_handler(&call.args[0]) {
+                    // 获取当前状态（merge 不改变路径前缀）
+                    let (current_base_path, current_module) = self.state_stack.last()
+                        .map(|(bp, m)| (bp.clone(), m.clone()))
+                        .unwrap_or((String::new(), None));
+
+                    // 将当前状态压入栈（merge 不改变前缀和模块）
+                    self.state_stack.push((current_base_path.clone(), current_module));
+
+                    // Calculate the full module path based on current_module
+                    let module_path = calculate_module_path(&self.current_module, &module_name);
+                    let module_path_str = module_path.join("/");
+
+                    // Visit the module router file using the shared method
+                    self.visit_module_router(&module_name, &module_path_str);
+
+                    // 恢复状态
+                    self.state_stack.pop();
+                } else {
+                    // 如果不是模块调用，则递归访问表达式
+                    // 这处理了内联的 router 表达式，如 merge(Router::new().route(...))
+                    syn::visit::visit_expr(self, &call.args[0]);
+                }
+            }
+            _ => {
+                // 其他方法调用，继续递归访问参数
+                for arg in &call.args {
+                    syn::visit::visit_expr(self, arg);
+                }
+            }
+        }
+        
+        // 重要：不要return，继续处理后续的链式调用
+    }
+}
+
+// Names of the functions of the same module called within an item
+#[derive(Default)]
+struct LocalCalls {
+    names: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for LocalCalls {
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(func) = &*call.func {
+            let segments = &func.path.segments;
+            let local = match segments.len() {
+                1 => true,
+                2 => segments[0].ident == "self",
+                _ => false,
+            };
+            if local {
+                self.names.insert(segments[segments.len() - 1].ident.to_string());
+            }
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+}
+
+/// Free functions among `items` whose return type is a `Router`.
+fn local_router_fns(items: &[syn::Item]) -> HashMap<String, Rc<syn::ItemFn>> {
+    items.iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(func) => match &func.sig.output {
+                syn::ReturnType::Type(_, ty) if is_router_type(ty) => Some((func.sig.ident.to_string(), Rc::new(func.clone()))),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_router_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path.path.segments.last().is_some_and(|segment| segment.ident == "Router"),
+        _ => false,
+    }
+}
+
+// `app`, `mut app` or `app: Router` -> the name, and whether it is typed as a router
+fn local_binding_name(pat: &syn::Pat) -> Option<(String, bool)> {
+    match pat {
+        syn::Pat::Ident(ident) if ident.subpat.is_none() => Some((ident.ident.to_string(), false)),
+        syn::Pat::Type(typed) => local_binding_name(&typed.pat)
+            .map(|(name, _)| (name, is_router_type(&typed.ty))),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_string_arg(expr: &syn::Expr) -> Option<String> {
+    if let syn::Expr::Lit(lit) = expr {
+        if let syn::Lit::Str(s) = &lit.lit {
+            return Some(s.value());
+        }
+    }
+    None
+}
+
+/// Base path of a router nested at `prefix` under a router at `current`,
+/// without repeating a prefix the two share.
+fn nested_base_path(current: &str, prefix: &str) -> String {
+    if current.is_empty() {
+        // 没有当前路径，直接使用新前缀
+        prefix.to_string()
+    } else if current == prefix {
+        // 完全相同的路径，不重复添加
+        current.to_string()
+    } else if prefix.starts_with(current) {
+        // 新前缀包含当前路径（例如：current="/api", new="/api/v1"）
+        // 使用更完整的新前缀
+        prefix.to_string()
+    } else if current.ends_with(prefix) {
+        // 当前路径已经包含新前缀（例如：current="/api/v1", new="/v1"）
+        // 保持当前路径不变
+        current.to_string()
+    } else {
+        // 检查是否有部分重叠
+        let combined = format!("{}{}", current, prefix);
+        let current_end = current.split('/').next_back().unwrap_or("");
+        let new_start = prefix.trim_start_matches('/').split('/').next().unwrap_or("");
+
+        if current_end == new_start && !current_end.is_empty() && !new_start.is_empty() {
+            // 有重叠，去掉重复部分
+            // 例如："/api/v1" + "/v1/user" -> "/api/v1/user"
+            let trimmed = current.trim_end_matches(current_end).trim_end_matches('/');
+            format!("{}/{}", trimmed, prefix.trim_start_matches('/'))
+        } else {
+            // 没有重叠，正常拼接
+            combined
+        }
+    }
+}
+
+/// Every method a `MethodRouter` expression routes, such as
+/// `get(list).post(create)`, `on(MethodFilter::PUT, update)` or
+/// `get_service(ServeDir::new("assets"))`. `any(..)` and `.fallback(..)`
+/// cover the common methods (GET, POST, PUT, PATCH and DELETE) not routed
+/// explicitly.
+pub(crate) fn parse_method_router(expr: &syn::Expr) -> Vec<MethodRoute> {
+    let mut routes = Vec::new();
+    let mut fallback = None;
+    collect_method_routes(expr, &mut routes, &mut fallback);
+    if let Some((path, service)) = fallback {
+        for method in ANY_METHODS {
+            if !routes.iter().any(|route| route.method == method) {
+                routes.push(MethodRoute { any: true, ..method_route(method.to_string(), path.clone(), service) });
+            }
+        }
+    }
+    routes
+}
+
+// Walks a chain such as `get(a).post(b).layer(..)` from its start
+fn collect_method_routes(expr: &syn::Expr, routes: &mut Vec<MethodRoute>, fallback: &mut Option<(Vec<String>, bool)>) {
+    match expr {
+        syn::Expr::Call(call) => {
+            if let syn::Expr::Path(func) = &*call.func {
+                if let Some(segment) = func.path.segments.last() {
+                    let args: Vec<&syn::Expr> = call.args.iter().collect();
+                    add_method_route(&segment.ident.to_string(), &args, routes, fallback);
+                }
+            }
+        }
+        syn::Expr::MethodCall(call) => {
+            collect_method_routes(&call.receiver, routes, fallback);
+            let args: Vec<&syn::Expr> = call.args.iter().collect();
+            add_method_route(&call.method.to_string(), &args, routes, fallback);
+        }
+        syn::Expr::Paren(paren) => collect_method_routes(&paren.expr, routes, fallback),
+        syn::Expr::Group(group) => collect_method_routes(&group.expr, routes, fallback),
+        _ => {}
+    }
+}
+
+// One link of the chain; `layer`, `with_state` and the like route nothing
+fn add_method_route(name: &str, args: &[&syn::Expr], routes: &mut Vec<MethodRoute>, fallback: &mut Option<(Vec<String>, bool)>) {
+    let (name, service) = match name.strip_suffix("_service") {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    let target = |expr: &syn::Expr| if service { service_name(expr).map(|name| vec![name]) } else { handler_path(expr) };
+    match (name, args) {
+        ("on", [filter, handler]) => {
+            if let Some(path) = target(handler) {
+                for method in parse_method_filter(filter) {
+                    routes.push(method_route(method, path.clone(), service));
+                }
+            }
+        }
+        ("any" | "fallback", [handler]) => {
+            if let Some(path) = target(handler) {
+                *fallback = Some((path, service));
+            }
+        }
+        (method, [handler]) if METHODS.contains(&method) => {
+            if let Some(path) = target(handler) {
+                routes.push(method_route(method.to_string(), path, service));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn method_route(method: String, path: Vec<String>, service: bool) -> MethodRoute {
+    let handler = path.last().cloned().unwrap_or_default();
+    MethodRoute { method, handler, path, service, any: false }
+}
+
+// `list_users` or `users::list` -> the path segments
+fn handler_path(expr: &syn::Expr) -> Option<Vec<String>> {
+    match expr {
+        syn::Expr::Path(path) => Some(path.path.segments.iter().map(|s| s.ident.to_string()).collect()),
+        _ => None,
+    }
+}
+
+// `ServeDir::new("assets").fallback(..)` -> `ServeDir`
+fn service_name(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Path(path) => Some(path.path.segments.last()?.ident.to_string()),
+        syn::Expr::Call(call) => match &*call.func {
+            syn::Expr::Path(func) => {
+                let segments = &func.path.segments;
+                let segment = if segments.len() > 1 { &segments[segments.len() - 2] } else { segments.last()? };
+                Some(segment.ident.to_string())
+            }
+            _ => None,
+        },
+        syn::Expr::MethodCall(call) => service_name(&call.receiver),
+        syn::Expr::Paren(paren) => service_name(&paren.expr),
+        _ => None,
+    }
+}
+
+// `MethodFilter::PUT` or `MethodFilter::GET.or(MethodFilter::HEAD)`
+fn parse_method_filter(expr: &syn::Expr) -> Vec<String> {
+    match expr {
+        syn::Expr::Path(path) => path.path.segments.last()
+            .map(|segment| segment.ident.to_string().to_lowercase())
+            .filter(|method| METHODS.contains(&method.as_str()))
+            .into_iter()
+            .collect(),
+        syn::Expr::MethodCall(call) if call.method == "or" => {
+            let mut methods = parse_method_filter(&call.receiver);
+            for method in call.args.iter().flat_map(parse_method_filter) {
+                if !methods.contains(&method) {
+                    methods.push(method);
+                }
+            }
+            methods
+        }
+        syn::Expr::Paren(paren) => parse_method_filter(&paren.expr),
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn parse_nest_handler(expr: &syn::Expr) -> Option<String> {
+    // 尝试解析 nest("/path", module::handlers::router()) 中的 module
+    if let syn::Expr::Call(call) = expr {
+        if let syn::Expr::Path(path) = &*call.func {
+            // 获取路径的第一个段作为模块名（通常是 module::handlers::router）
+            if let Some(segment) = path.path.segments.first() {
+                return Some(segment.ident.to_string());
+            }
+        }
+    }
+
+    // 尝试解析 nest("/path", module::handlers::router) 中的 module
+    if let syn::Expr::Path(path) = expr {
+        if let Some(segment) = path.path.segments.first() {
+            return Some(segment.ident.to_string());
+        }
+    }
+
+    None
+}
+
+pub(crate) fn parse_merge_handler(expr: &syn::Expr) -> Option<String> {
+    // Try to parse merge(module::handlers::router()) or merge(module::handlers::router)
+    if let syn::Expr::Call(call) = expr {
+        if let syn::Expr::Path(path) = &*call.func {
+            // Get the first segment as module name
+            if let Some(segment) = path.path.segments.first() {
+                return Some(segment.ident.to_string());
+            }
+        }
+    }
+
+    // Try to parse merge(module::handlers::router) without call
+    if let syn::Expr::Path(path) = expr {
+        if let Some(segment) = path.path.segments.first() {
+            return Some(segment.ident.to_string());
+        }
+    }
+
+    None
+}
+
+/// Extract the target module name from a router() call expression.
+/// For example, `handler::router()` returns Some("handler").
+/// Returns None if the expression is not a router() call.
+fn extract_router_module_call(expr: &syn::Expr) -> Option<String> {
+    if let syn::Expr::Call(call) = expr {
+        if let syn::Expr::Path(path) = &*call.func {
+            // Get the last segment as the function name
+            if let Some(last_segment) = path.path.segments.last() {
+                let func_name = last_segment.ident.to_string();
+
+                // Check if this is a router() function
+                if func_name.contains("router") {
+                    // Extract the module name from the path
+                    // For "handler::router()", we want "handler"
+                    if path.path.segments.len() > 1 {
+                        if let Some(segment) = path.path.segments.first() {
+                            return Some(segment.ident.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Calculate the full module path for a referenced module.
+/// For example, if current_module is ["modules"] and we see "user::router()",
+/// this returns ["modules", "user"].
+fn calculate_module_path(current_module: &[String], module_name: &str) -> Vec<String> {
+    let mut path = current_module.to_vec();
+    path.push(module_name.to_string());
+    path
+}
+
+/// Extract module path from a file path.
+/// For example, "src/modules/user/mod.rs" returns ["modules", "user"].
+pub(crate) fn extract_module_from_path(base_path: &StdPath, file_path: &StdPath) -> Vec<String> {
+    // Get the path relative to src/
+    let path = file_path.strip_prefix(base_path).unwrap_or(file_path);
+    let path = path.strip_prefix("src").unwrap_or(path);
+
+    // Collect path components, excluding:
+    // - mod.rs, main.rs, lib.rs (module definition files)
+    // - .rs extension
+    let mut modules = Vec::new();
+    for component in path.components() {
+        if let std::path::Component::Normal(name) = component {
+            let name = name.to_string_lossy();
+            // Skip module definition files
+            if name != "mod.rs" && name != "main.rs" && name != "lib.rs" {
+                // Remove .rs extension if present
+                let module_name = name.trim_end_matches(".rs");
+                if !module_name.is_empty() {
+                    modules.push(module_name.to_string());
+                }
+            }
+        }
+    }
+
+    modules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_string_arg() {
+        // Test parsing string literal
+        let expr: syn::Expr = syn::parse_quote!("/api/v1/users");
+        let result = parse_string_arg(&expr);
+        assert_eq!(result, Some("/api/v1/users".to_string()));
+
+        // Test parsing non-string
+        let expr: syn::Expr = syn::parse_quote!(123);
+        let result = parse_string_arg(&expr);
+        assert_eq!(result, None);
+    }
+
+    // (method, handler, service) of every route a method router expression registers
+    fn method_routes(expr: syn::Expr) -> Vec<(String, String, bool)> {
+        parse_method_router(&expr).into_iter()
+            .map(|route| (route.method, route.handler, route.service))
+            .collect()
+    }
+
+    fn handler_route(method: &str, handler: &str) -> (String, String, bool) {
+        (method.to_string(), handler.to_string(), false)
+    }
+
+    #[test]
+    fn test_parse_method() {
+        // Test parsing method call like post(handler)
+        let routes = method_routes(syn::parse_quote!(post(my_handler)));
+        assert_eq!(routes, vec![handler_route("post", "my_handler")]);
+
+        // Test parsing get(handler)
+        let routes = method_routes(syn::parse_quote!(get(my_handler)));
+        assert_eq!(routes, vec![handler_route("get", "my_handler")]);
+    }
+
+    #[test]
+    fn test_parse_handler_name() {
+        // Test parsing nested path (only gets the final segment)
+        let routes = method_routes(syn::parse_quote!(post(module::handler)));
+        assert_eq!(routes, vec![handler_route("post", "handler")]);
+    }
+
+    #[test]
+    fn test_parse_method_router_chain() {
+        let routes = method_routes(syn::parse_quote!(
+            get(list).post(create).delete(users::remove).layer(TraceLayer::new_for_http())
+        ));
+        assert_eq!(routes, vec![
+            handler_route("get", "list"),
+            handler_route("post", "create"),
+            handler_route("delete", "remove"),
+        ]);
+
+        let routes = method_routes(syn::parse_quote!(
+            on(MethodFilter::PUT.or(MethodFilter::PATCH), update).get(show)
+        ));
+        assert_eq!(routes, vec![handler_route("put", "update"), handler_route("patch", "update"), handler_route("get", "show")]);
+
+        let routes = method_routes(syn::parse_quote!(get_service(ServeDir::new("assets").precompressed_gzip())));
+        assert_eq!(routes, vec![("get".to_string(), "ServeDir".to_string(), true)]);
+
+        // Explicit methods take precedence over `any`, which covers the common methods
+        let routes = parse_method_router(&syn::parse_quote!(any(fallback).post(create)));
+        let methods: Vec<(&str, &str, bool)> = routes.iter()
+            .map(|route| (route.method.as_str(), route.handler.as_str(), route.any))
+            .collect();
+        assert_eq!(methods, vec![
+            ("post", "create", false),
+            ("get", "fallback", true),
+            ("put", "fallback", true),
+            ("patch", "fallback", true),
+            ("delete", "fallback", true),
+        ]);
+
+        // Closures have no handler to document
+        assert!(method_routes(syn::parse_quote!(get(|| async { "ok" }))).is_empty());
+    }
+
+    #[test]
+    fn test_parse_nest_handler() {
+        // Test parsing module::router() call
+        let expr: syn::Expr = syn::parse_quote!(module::handlers::router());
+        let result = parse_nest_handler(&expr);
+        assert_eq!(result, Some("module".to_string()));
+
+        // Test parsing simple path
+        let expr: syn::Expr = syn::parse_quote!(module::router);
+        let result = parse_nest_handler(&expr);
+        assert_eq!(result, Some("module".to_string()));
+    }
+
+    #[test]
+    fn test_parse_merge_handler() {
+        // Test parsing module::handlers::router() call
+        let expr: syn::Expr = syn::parse_quote!(module::handlers::router());
+        let result = parse_merge_handler(&expr);
+        assert_eq!(result, Some("module".to_string()));
+
+        // Test parsing simple path
+        let expr: syn::Expr = syn::parse_quote!(module::router);
+        let result = parse_merge_handler(&expr);
+        assert_eq!(result, Some("module".to_string()));
+    }
+
+    #[test]
+    fn test_module_handler_parsing() {
+        // Test that we can extract module name from various patterns
+
+        // Pattern 1: module::handlers::router()
+        let expr: syn::Expr = syn::parse_quote!(api::handlers::router());
+        let result = parse_merge_handler(&expr);
+        assert_eq!(result, Some("api".to_string()));
+
+        // Pattern 2: auth::router
+        let expr: syn::Expr = syn::parse_quote!(auth::router);
+        let result = parse_merge_handler(&expr);
+        assert_eq!(result, Some("auth".to_string()));
+
+        // Pattern 3: single module
+        let expr: syn::Expr = syn::parse_quote!(users::router());
+        let result = parse_nest_handler(&expr);
+        assert_eq!(result, Some("users".to_string()));
+    }
+
+    // (method, path, handler) of every route the visitor collects from `source`
+    fn collect_routes(source: &str) -> Vec<(String, String, String)> {
+        let mut visitor = RouterVisitor::new(StdPath::new("."), StdPath::new("./src/main.rs"));
+        visitor.visit_file(&syn::parse_file(source).unwrap());
+        visitor.routes.into_iter()
+            .map(|route| (route.method, route.path, route.handler))
+            .collect()
+    }
+
+    fn route(method: &str, path: &str, handler: &str) -> (String, String, String) {
+        (method.to_string(), path.to_string(), handler.to_string())
+    }
+
+    #[test]
+    fn test_let_bound_routers() {
+        let routes = collect_routes(r#"
+            #[tokio::main]
+            async fn main() {
+                let users = Router::new().route("/:id", get(show));
+                let api = Router::new().route("/health", get(health)).nest("/users", users);
+                let mut app = Router::new().nest("/api", api);
+                app = app.route("/", get(root));
+                let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+                axum::serve(listener, app).await.unwrap();
+            }
+        "#);
+        assert_eq!(routes, vec![
+            route("get", "/api/health", "health"),
+            route("get", "/api/users/:id", "show"),
+            route("get", "/", "root"),
+        ]);
+    }
+
+    #[test]
+    fn test_local_router_functions() {
+        let routes = collect_routes(r#"
+            fn user_routes() -> Router<AppState> {
+                Router::new().route("/", get(list).post(create))
+            }
+
+            fn app(state: AppState) -> Router {
+                let app: Router = Router::new().nest("/users", user_routes()).merge(admin_routes());
+                app.with_state(state)
+            }
+
+            fn admin_routes() -> Router<AppState> {
+                Router::new().route("/admin", get(admin)).merge(admin_routes())
+            }
+
+            fn main() {
+                let app = app(AppState::default());
+            }
+        "#);
+        assert_eq!(routes, vec![
+            route("get", "/users/", "list"),
+            route("post", "/users/", "create"),
+            route("get", "/admin", "admin"),
+        ]);
+    }
+
+    #[test]
+    fn test_resolves_router_functions_through_imports() {
+        let dir = std::env::temp_dir().join(format!("axum_doc_router_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (path, content) in [
+            ("src/main.rs", r#"
+                mod api;
+                use api::admin;
+                fn main() {
+                    let app = Router::new()
+                        .nest("/api/v1/users", crate::api::v1::users::routes())
+                        .merge(admin::panel());
+                }
+            "#),
+            ("src/api/mod.rs", r#"
+                pub mod v1;
+                #[path = "admin_routes.rs"]
+                mod admin_impl;
+                pub mod admin { pub use super::admin_impl::routes as panel; }
+            "#),
+            ("src/api/v1/mod.rs", "pub mod users; pub fn health() -> Router { Router::new().route(\"/health\", get(health)) }"),
+            ("src/api/v1/users.rs", r#"
+                use super::health;
+                pub fn routes() -> Router { Router::new().route("/", get(list)).merge(health()) }
+            "#),
+            ("src/api/admin_routes.rs", "pub fn routes() -> Router { Router::new().route(\"/admin\", get(self::dashboard)) }"),
+        ] {
+            let file = dir.join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+        }
+
+        let entry = dir.join("src/main.rs");
+        let index = CrateIndex::build(&dir, &entry).unwrap();
+        let mut visitor = RouterVisitor::new(&dir, &entry).with_index(Rc::new(index));
+        visitor.visit_file(&syn::parse_file(&std::fs::read_to_string(&entry).unwrap()).unwrap());
+
+        let routes: Vec<(String, Option<String>)> = visitor.routes.iter()
+            .map(|route| (route.path.clone(), route.module.as_ref().map(|m| m.join("::"))))
+            .collect();
+        assert_eq!(routes, vec![
+            ("/api/v1/users/".to_string(), Some("api::v1::users".to_string())),
+            ("/api/v1/users/health".to_string(), Some("api::v1".to_string())),
+            ("/admin".to_string(), Some("api::admin_impl".to_string())),
+        ]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_inline_module_routers_collected_where_called() {
+        let dir = std::env::temp_dir().join(format!("axum_doc_router_inline_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let main = r#"
+            async fn status() {}
+            fn main() {
+                let app = Router::new().nest("/test", test::router());
+            }
+            mod test {
+                pub fn router() -> Router { Router::new().route("/status", get(super::status)) }
+            }
+        "#;
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/main.rs"), main).unwrap();
+
+        let entry = dir.join("src/main.rs");
+        let index = CrateIndex::build(&dir, &entry).unwrap();
+        let mut visitor = RouterVisitor::new(&dir, &entry).with_index(Rc::new(index));
+        visitor.visit_file(&syn::parse_file(main).unwrap());
+
+        let routes: Vec<(String, Option<String>)> = visitor.routes.iter()
+            .map(|route| (route.path.clone(), route.handler_path.as_ref().map(|p| p.join("::"))))
+            .collect();
+        assert_eq!(routes, vec![("/test/status".to_string(), Some("status".to_string()))]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
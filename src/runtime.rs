@@ -0,0 +1,132 @@
+//! Serving a spec generated at build time from the documented application
+//! itself (`runtime` feature).
+//!
+//! Generate the spec from `build.rs`, with `axum_doc` as a build dependency
+//! (`default-features = false` keeps the preview server out of the build):
+//!
+//! ```no_run
+//! // build.rs
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let sources = axum_doc::SourceCache::new();
+//!     let spec = axum_doc::Generator::new(std::env::var("CARGO_MANIFEST_DIR")?).generate_with(&sources)?;
+//!     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+//!     std::fs::write(out_dir.join("openapi.json"), spec.to_json_pretty()?)?;
+//!     for file in sources.reads() {
+//!         println!("cargo:rerun-if-changed={}", file.display());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! and mount it in the application, with `axum_doc` as a dependency with
+//! the `runtime` feature (and `default-features = false`):
+//!
+//! ```ignore
+//! let app = Router::new()
+//!     .route("/users", get(list_users))
+//!     .merge(axum_doc::router_from_embedded(include_str!(concat!(env!("OUT_DIR"), "/openapi.json"))));
+//! ```
+//!
+//! The routes are an axum 0.7 [`Router`], so the application has to use
+//! axum 0.7 as well; a router from another axum version can't be merged.
+
+use axum::{http::header, response::Html, routing::get, Router};
+use serde_json::Value;
+
+use crate::ui::{swagger_page, SWAGGER_UI_CSS, SWAGGER_UI_JS};
+
+/// Where an embedded spec and its Swagger UI are mounted.
+#[derive(Debug, Clone)]
+pub struct EmbeddedDocs {
+    /// Path of the JSON spec, `/openapi.json` by default
+    pub spec_path: String,
+    /// Path of the Swagger UI page, `/docs` by default; the UI's bundled
+    /// assets are served under `{ui_path}/assets/`
+    pub ui_path: String,
+}
+
+impl Default for EmbeddedDocs {
+    fn default() -> Self {
+        EmbeddedDocs {
+            spec_path: "/openapi.json".to_string(),
+            ui_path: "/docs".to_string(),
+        }
+    }
+}
+
+impl EmbeddedDocs {
+    /// Routes serving `spec_json` and a Swagger UI page for it, to be merged
+    /// into the application's router.
+    pub fn router<S>(&self, spec_json: &'static str) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let title = serde_json::from_str::<Value>(spec_json).ok()
+            .and_then(|spec| spec.pointer("/info/title").and_then(Value::as_str).map(str::to_string))
+            .unwrap_or_else(|| "API documentation".to_string());
+        let assets = format!("{}/assets", self.ui_path.trim_end_matches('/'));
+        let page = swagger_page(&title, &self.spec_path, &assets, "");
+
+        Router::new()
+            .route(&self.spec_path, get(move || async move { ([(header::CONTENT_TYPE, "application/json")], spec_json) }))
+            .route(&self.ui_path, get(move || async move { Html(page) }))
+            .route(
+                &format!("{}/swagger-ui-bundle.js", assets),
+                get(|| async { ([(header::CONTENT_TYPE, "text/javascript")], SWAGGER_UI_JS) }),
+            )
+            .route(
+                &format!("{}/swagger-ui.css", assets),
+                get(|| async { ([(header::CONTENT_TYPE, "text/css")], SWAGGER_UI_CSS) }),
+            )
+    }
+}
+
+/// Routes serving `spec_json` at `/openapi.json` and Swagger UI at `/docs`,
+/// as an axum 0.7 [`Router`] (the application must use axum 0.7 too).
+/// See [`EmbeddedDocs`] to mount them elsewhere.
+pub fn router_from_embedded<S>(spec_json: &'static str) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    EmbeddedDocs::default().router(spec_json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::{to_bytes, Body}, http::{Request, StatusCode}};
+    use tower::ServiceExt;
+
+    const SPEC: &str = r#"{"openapi": "3.0.0", "info": {"title": "Shop API", "version": "1"}, "paths": {}}"#;
+
+    fn get_body(app: Router, path: &str) -> (StatusCode, String) {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(async {
+            let response = app.oneshot(Request::get(path).body(Body::empty()).unwrap()).await.unwrap();
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, String::from_utf8_lossy(&body).into_owned())
+        })
+    }
+
+    #[test]
+    fn test_router_from_embedded() {
+        let app: Router = Router::new().merge(router_from_embedded(SPEC));
+        assert_eq!(get_body(app.clone(), "/openapi.json"), (StatusCode::OK, SPEC.to_string()));
+
+        let (status, page) = get_body(app.clone(), "/docs");
+        assert_eq!(status, StatusCode::OK);
+        assert!(page.contains("<title>Shop API - Swagger UI</title>"));
+        assert!(page.contains("<script src=\"/docs/assets/swagger-ui-bundle.js\"></script>"));
+        assert_eq!(get_body(app, "/docs/assets/swagger-ui.css").0, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_custom_paths() {
+        let docs = EmbeddedDocs { spec_path: "/api/spec.json".to_string(), ui_path: "/api/docs/".to_string() };
+        let app: Router = docs.router(SPEC);
+        assert_eq!(get_body(app.clone(), "/api/spec.json").0, StatusCode::OK);
+        assert!(get_body(app.clone(), "/api/docs/").1.contains("url: \"/api/spec.json\""));
+        assert_eq!(get_body(app, "/api/docs/assets/swagger-ui-bundle.js").0, StatusCode::OK);
+    }
+}
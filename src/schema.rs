@@ -0,0 +1,923 @@
+//! Rust type to OpenAPI schema mapping.
+
+use std::collections::{HashMap, HashSet};
+use syn::{visit::{self, Visit}, Type, TypePath, PathArguments, GenericArgument};
+use quote::ToTokens;
+use serde_json::{json, Value};
+
+use crate::models::{EnumInfo, EnumTagging, FieldInfo, StructInfo, VariantKind};
+
+/// Maps a Rust type string (as produced by `to_token_stream`) to an OpenAPI schema.
+pub fn rust_type_to_openapi(ty: &str, models: &HashMap<String, StructInfo>) -> Value {
+    // Clean up reference types first
+    let clean_ty = ty.trim()
+        .trim_start_matches("&'static ")
+        .trim_start_matches("& 'static ")
+        .trim_start_matches("&'static")
+        .trim_start_matches("& ")
+        .trim_start_matches("&")
+        .trim_start_matches("mut ")
+        .trim();
+
+    // Configured type overrides take precedence over every built-in mapping
+    if let Some(model) = models.get(&schema_name(clean_ty)).filter(|model| model.schema.is_some()) {
+        return json!({"$ref": format!("#/components/schemas/{}", model.name)});
+    }
+
+    // Handle generics first (order matters - must check before simple types)
+    if let Some(inner_start) = clean_ty.find('<') {
+        let outer_type = &clean_ty[..inner_start];
+        let inner_end = clean_ty.rfind('>').unwrap_or(clean_ty.len());
+        let inner_type = &clean_ty[inner_start + 1..inner_end];
+
+        match outer_type.trim() {
+            "Vec" | "std::vec::Vec" => {
+                return json!({
+                    "type": "array",
+                    "items": rust_type_to_openapi(inner_type, models)
+                });
+            }
+            "Option" | "std::option::Option" => {
+                let mut inner_schema = rust_type_to_openapi(inner_type, models);
+                inner_schema["nullable"] = json!(true);
+                return inner_schema;
+            }
+            "HashMap" | "std::collections::HashMap" => {
+                let parts: Vec<&str> = inner_type.split(',').collect();
+                if parts.len() == 2 {
+                    let value_type = parts[1].trim();
+                    return json!({
+                        "type": "object",
+                        "additionalProperties": rust_type_to_openapi(value_type, models)
+                    });
+                }
+            }
+            // 泛型模型的实例，如 Page<User> -> Page_User
+            _ => {
+                if let Some(model) = models.get(&schema_name(clean_ty)) {
+                    return json!({"$ref": format!("#/components/schemas/{}", model.name)});
+                }
+            }
+        }
+    }
+
+    // Handle simple types
+    match clean_ty {
+        "String" | "&str" | "str" => json!({"type": "string"}),
+        "i8" | "u8" | "i16" | "u16" | "i32" | "u32" => json!({"type": "integer", "format": "int32"}),
+        "i64" | "u64" | "isize" | "usize" => json!({"type": "integer", "format": "int64"}),
+        "f32" => json!({"type": "number", "format": "float"}),
+        "f64" => json!({"type": "number", "format": "double"}),
+        "bool" => json!({"type": "boolean"}),
+        // UUID type (check for uuid::Uuid or just Uuid)
+        t if t.contains("Uuid") => json!({
+            "type": "string",
+            "format": "uuid",
+            "example": "550e8400-e29b-41d4-a716-446655440000"
+        }),
+        // DateTime types
+        t if t.contains("DateTime") || t.contains("chrono") => json!({
+            "type": "string",
+            "format": "date-time",
+            "example": "2024-01-01T00:00:00Z"
+        }),
+        // Duration
+        t if t.contains("Duration") || t.contains("duration") => json!({
+            "type": "string",
+            "format": "duration"
+        }),
+        // Custom types from models
+        _ => {
+            if let Some(model) = models.get(&schema_name(clean_ty)) {
+                json!({"$ref": format!("#/components/schemas/{}", model.name)})
+            } else {
+                // Provide helpful suggestions for common type errors
+                let clean_type_no_spaces = clean_ty.replace(" ", "");
+                let suggestion = if clean_type_no_spaces.starts_with("Json<") {
+                    "Note: Json<T> extractors are not fully supported yet. Consider defining the response type in --model-files".to_string()
+                } else if clean_ty.contains("::") {
+                    format!("Note: Type path '{}' may need to be added to model files", clean_ty)
+                } else {
+                    String::new()
+                };
+
+                if !suggestion.is_empty() {
+                    eprintln!("Warning: Unknown type '{}', defaulting to object. {}", clean_ty, suggestion);
+                } else {
+                    eprintln!("Warning: Unknown type '{}', defaulting to object", clean_ty);
+                }
+                json!({"type": "object"})
+            }
+        }
+    }
+}
+
+/// Component name a type is stored under: the last path segment, with
+/// generic arguments appended (`Page<User>` becomes `Page_User`,
+/// `ApiResponse<Vec<User>>` becomes `ApiResponse_Vec_User`). `crate::`
+/// paths, which the crate index keeps for clashing type names, are named
+/// after their module path (`crate::users::Item` becomes `users.Item`).
+pub fn schema_name(ty: &str) -> String {
+    match syn::parse_str::<Type>(ty) {
+        Ok(parsed) => type_schema_name(&parsed),
+        Err(_) => ty.replace(' ', ""),
+    }
+}
+
+fn type_schema_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(type_path) => match type_path.path.segments.last() {
+            Some(segment) => {
+                let mut name = path_name(&type_path.path);
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    for arg in &args.args {
+                        if let GenericArgument::Type(arg) = arg {
+                            name.push('_');
+                            name.push_str(&type_schema_name(arg));
+                        }
+                    }
+                }
+                name
+            }
+            None => String::new(),
+        },
+        Type::Reference(reference) => type_schema_name(&reference.elem),
+        Type::Paren(paren) => type_schema_name(&paren.elem),
+        Type::Group(group) => type_schema_name(&group.elem),
+        Type::Slice(slice) => format!("Array_{}", type_schema_name(&slice.elem)),
+        Type::Array(array) => format!("Array_{}", type_schema_name(&array.elem)),
+        Type::Tuple(tuple) => tuple.elems.iter().map(type_schema_name).collect::<Vec<_>>().join("_"),
+        other => other.to_token_stream().to_string()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '_')
+            .collect(),
+    }
+}
+
+/// Returns `models` plus a concrete model for every instantiation of a
+/// generic model used by `roots` or by a non-generic model, named after
+/// [`schema_name`] (`Page<User>` is added as `Page_User`). Instantiations
+/// used by the new models' fields are added as well.
+pub fn instantiate_generics<I, S>(roots: I, models: &HashMap<String, StructInfo>) -> HashMap<String, StructInfo>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut instantiated = models.clone();
+    let mut pending: Vec<String> = roots.into_iter().map(|ty| ty.as_ref().to_string()).collect();
+    for model in models.values().filter(|model| model.generics.is_empty()) {
+        pending.extend(model.field_types().into_iter().map(String::from));
+    }
+
+    let mut seen = HashSet::new();
+    while let Some(ty) = pending.pop() {
+        if !seen.insert(ty.clone()) {
+            continue;
+        }
+        let Ok(parsed) = syn::parse_str::<Type>(&ty) else { continue };
+        let mut uses = GenericUses { models, found: Vec::new() };
+        uses.visit_type(&parsed);
+
+        for (template, ty) in uses.found {
+            let args: Vec<Type> = match &ty.path.segments.last().expect("matched on the last segment").arguments {
+                PathArguments::AngleBracketed(args) => args.args.iter()
+                    .filter_map(|arg| match arg {
+                        GenericArgument::Type(arg) => Some(arg.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            if args.len() != template.generics.len() {
+                eprintln!("Warning: '{}' expects {} type argument(s), skipping instantiation",
+                         template.name, template.generics.len());
+                continue;
+            }
+            let name = type_schema_name(&Type::Path(ty));
+            if instantiated.contains_key(&name) {
+                continue;
+            }
+            let instance = template.instantiate(&name, &args);
+            pending.extend(instance.field_types().into_iter().map(String::from));
+            instantiated.insert(name, instance);
+        }
+    }
+    instantiated
+}
+
+/// Name of a type path without its generic arguments, qualified like
+/// [`schema_name`] for `crate::` paths.
+pub(crate) fn path_name(path: &syn::Path) -> String {
+    let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    match segments.split_first() {
+        Some((first, rest)) if first == "crate" && !rest.is_empty() => rest.join("."),
+        _ => segments.last().cloned().unwrap_or_default(),
+    }
+}
+
+// Collects every path in a type that names a generic model
+struct GenericUses<'a> {
+    models: &'a HashMap<String, StructInfo>,
+    found: Vec<(&'a StructInfo, TypePath)>,
+}
+
+impl<'ast, 'a> Visit<'ast> for GenericUses<'a> {
+    fn visit_type_path(&mut self, type_path: &'ast TypePath) {
+        if let Some(model) = self.models.get(&path_name(&type_path.path)) {
+            if !model.generics.is_empty() {
+                self.found.push((model, type_path.clone()));
+            }
+        }
+        visit::visit_type_path(self, type_path);
+    }
+}
+
+/// Generate OpenAPI schema definitions from models
+pub fn generate_schemas(models: &HashMap<String, StructInfo>) -> Value {
+    let mut schemas = json!({});
+
+    // 泛型模型本身不输出，只输出其实例
+    for info in models.values().filter(|info| info.generics.is_empty()) {
+        if let Some(schema) = &info.schema {
+            schemas[&info.name] = schema.clone();
+            continue;
+        }
+        schemas[&info.name] = match &info.enum_info {
+            Some(enum_info) => enum_schema(enum_info, models),
+            // Newtype 和 transparent 结构体序列化为其唯一字段
+            None if info.is_transparent() => rust_type_to_openapi(&info.fields[0].ty, models),
+            // 元组结构体序列化为数组
+            None if info.is_tuple() => {
+                let types: Vec<String> = info.fields.iter().map(|f| f.ty.clone()).collect();
+                tuple_schema(&types, models)
+            }
+            None => {
+                let mut schema = object_schema(&info.fields, models);
+                if info.serde.deny_unknown_fields && schema.get("allOf").is_none() {
+                    schema["additionalProperties"] = json!(false);
+                }
+                schema
+            }
+        };
+    }
+
+    schemas
+}
+
+/// Object schema for named fields, applying serde renames, skips and
+/// defaults. Flattened fields are merged in through `allOf`, or as
+/// `additionalProperties` when they are maps.
+fn object_schema(fields: &[FieldInfo], models: &HashMap<String, StructInfo>) -> Value {
+    let mut properties = json!({});
+    let mut required = Vec::new();
+    let mut flattened = Vec::new();
+
+    for field in fields {
+        if field.serde.skip {
+            continue;
+        }
+        let mut schema = rust_type_to_openapi(&field.ty, models);
+        if field.serde.flatten {
+            flattened.push(schema);
+            continue;
+        }
+        if field.serde.skip_serializing {
+            schema = with_keyword(schema, "writeOnly", json!(true));
+        } else if field.serde.skip_deserializing {
+            schema = with_keyword(schema, "readOnly", json!(true));
+        }
+        if field.is_required() {
+            required.push(field.wire_name().to_string());
+        }
+        properties[field.wire_name()] = schema;
+    }
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties
+    });
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+
+    let mut all_of = Vec::new();
+    for flat in flattened {
+        let is_map = flat.get("additionalProperties").is_some() && flat.get("properties").is_none();
+        if is_map {
+            schema["additionalProperties"] = flat["additionalProperties"].clone();
+        } else {
+            all_of.push(flat);
+        }
+    }
+    if all_of.is_empty() {
+        schema
+    } else {
+        all_of.push(schema);
+        json!({"allOf": all_of})
+    }
+}
+
+// Adds a keyword to a schema; `$ref` siblings are ignored in OpenAPI 3.0, so
+// references are wrapped in `allOf` first
+fn with_keyword(mut schema: Value, key: &str, value: Value) -> Value {
+    if schema.get("$ref").is_some() {
+        schema = json!({"allOf": [schema]});
+    }
+    schema[key] = value;
+    schema
+}
+
+/// Schema for an enum following serde's representation for its tagging mode.
+fn enum_schema(enum_info: &EnumInfo, models: &HashMap<String, StructInfo>) -> Value {
+    let all_unit = enum_info.variants.iter().all(|v| matches!(v.kind, VariantKind::Unit));
+
+    let variants: Vec<Value> = match &enum_info.tagging {
+        EnumTagging::External => {
+            // 单元变体序列化为字符串
+            let unit_names: Vec<&str> = enum_info.variants.iter()
+                .filter(|v| matches!(v.kind, VariantKind::Unit))
+                .map(|v| v.wire_name())
+                .collect();
+            if all_unit {
+                return json!({"type": "string", "enum": unit_names});
+            }
+
+            let mut variants = Vec::new();
+            if !unit_names.is_empty() {
+                variants.push(json!({"type": "string", "enum": unit_names}));
+            }
+            for variant in &enum_info.variants {
+                if let Some(content) = variant_content_schema(&variant.kind, models) {
+                    variants.push(json!({
+                        "type": "object",
+                        "properties": { variant.wire_name(): content },
+                        "required": [variant.wire_name()]
+                    }));
+                }
+            }
+            variants
+        }
+        EnumTagging::Internal { tag } => {
+            let mut variants = Vec::new();
+            for variant in &enum_info.variants {
+                let tag_schema = tag_object_schema(tag, variant.wire_name());
+                match &variant.kind {
+                    VariantKind::Unit => variants.push(tag_schema),
+                    VariantKind::Struct(fields) => {
+                        let mut schema = object_schema(fields, models);
+                        if schema.get("allOf").is_some() {
+                            // 含 flatten 字段时无法直接合并，改用 allOf 组合标签
+                            variants.push(json!({"allOf": [tag_schema, schema]}));
+                            continue;
+                        }
+                        schema["properties"][tag.as_str()] = tag_schema["properties"][tag.as_str()].clone();
+                        let mut required = vec![json!(tag)];
+                        if let Some(Value::Array(existing)) = schema.get("required") {
+                            required.extend(existing.iter().cloned());
+                        }
+                        schema["required"] = json!(required);
+                        variants.push(schema);
+                    }
+                    VariantKind::Newtype(ty) => {
+                        variants.push(json!({"allOf": [rust_type_to_openapi(ty, models), tag_schema]}));
+                    }
+                    VariantKind::Tuple(_) => {
+                        eprintln!("Warning: Tuple variant '{}' cannot be internally tagged, skipping", variant.name);
+                    }
+                }
+            }
+            return json!({"oneOf": variants, "discriminator": {"propertyName": tag}});
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            let mut variants = Vec::new();
+            for variant in &enum_info.variants {
+                let mut schema = tag_object_schema(tag, variant.wire_name());
+                if let Some(content_schema) = variant_content_schema(&variant.kind, models) {
+                    schema["properties"][content.as_str()] = content_schema;
+                    schema["required"] = json!([tag, content]);
+                }
+                variants.push(schema);
+            }
+            return json!({"oneOf": variants, "discriminator": {"propertyName": tag}});
+        }
+        EnumTagging::Untagged => enum_info.variants.iter()
+            .map(|variant| {
+                // 无标签的单元变体序列化为 null
+                variant_content_schema(&variant.kind, models)
+                    .unwrap_or_else(|| json!({"nullable": true, "enum": [null]}))
+            })
+            .collect(),
+    };
+
+    json!({"oneOf": variants})
+}
+
+// Schema of what a variant carries; `None` for unit variants
+fn variant_content_schema(kind: &VariantKind, models: &HashMap<String, StructInfo>) -> Option<Value> {
+    match kind {
+        VariantKind::Unit => None,
+        VariantKind::Newtype(ty) => Some(rust_type_to_openapi(ty, models)),
+        VariantKind::Tuple(tys) => Some(tuple_schema(tys, models)),
+        VariantKind::Struct(fields) => Some(object_schema(fields, models)),
+    }
+}
+
+// Fixed-length array; OpenAPI 3.0 has no tuple keyword, so positions share one items schema
+fn tuple_schema(tys: &[String], models: &HashMap<String, StructInfo>) -> Value {
+    let mut items: Vec<Value> = Vec::new();
+    for schema in tys.iter().map(|ty| rust_type_to_openapi(ty, models)) {
+        if !items.contains(&schema) {
+            items.push(schema);
+        }
+    }
+    let items = if items.len() == 1 { items.remove(0) } else { json!({"oneOf": items}) };
+    json!({
+        "type": "array",
+        "items": items,
+        "minItems": tys.len(),
+        "maxItems": tys.len()
+    })
+}
+
+fn tag_object_schema(tag: &str, variant: &str) -> Value {
+    json!({
+        "type": "object",
+        "properties": { tag: {"type": "string", "enum": [variant]} },
+        "required": [tag]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::models::parse_models;
+
+    #[test]
+    fn test_rust_type_to_openapi_primitives() {
+        let models = HashMap::new();
+
+        // Test String
+        let schema = rust_type_to_openapi("String", &models);
+        assert_eq!(schema["type"], "string");
+
+        // Test i32
+        let schema = rust_type_to_openapi("i32", &models);
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["format"], "int32");
+
+        // Test i64
+        let schema = rust_type_to_openapi("i64", &models);
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["format"], "int64");
+
+        // Test f32
+        let schema = rust_type_to_openapi("f32", &models);
+        assert_eq!(schema["type"], "number");
+        assert_eq!(schema["format"], "float");
+
+        // Test f64
+        let schema = rust_type_to_openapi("f64", &models);
+        assert_eq!(schema["type"], "number");
+        assert_eq!(schema["format"], "double");
+
+        // Test bool
+        let schema = rust_type_to_openapi("bool", &models);
+        assert_eq!(schema["type"], "boolean");
+    }
+
+    #[test]
+    fn test_rust_type_to_openapi_uuid() {
+        let models = HashMap::new();
+        let schema = rust_type_to_openapi("Uuid", &models);
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["format"], "uuid");
+        assert_eq!(schema["example"], "550e8400-e29b-41d4-a716-446655440000");
+    }
+
+    #[test]
+    fn test_rust_type_to_openapi_datetime() {
+        let models = HashMap::new();
+        let schema = rust_type_to_openapi("DateTime", &models);
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["format"], "date-time");
+        assert_eq!(schema["example"], "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_rust_type_to_openapi_duration() {
+        let models = HashMap::new();
+        let schema = rust_type_to_openapi("Duration", &models);
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["format"], "duration");
+    }
+
+    #[test]
+    fn test_rust_type_to_openapi_vec() {
+        let models = HashMap::new();
+
+        // Test Vec<String>
+        let schema = rust_type_to_openapi("Vec<String>", &models);
+        assert_eq!(schema["type"], "array");
+        assert_eq!(schema["items"]["type"], "string");
+
+        // Test Vec<i32>
+        let schema = rust_type_to_openapi("Vec<i32>", &models);
+        assert_eq!(schema["type"], "array");
+        assert_eq!(schema["items"]["type"], "integer");
+        assert_eq!(schema["items"]["format"], "int32");
+    }
+
+    #[test]
+    fn test_rust_type_to_openapi_option() {
+        let models = HashMap::new();
+
+        // Test Option<String>
+        let schema = rust_type_to_openapi("Option<String>", &models);
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["nullable"], true);
+
+        // Test Option<i64>
+        let schema = rust_type_to_openapi("Option<i64>", &models);
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["format"], "int64");
+        assert_eq!(schema["nullable"], true);
+    }
+
+    #[test]
+    fn test_rust_type_to_openapi_hashmap() {
+        let models = HashMap::new();
+
+        // Test HashMap<String, i32>
+        let schema = rust_type_to_openapi("HashMap<String, i32>", &models);
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["additionalProperties"]["type"], "integer");
+        assert_eq!(schema["additionalProperties"]["format"], "int32");
+    }
+
+    #[test]
+    fn test_rust_type_to_openapi_custom_type() {
+        let mut models = HashMap::new();
+        models.insert("User".to_string(), StructInfo {
+            name: "User".to_string(),
+            generics: Vec::new(),
+            fields: vec![],
+            enum_info: None,
+            serde: Default::default(),
+            schema: None,
+        });
+
+        let schema = rust_type_to_openapi("User", &models);
+        assert_eq!(schema["$ref"], "#/components/schemas/User");
+    }
+
+    #[test]
+    fn test_nested_generic_types() {
+        let models = HashMap::new();
+
+        // Test Vec<Vec<String>>
+        let schema = rust_type_to_openapi("Vec<Vec<String>>", &models);
+        assert_eq!(schema["type"], "array");
+        assert_eq!(schema["items"]["type"], "array");
+        assert_eq!(schema["items"]["items"]["type"], "string");
+
+        // Test Option<Vec<i32>>
+        let schema = rust_type_to_openapi("Option<Vec<i32>>", &models);
+        assert_eq!(schema["type"], "array");
+        assert_eq!(schema["nullable"], true);
+        assert_eq!(schema["items"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_unknown_type_fallback() {
+        let models = HashMap::new();
+
+        // Test unknown type falls back to object
+        let schema = rust_type_to_openapi("UnknownType", &models);
+        assert_eq!(schema["type"], "object");
+    }
+
+    #[test]
+    fn test_usize_isize_types() {
+        let models = HashMap::new();
+
+        // Test usize
+        let schema = rust_type_to_openapi("usize", &models);
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["format"], "int64");
+
+        // Test isize
+        let schema = rust_type_to_openapi("isize", &models);
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["format"], "int64");
+    }
+
+    #[test]
+    fn test_and_str_type() {
+        let models = HashMap::new();
+
+        // Test &str
+        let schema = rust_type_to_openapi("&str", &models);
+        assert_eq!(schema["type"], "string");
+    }
+
+    #[test]
+    fn test_complex_hashmap() {
+        let models = HashMap::new();
+
+        // Test HashMap<String, Vec<i32>>
+        let schema = rust_type_to_openapi("HashMap<String, Vec<i32>>", &models);
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["additionalProperties"]["type"], "array");
+        assert_eq!(schema["additionalProperties"]["items"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_generic_types_with_spaces() {
+        let models = HashMap::new();
+
+        // Test Vec<String> with spaces (real-world case from syn::to_token_stream())
+        let schema = rust_type_to_openapi("Vec < String >", &models);
+        assert_eq!(schema["type"], "array", "Vec with spaces should be array");
+        assert_eq!(schema["items"]["type"], "string");
+
+        // Test Option<i64> with spaces
+        let schema = rust_type_to_openapi("Option < i64 >", &models);
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["format"], "int64");
+        assert_eq!(schema["nullable"], true);
+
+        // Test HashMap<String, i32> with spaces
+        let schema = rust_type_to_openapi("HashMap < String , i32 >", &models);
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["additionalProperties"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_generic_types_std_paths() {
+        let models = HashMap::new();
+
+        // Test std::vec::Vec<String>
+        let schema = rust_type_to_openapi("std::vec::Vec<String>", &models);
+        assert_eq!(schema["type"], "array");
+
+        // Test std::option::Option<i64>
+        let schema = rust_type_to_openapi("std::option::Option<i64>", &models);
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["nullable"], true);
+
+        // Test std::collections::HashMap<String, i32>
+        let schema = rust_type_to_openapi("std::collections::HashMap<String, i32>", &models);
+        assert_eq!(schema["type"], "object");
+    }
+
+    // P0-2: Reference type mapping tests
+    #[test]
+    fn test_rust_type_to_openapi_reference_types() {
+        let models = HashMap::new();
+
+        // Test &'static str
+        let schema = rust_type_to_openapi("&'static str", &models);
+        assert_eq!(schema["type"], "string");
+
+        // Test &str
+        let schema = rust_type_to_openapi("&str", &models);
+        assert_eq!(schema["type"], "string");
+
+        // Test &String
+        let schema = rust_type_to_openapi("&String", &models);
+        assert_eq!(schema["type"], "string");
+
+        // Test & 'static str (with space)
+        let schema = rust_type_to_openapi("& 'static str", &models);
+        assert_eq!(schema["type"], "string");
+    }
+
+    // P1-2: Helper function tests
+    #[test]
+    fn test_generate_schemas_empty() {
+        let models = HashMap::new();
+        let schemas = generate_schemas(&models);
+        assert_eq!(schemas.as_object().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_generate_schemas_single_struct() {
+        let mut models = HashMap::new();
+        models.insert("User".to_string(), StructInfo {
+            name: "User".to_string(),
+            generics: Vec::new(),
+            fields: vec![
+                FieldInfo {
+                    name: "id".to_string(),
+                    ty: "String".to_string(),
+                    serde: Default::default(),
+                },
+                FieldInfo {
+                    name: "age".to_string(),
+                    ty: "i32".to_string(),
+                    serde: Default::default(),
+                },
+            ],
+            enum_info: None,
+            serde: Default::default(),
+            schema: None,
+        });
+
+        let schemas = generate_schemas(&models);
+        assert!(schemas.as_object().unwrap().contains_key("User"));
+
+        let user_schema = &schemas["User"];
+        assert_eq!(user_schema["type"], "object");
+        assert!(user_schema["properties"].is_object());
+        assert_eq!(user_schema["properties"]["id"]["type"], "string");
+        assert_eq!(user_schema["properties"]["age"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_generate_schemas_unit_enum() {
+        let models = parse_models("enum Status { Active, Disabled }");
+        let schemas = generate_schemas(&models);
+        assert_eq!(schemas["Status"]["type"], "string");
+        assert_eq!(schemas["Status"]["enum"], json!(["Active", "Disabled"]));
+    }
+
+    #[test]
+    fn test_generate_schemas_externally_tagged_enum() {
+        let models = parse_models(r#"
+            enum Shape { Empty, Circle(f64), Rect { w: f64, h: f64 }, Point(i32, i32) }
+        "#);
+        let schemas = generate_schemas(&models);
+        let variants = schemas["Shape"]["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 4);
+        assert_eq!(variants[0], json!({"type": "string", "enum": ["Empty"]}));
+        assert_eq!(variants[1]["properties"]["Circle"]["type"], "number");
+        assert_eq!(variants[1]["required"], json!(["Circle"]));
+        assert_eq!(variants[2]["properties"]["Rect"]["properties"]["w"]["type"], "number");
+        assert_eq!(variants[3]["properties"]["Point"]["type"], "array");
+        assert_eq!(variants[3]["properties"]["Point"]["minItems"], 2);
+    }
+
+    #[test]
+    fn test_generate_schemas_internally_tagged_enum() {
+        let models = parse_models(r#"
+            #[serde(tag = "type")]
+            enum Event { Created { id: u64 }, Deleted, Moved(Location) }
+            struct Location { x: i32 }
+        "#);
+        let schemas = generate_schemas(&models);
+        let event = &schemas["Event"];
+        assert_eq!(event["discriminator"]["propertyName"], "type");
+
+        let variants = event["oneOf"].as_array().unwrap();
+        assert_eq!(variants[0]["properties"]["type"]["enum"], json!(["Created"]));
+        assert_eq!(variants[0]["properties"]["id"]["type"], "integer");
+        assert_eq!(variants[0]["required"], json!(["type", "id"]));
+        assert_eq!(variants[1]["properties"]["type"]["enum"], json!(["Deleted"]));
+        assert_eq!(variants[2]["allOf"][0]["$ref"], "#/components/schemas/Location");
+    }
+
+    #[test]
+    fn test_generate_schemas_adjacently_tagged_enum() {
+        let models = parse_models(r#"
+            #[serde(tag = "kind", content = "data")]
+            enum Message { Ping, Text(String) }
+        "#);
+        let schemas = generate_schemas(&models);
+        let message = &schemas["Message"];
+        assert_eq!(message["discriminator"]["propertyName"], "kind");
+
+        let variants = message["oneOf"].as_array().unwrap();
+        assert_eq!(variants[0]["required"], json!(["kind"]));
+        assert_eq!(variants[1]["properties"]["data"]["type"], "string");
+        assert_eq!(variants[1]["required"], json!(["kind", "data"]));
+    }
+
+    #[test]
+    fn test_generate_schemas_untagged_enum() {
+        let models = parse_models(r#"
+            #[serde(untagged)]
+            enum Id { Number(u64), Text(String) }
+        "#);
+        let schemas = generate_schemas(&models);
+        let variants = schemas["Id"]["oneOf"].as_array().unwrap();
+        assert_eq!(variants[0]["type"], "integer");
+        assert_eq!(variants[1]["type"], "string");
+        assert!(schemas["Id"]["discriminator"].is_null());
+    }
+
+    #[test]
+    fn test_generate_schemas_serde_renames_and_skips() {
+        let models = parse_models(r#"
+            #[serde(rename_all = "camelCase")]
+            struct Account {
+                user_id: u64,
+                #[serde(rename = "mail")]
+                email_address: String,
+                #[serde(skip)]
+                cache: String,
+                #[serde(skip_serializing)]
+                password: String,
+                #[serde(skip_deserializing)]
+                created_at: String,
+                #[serde(default)]
+                nickname: String,
+                #[serde(skip_serializing_if = "Vec::is_empty")]
+                tags: Vec<String>,
+                bio: Option<String>,
+            }
+        "#);
+        let schema = &generate_schemas(&models)["Account"];
+        let properties = schema["properties"].as_object().unwrap();
+
+        assert!(properties.contains_key("userId"));
+        assert!(properties.contains_key("mail"));
+        assert!(!properties.contains_key("cache"));
+        assert_eq!(properties["password"]["writeOnly"], true);
+        assert_eq!(properties["createdAt"]["readOnly"], true);
+        assert_eq!(schema["required"], json!(["userId", "mail", "password", "createdAt"]));
+    }
+
+    #[test]
+    fn test_generate_schemas_serde_flatten() {
+        let models = parse_models(r#"
+            struct Page { page: u32 }
+            struct Listing {
+                name: String,
+                #[serde(flatten)]
+                page: Page,
+                #[serde(flatten)]
+                extra: HashMap<String, String>,
+            }
+        "#);
+        let schema = &generate_schemas(&models)["Listing"];
+        let all_of = schema["allOf"].as_array().unwrap();
+        assert_eq!(all_of[0]["$ref"], "#/components/schemas/Page");
+        assert_eq!(all_of[1]["properties"]["name"]["type"], "string");
+        assert!(all_of[1]["properties"].get("page").is_none());
+        assert_eq!(all_of[1]["additionalProperties"]["type"], "string");
+    }
+
+    #[test]
+    fn test_generate_schemas_serde_container_attrs() {
+        let models = parse_models(r#"
+            #[serde(default, deny_unknown_fields)]
+            struct Settings { verbose: bool }
+            struct UserId(u64);
+            #[serde(transparent)]
+            struct Email { value: String }
+            struct Pair(String, u32);
+            #[serde(rename_all = "snake_case")]
+            enum Status { InProgress, #[serde(rename = "done")] Finished, #[serde(skip)] Internal }
+        "#);
+        let schemas = generate_schemas(&models);
+
+        assert!(schemas["Settings"]["required"].is_null());
+        assert_eq!(schemas["Settings"]["additionalProperties"], false);
+        assert_eq!(schemas["UserId"]["type"], "integer");
+        assert_eq!(schemas["Email"]["type"], "string");
+        assert_eq!(schemas["Pair"]["type"], "array");
+        assert_eq!(schemas["Pair"]["maxItems"], 2);
+        assert_eq!(schemas["Status"]["enum"], json!(["in_progress", "done"]));
+    }
+
+    #[test]
+    fn test_schema_name() {
+        assert_eq!(schema_name("User"), "User");
+        assert_eq!(schema_name("models :: User"), "User");
+        assert_eq!(schema_name("Page < User >"), "Page_User");
+        assert_eq!(schema_name("ApiResponse < Vec < models :: User > >"), "ApiResponse_Vec_User");
+        // 重名类型由 crate 索引保留为 crate 路径
+        assert_eq!(schema_name("crate :: types :: User"), "types.User");
+        assert_eq!(schema_name("Page < crate :: users :: Item >"), "Page_users.Item");
+        assert_eq!(schema_name("Pair < String , u32 >"), "Pair_String_u32");
+    }
+
+    #[test]
+    fn test_instantiate_generics() {
+        let models = parse_models(r#"
+            struct Page<T> { items: Vec<T>, total: u64 }
+            struct ApiResponse<T> { data: T, error: Option<String> }
+            enum Either<L, R> { Left(L), Right { value: R } }
+            struct User { id: u64 }
+            struct Report { pages: Page<Either<User, String>> }
+        "#);
+        let models = instantiate_generics(["Json < ApiResponse < Page < User > > >"], &models);
+        let schemas = generate_schemas(&models);
+
+        assert!(schemas.get("Page").is_none());
+        assert!(schemas.get("ApiResponse").is_none());
+        assert_eq!(schemas["ApiResponse_Page_User"]["properties"]["data"]["$ref"], "#/components/schemas/Page_User");
+        assert_eq!(schemas["ApiResponse_Page_User"]["required"], json!(["data"]));
+        assert_eq!(schemas["Page_User"]["properties"]["items"]["items"]["$ref"], "#/components/schemas/User");
+        assert_eq!(schemas["Page_User"]["properties"]["total"]["type"], "integer");
+
+        // Instantiations reached only through another model's fields
+        assert_eq!(schemas["Report"]["properties"]["pages"]["$ref"], "#/components/schemas/Page_Either_User_String");
+        assert_eq!(schemas["Page_Either_User_String"]["properties"]["items"]["items"]["$ref"],
+                   "#/components/schemas/Either_User_String");
+        let variants = schemas["Either_User_String"]["oneOf"].as_array().unwrap();
+        assert_eq!(variants[0]["properties"]["Left"]["$ref"], "#/components/schemas/User");
+        assert_eq!(variants[1]["properties"]["Right"]["properties"]["value"]["type"], "string");
+    }
+}
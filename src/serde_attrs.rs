@@ -0,0 +1,283 @@
+//! Reading `#[serde(...)]` attributes off models.
+
+use syn::Attribute;
+
+/// Container-level serde attributes of a struct or enum.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContainerAttrs {
+    /// `#[serde(tag = "...")]`
+    pub tag: Option<String>,
+    /// `#[serde(content = "...")]`
+    pub content: Option<String>,
+    /// `#[serde(untagged)]`
+    pub untagged: bool,
+    /// `#[serde(rename_all = "...")]`, the serialize-side rule
+    pub rename_all: Option<String>,
+    /// `#[serde(rename_all_fields = "...")]`, applied to struct variant fields
+    pub rename_all_fields: Option<String>,
+    /// `#[serde(default)]`: every field may be omitted
+    pub default: bool,
+    /// `#[serde(transparent)]`: serialized as its single field
+    pub transparent: bool,
+    /// `#[serde(deny_unknown_fields)]`
+    pub deny_unknown_fields: bool,
+}
+
+/// Field- or variant-level serde attributes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldAttrs {
+    /// Explicit `rename`, or the name produced by the container's `rename_all`
+    pub rename: Option<String>,
+    /// `skip`, or both `skip_serializing` and `skip_deserializing`
+    pub skip: bool,
+    pub skip_serializing: bool,
+    pub skip_deserializing: bool,
+    /// `skip_serializing_if = "..."`: may be absent from output
+    pub skip_serializing_if: bool,
+    /// `default` or `default = "..."`: may be absent from input
+    pub default: bool,
+    pub flatten: bool,
+    /// Variant-level `rename_all`, applied to a struct variant's fields
+    pub rename_all: Option<String>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Self {
+        let mut parsed = ContainerAttrs::default();
+        for_each_serde_meta(attrs, |meta| {
+            if meta.path.is_ident("tag") {
+                parsed.tag = Some(string_value(&meta)?);
+            } else if meta.path.is_ident("content") {
+                parsed.content = Some(string_value(&meta)?);
+            } else if meta.path.is_ident("untagged") {
+                parsed.untagged = true;
+            } else if meta.path.is_ident("rename_all") {
+                parsed.rename_all = serialize_value(&meta)?;
+            } else if meta.path.is_ident("rename_all_fields") {
+                parsed.rename_all_fields = serialize_value(&meta)?;
+            } else if meta.path.is_ident("default") {
+                parsed.default = true;
+                skip_value(&meta)?;
+            } else if meta.path.is_ident("transparent") {
+                parsed.transparent = true;
+            } else if meta.path.is_ident("deny_unknown_fields") {
+                parsed.deny_unknown_fields = true;
+            } else {
+                skip_value(&meta)?;
+            }
+            Ok(())
+        });
+        parsed
+    }
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Self {
+        let mut parsed = FieldAttrs::default();
+        for_each_serde_meta(attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                parsed.rename = serialize_value(&meta)?;
+            } else if meta.path.is_ident("skip") {
+                parsed.skip = true;
+            } else if meta.path.is_ident("skip_serializing") {
+                parsed.skip_serializing = true;
+            } else if meta.path.is_ident("skip_deserializing") {
+                parsed.skip_deserializing = true;
+            } else if meta.path.is_ident("skip_serializing_if") {
+                parsed.skip_serializing_if = true;
+                skip_value(&meta)?;
+            } else if meta.path.is_ident("default") {
+                parsed.default = true;
+                skip_value(&meta)?;
+            } else if meta.path.is_ident("flatten") {
+                parsed.flatten = true;
+            } else if meta.path.is_ident("rename_all") {
+                parsed.rename_all = serialize_value(&meta)?;
+            } else {
+                skip_value(&meta)?;
+            }
+            Ok(())
+        });
+        if parsed.skip_serializing && parsed.skip_deserializing {
+            parsed.skip = true;
+        }
+        parsed
+    }
+}
+
+/// Applies a serde `rename_all` rule to a field (`is_variant == false`,
+/// snake_case source) or variant (`is_variant == true`, PascalCase source)
+/// name. Unknown rules leave the name unchanged.
+pub fn apply_rename_rule(rule: &str, name: &str, is_variant: bool) -> String {
+    let name = name.trim_start_matches("r#");
+    if is_variant {
+        let snake = pascal_to_snake(name);
+        match rule {
+            "lowercase" => name.to_ascii_lowercase(),
+            "UPPERCASE" => name.to_ascii_uppercase(),
+            "camelCase" => lowercase_first(name),
+            "snake_case" => snake,
+            "SCREAMING_SNAKE_CASE" => snake.to_ascii_uppercase(),
+            "kebab-case" => snake.replace('_', "-"),
+            "SCREAMING-KEBAB-CASE" => snake.to_ascii_uppercase().replace('_', "-"),
+            _ => name.to_string(),
+        }
+    } else {
+        match rule {
+            "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+            "PascalCase" => snake_to_pascal(name),
+            "camelCase" => lowercase_first(&snake_to_pascal(name)),
+            "kebab-case" => name.replace('_', "-"),
+            "SCREAMING-KEBAB-CASE" => name.to_ascii_uppercase().replace('_', "-"),
+            _ => name.to_string(),
+        }
+    }
+}
+
+fn pascal_to_snake(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, ch) in name.char_indices() {
+        if i > 0 && ch.is_uppercase() {
+            snake.push('_');
+        }
+        snake.push(ch.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn snake_to_pascal(name: &str) -> String {
+    let mut pascal = String::new();
+    let mut capitalize = true;
+    for ch in name.chars() {
+        if ch == '_' {
+            capitalize = true;
+        } else if capitalize {
+            pascal.push(ch.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            pascal.push(ch);
+        }
+    }
+    pascal
+}
+
+fn lowercase_first(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Runs `f` on every nested meta of every `#[serde(...)]` attribute. Malformed
+// attributes are ignored: rustc would reject them anyway.
+fn for_each_serde_meta<F>(attrs: &[Attribute], mut f: F)
+where
+    F: FnMut(syn::meta::ParseNestedMeta) -> syn::Result<()>,
+{
+    for attr in attrs {
+        if attr.path().is_ident("serde") {
+            let _ = attr.parse_nested_meta(&mut f);
+        }
+    }
+}
+
+fn string_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<String> {
+    let lit: syn::LitStr = meta.value()?.parse()?;
+    Ok(lit.value())
+}
+
+// Reads `= "x"` or `(serialize = "x", deserialize = "y")`, keeping the serialize side
+fn serialize_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Option<String>> {
+    if meta.input.peek(syn::Token![=]) {
+        return string_value(meta).map(Some);
+    }
+    let mut value = None;
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("serialize") {
+            value = Some(string_value(&nested)?);
+        } else {
+            skip_value(&nested)?;
+        }
+        Ok(())
+    })?;
+    Ok(value)
+}
+
+// Consumes `= value` or `(...)` for attributes we don't interpret
+fn skip_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        let _: syn::Expr = meta.value()?.parse()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        content.step(|cursor| {
+            let mut rest = *cursor;
+            while let Some((_, next)) = rest.token_tree() {
+                rest = next;
+            }
+            Ok(((), rest))
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_attrs_tagging() {
+        let item: syn::ItemEnum = syn::parse_quote! {
+            #[derive(Serialize)]
+            #[serde(tag = "type", content = "data", rename_all = "snake_case")]
+            enum Event { A }
+        };
+        let attrs = ContainerAttrs::parse(&item.attrs);
+        assert_eq!(attrs.tag.as_deref(), Some("type"));
+        assert_eq!(attrs.content.as_deref(), Some("data"));
+        assert_eq!(attrs.rename_all.as_deref(), Some("snake_case"));
+        assert!(!attrs.untagged);
+
+        let item: syn::ItemEnum = syn::parse_quote! {
+            #[serde(untagged)]
+            enum Value { A }
+        };
+        assert!(ContainerAttrs::parse(&item.attrs).untagged);
+    }
+
+    #[test]
+    fn test_field_attrs() {
+        let item: syn::ItemStruct = syn::parse_quote! {
+            struct User {
+                #[serde(rename(serialize = "userName", deserialize = "user_name"))]
+                name: String,
+                #[serde(default = "default_age", skip_serializing_if = "Option::is_none")]
+                age: Option<u8>,
+                #[serde(skip_serializing, skip_deserializing)]
+                secret: String,
+                #[serde(flatten)]
+                extra: Extra,
+            }
+        };
+        let fields: Vec<FieldAttrs> = item.fields.iter().map(|f| FieldAttrs::parse(&f.attrs)).collect();
+        assert_eq!(fields[0].rename.as_deref(), Some("userName"));
+        assert!(fields[1].default && fields[1].skip_serializing_if);
+        assert!(fields[2].skip);
+        assert!(fields[3].flatten);
+    }
+
+    #[test]
+    fn test_apply_rename_rule() {
+        assert_eq!(apply_rename_rule("camelCase", "created_at", false), "createdAt");
+        assert_eq!(apply_rename_rule("PascalCase", "created_at", false), "CreatedAt");
+        assert_eq!(apply_rename_rule("kebab-case", "created_at", false), "created-at");
+        assert_eq!(apply_rename_rule("SCREAMING_SNAKE_CASE", "created_at", false), "CREATED_AT");
+        assert_eq!(apply_rename_rule("camelCase", "r#type", false), "type");
+
+        assert_eq!(apply_rename_rule("snake_case", "NotFound", true), "not_found");
+        assert_eq!(apply_rename_rule("lowercase", "NotFound", true), "notfound");
+        assert_eq!(apply_rename_rule("camelCase", "NotFound", true), "notFound");
+        assert_eq!(apply_rename_rule("SCREAMING-KEBAB-CASE", "NotFound", true), "NOT-FOUND");
+    }
+}
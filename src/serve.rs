@@ -0,0 +1,178 @@
+//! Documentation preview server: serves the generated spec with Swagger UI
+//! and Redoc, regenerating it (and reloading open pages) when the sources
+//! change.
+//!
+//! | Path | Content |
+//! |------|---------|
+//! | `/swagger` | Swagger UI |
+//! | `/redoc` | Redoc |
+//! | `/openapi.json`, `/openapi.yaml` | The current spec |
+//!
+//! Swagger UI's and Redoc's assets are compiled into the binary, so the
+//! preview works offline. A different `redoc.standalone.js` can be served
+//! with [`ServeOptions::redoc_js`].
+
+use std::{error::Error, fs, net::SocketAddr, path::PathBuf, sync::{Arc, RwLock}, time::Duration};
+use axum::{
+    extract::State,
+    http::header,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::get,
+    Router,
+};
+
+use crate::generator::Generator;
+use crate::openapi::OpenApi;
+use crate::ui::{redoc_page as render_redoc, swagger_page as render_swagger, REDOC_JS, SWAGGER_UI_CSS, SWAGGER_UI_JS};
+use crate::watch::Watcher;
+
+const RELOAD_SCRIPT: &str = include_str!("../assets/reload.html");
+
+// How often the sources are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Settings of the preview server.
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    pub addr: SocketAddr,
+    /// Local `redoc.standalone.js` to serve instead of the bundled one
+    pub redoc_js: Option<PathBuf>,
+}
+
+// The spec being served, replaced on every successful regeneration
+struct Served {
+    title: String,
+    json: String,
+    yaml: String,
+    /// Incremented on every regeneration; pages reload when it changes
+    version: u64,
+    redoc_js: Option<Vec<u8>>,
+}
+
+type SharedState = Arc<RwLock<Served>>;
+
+impl Served {
+    fn update(&mut self, spec: &OpenApi) -> Result<(), Box<dyn Error>> {
+        self.title = spec.info.title.clone();
+        self.json = spec.to_json_pretty()?;
+        self.yaml = spec.to_yaml()?;
+        self.version += 1;
+        Ok(())
+    }
+
+    fn reload_script(&self) -> String {
+        RELOAD_SCRIPT.replace("{{version}}", &self.version.to_string()).trim_end().to_string()
+    }
+
+    fn swagger_page(&self) -> String {
+        render_swagger(&self.title, "/openapi.json", "/assets", &self.reload_script())
+    }
+
+    fn redoc_page(&self) -> String {
+        render_redoc(&self.title, "/openapi.json", "/assets/redoc.standalone.js", &self.reload_script())
+    }
+}
+
+/// Generates the spec and serves it until the process is stopped,
+/// regenerating it whenever one of its source files changes.
+pub fn serve(generator: Generator, options: ServeOptions) -> Result<(), Box<dyn Error>> {
+    let redoc_js = match &options.redoc_js {
+        Some(path) => Some(fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?),
+        None => None,
+    };
+    let mut watcher = Watcher::new(generator);
+    let mut served = Served { title: String::new(), json: String::new(), yaml: String::new(), version: 0, redoc_js };
+    served.update(watcher.generate()?)?;
+    let state: SharedState = Arc::new(RwLock::new(served));
+
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+    let listener = runtime.block_on(tokio::net::TcpListener::bind(options.addr))
+        .map_err(|e| format!("Failed to listen on {}: {}", options.addr, e))?;
+    let app = router(state.clone());
+    runtime.spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            eprintln!("Error: Documentation server stopped: {}", e);
+        }
+    });
+    println!("Serving Swagger UI at http://{}/swagger and Redoc at http://{}/redoc", options.addr, options.addr);
+
+    // 源文件的轮询在当前线程中进行，服务器运行在 tokio 的工作线程上
+    watcher.run(POLL_INTERVAL, |result| {
+        let updated = result.and_then(|update| {
+            state.write().expect("state lock poisoned").update(&update.spec)?;
+            Ok(update)
+        });
+        match updated {
+            Ok(update) => print!("{}", update),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    })
+}
+
+fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/", get(|| async { Redirect::temporary("/swagger") }))
+        .route("/swagger", get(swagger_page))
+        .route("/redoc", get(redoc_page))
+        .route("/openapi.json", get(spec_json))
+        .route("/openapi.yaml", get(spec_yaml))
+        .route("/version", get(version))
+        .route("/assets/swagger-ui-bundle.js", get(|| async { asset("text/javascript", SWAGGER_UI_JS) }))
+        .route("/assets/swagger-ui.css", get(|| async { asset("text/css", SWAGGER_UI_CSS) }))
+        .route("/assets/redoc.standalone.js", get(redoc_js))
+        .with_state(state)
+}
+
+fn asset(content_type: &'static str, body: &'static [u8]) -> Response {
+    ([(header::CONTENT_TYPE, content_type)], body).into_response()
+}
+
+async fn swagger_page(State(state): State<SharedState>) -> Html<String> {
+    Html(state.read().expect("state lock poisoned").swagger_page())
+}
+
+async fn redoc_page(State(state): State<SharedState>) -> Html<String> {
+    Html(state.read().expect("state lock poisoned").redoc_page())
+}
+
+async fn spec_json(State(state): State<SharedState>) -> Response {
+    let json = state.read().expect("state lock poisoned").json.clone();
+    ([(header::CONTENT_TYPE, "application/json")], json).into_response()
+}
+
+async fn spec_yaml(State(state): State<SharedState>) -> Response {
+    let yaml = state.read().expect("state lock poisoned").yaml.clone();
+    ([(header::CONTENT_TYPE, "application/yaml")], yaml).into_response()
+}
+
+async fn version(State(state): State<SharedState>) -> String {
+    state.read().expect("state lock poisoned").version.to_string()
+}
+
+async fn redoc_js(State(state): State<SharedState>) -> Response {
+    match &state.read().expect("state lock poisoned").redoc_js {
+        Some(script) => ([(header::CONTENT_TYPE, "text/javascript")], script.clone()).into_response(),
+        None => asset("text/javascript", REDOC_JS),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_reload_on_new_version() {
+        let served = Served {
+            title: "Shop".to_string(),
+            json: String::new(),
+            yaml: String::new(),
+            version: 3,
+            redoc_js: None,
+        };
+        let page = served.swagger_page();
+        assert!(page.contains("<script src=\"/assets/swagger-ui-bundle.js\"></script>"));
+        assert!(page.contains("var loaded = \"3\";"));
+        assert!(served.redoc_page().contains("<script src=\"/assets/redoc.standalone.js\"></script>"));
+        assert!(!served.redoc_page().contains("https://"));
+    }
+}
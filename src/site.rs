@@ -0,0 +1,385 @@
+//! Static HTML documentation: an index, one page per tag and a schema
+//! reference, cross-linked and self-contained (no scripts, fonts or other
+//! network resources).
+//!
+//! | File | Content |
+//! |------|---------|
+//! | `index.html` | Document info, servers and every operation by tag |
+//! | `tag-{tag}.html` | The operations of one tag |
+//! | `schemas.html` | Every component schema |
+//! | `style.css` | Shared stylesheet |
+
+use std::{collections::BTreeMap, error::Error, fs, path::Path};
+use serde_json::Value;
+
+use crate::openapi::OpenApi;
+use crate::reference::{example, is_json, notes, sections, shape, Content, Note, Operation, Section, Shape, TypeName};
+
+const PAGE: &str = include_str!("../assets/site.html");
+const STYLE: &str = include_str!("../assets/site.css");
+
+/// Renders the site as file names and their contents.
+pub fn render_site(spec: &OpenApi) -> BTreeMap<String, String> {
+    let sections = sections(spec);
+    let nav = nav(spec, &sections);
+    let mut files = BTreeMap::new();
+    files.insert("style.css".to_string(), STYLE.to_string());
+    files.insert("index.html".to_string(), page(&spec.info.title, &nav, &index(spec, &sections)));
+    for section in &sections {
+        let title = format!("{} - {}", section.name, spec.info.title);
+        files.insert(section_file(section), page(&title, &nav, &section_page(spec, section)));
+    }
+    let title = format!("Schemas - {}", spec.info.title);
+    files.insert("schemas.html".to_string(), page(&title, &nav, &schemas_page(spec)));
+    files
+}
+
+/// Writes the site into `dir`, creating it if needed. Existing files other
+/// than the site's own are left alone.
+pub fn write_site(spec: &OpenApi, dir: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    for (name, content) in render_site(spec) {
+        let path = dir.join(name);
+        fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn page(title: &str, nav: &str, content: &str) -> String {
+    // 内容最后替换，避免文档中的 `{{..}}` 被当作占位符
+    PAGE.replace("{{title}}", &html_escape(title))
+        .replace("{{nav}}", nav)
+        .replace("{{content}}", content)
+}
+
+fn section_file(section: &Section) -> String {
+    format!("tag-{}.html", section.slug)
+}
+
+fn schema_link(name: &str) -> String {
+    format!("<a href=\"schemas.html#schema-{}\">{}</a>", html_escape(name), html_escape(name))
+}
+
+fn type_html(schema: &Value) -> String {
+    TypeName::of(schema).render(&schema_link)
+}
+
+fn nav(spec: &OpenApi, sections: &[Section]) -> String {
+    let mut nav = format!("    <a class=\"title\" href=\"index.html\">{}</a>\n", html_escape(&spec.info.title));
+    nav.push_str("    <div class=\"heading\">Operations</div>\n");
+    for section in sections {
+        nav.push_str(&format!("    <a href=\"{}\">{}</a>\n", section_file(section), html_escape(&section.name)));
+    }
+    if !spec.components.schemas.is_empty() {
+        nav.push_str("    <div class=\"heading\">Reference</div>\n");
+        nav.push_str("    <a href=\"schemas.html\">Schemas</a>\n");
+    }
+    nav.trim_end().to_string()
+}
+
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p>{}</p>\n", html_escape(paragraph)))
+        .collect()
+}
+
+fn index(spec: &OpenApi, sections: &[Section]) -> String {
+    let mut html = format!("<h1>{}</h1>\n<p>Version {}</p>\n", html_escape(&spec.info.title), html_escape(&spec.info.version));
+    if let Some(description) = &spec.info.description {
+        html.push_str(&paragraphs(description));
+    }
+    if !spec.servers.is_empty() {
+        html.push_str("<h2>Servers</h2>\n<ul>\n");
+        for server in &spec.servers {
+            html.push_str(&format!("<li><code>{}</code>", html_escape(&server.url)));
+            if let Some(description) = &server.description {
+                html.push_str(&format!(" — {}", html_escape(description)));
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+    }
+
+    for section in sections {
+        html.push_str(&format!("<h2><a href=\"{}\">{}</a></h2>\n", section_file(section), html_escape(&section.name)));
+        if let Some(description) = section.description {
+            html.push_str(&paragraphs(description));
+        }
+        html.push_str("<table>\n<thead><tr><th>Method</th><th>Path</th><th>Summary</th></tr></thead>\n<tbody>\n");
+        for operation in &section.operations {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td><a href=\"{}#{}\"><code>{}</code></a></td><td>{}</td></tr>\n",
+                method_badge(operation.method),
+                section_file(section),
+                operation.anchor(),
+                html_escape(operation.path),
+                html_escape(operation.str_field("summary").unwrap_or_default()),
+            ));
+        }
+        html.push_str("</tbody>\n</table>\n");
+    }
+    html
+}
+
+fn method_badge(method: &str) -> String {
+    format!("<span class=\"method {}\">{}</span>", method, method.to_uppercase())
+}
+
+fn section_page(spec: &OpenApi, section: &Section) -> String {
+    let mut html = format!("<h1>{}</h1>\n", html_escape(&section.name));
+    if let Some(description) = section.description {
+        html.push_str(&paragraphs(description));
+    }
+    for operation in &section.operations {
+        html.push_str(&operation_html(spec, operation));
+    }
+    html
+}
+
+fn operation_html(spec: &OpenApi, operation: &Operation) -> String {
+    let class = if operation.deprecated() { "operation deprecated-operation" } else { "operation" };
+    let mut html = format!(
+        "<section class=\"{}\" id=\"{}\">\n<h2>{} <code>{}</code>{}</h2>\n",
+        class,
+        operation.anchor(),
+        method_badge(operation.method),
+        html_escape(operation.path),
+        if operation.deprecated() { "<span class=\"deprecated\">deprecated</span>" } else { "" },
+    );
+    if let Some(summary) = operation.str_field("summary") {
+        html.push_str(&format!("<p><strong>{}</strong></p>\n", html_escape(summary)));
+    }
+    if let Some(description) = operation.str_field("description") {
+        html.push_str(&paragraphs(description));
+    }
+
+    let parameters = operation.parameters();
+    if !parameters.is_empty() {
+        html.push_str("<h3>Parameters</h3>\n<table>\n<thead><tr><th>Name</th><th>In</th><th>Type</th><th>Required</th><th>Description</th></tr></thead>\n<tbody>\n");
+        for parameter in parameters {
+            html.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(parameter.name),
+                html_escape(parameter.location),
+                type_html(parameter.schema),
+                if parameter.required { "yes" } else { "no" },
+                notes_html(parameter.schema, parameter.description),
+            ));
+        }
+        html.push_str("</tbody>\n</table>\n");
+    }
+
+    let body = operation.request_body();
+    if !body.is_empty() {
+        html.push_str("<h3>Request body</h3>\n");
+        html.push_str(&contents_html(spec, &body));
+    }
+
+    let responses = operation.responses();
+    if !responses.is_empty() {
+        html.push_str("<h3>Responses</h3>\n<table>\n<thead><tr><th>Status</th><th>Description</th><th>Body</th></tr></thead>\n<tbody>\n");
+        for response in &responses {
+            let body: Vec<String> = response.content.iter()
+                .map(|content| format!("{} <code>{}</code>", type_html(content.schema), html_escape(content.media_type)))
+                .collect();
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(response.status),
+                html_escape(response.description),
+                body.join("<br>"),
+            ));
+        }
+        html.push_str("</tbody>\n</table>\n");
+        for (statuses, schema) in operation.response_examples() {
+            html.push_str(&format!("<h4>Example {} response</h4>\n", html_escape(&statuses.join(", "))));
+            html.push_str(&example_html(spec, schema));
+        }
+    }
+    html.push_str("</section>\n");
+    html
+}
+
+fn contents_html(spec: &OpenApi, contents: &[Content]) -> String {
+    let mut html = String::new();
+    for content in contents {
+        html.push_str(&format!("<p><code>{}</code>: {}</p>\n", html_escape(content.media_type), type_html(content.schema)));
+        if is_json(content.media_type) {
+            html.push_str(&example_html(spec, content.schema));
+        }
+    }
+    html
+}
+
+fn example_html(spec: &OpenApi, schema: &Value) -> String {
+    let example = example(schema, &spec.components.schemas);
+    let json = serde_json::to_string_pretty(&example).expect("examples always serialize");
+    format!("<pre><code>{}</code></pre>\n", html_escape(&json))
+}
+
+// Description column: the schema's description, allowed values and default
+fn notes_html(schema: &Value, description: Option<&str>) -> String {
+    let notes: Vec<String> = notes(schema, description).into_iter()
+        .map(|note| match note {
+            Note::Text(text) => html_escape(text),
+            Note::Values(values) => format!("One of {}", values_html(&values)),
+            Note::Default(value) => format!("Default <code>{}</code>", html_escape(&value)),
+            Note::Deprecated => "Deprecated".to_string(),
+        })
+        .collect();
+    notes.join("<br>")
+}
+
+fn values_html(values: &[String]) -> String {
+    values.iter().map(|value| format!("<code>{}</code>", html_escape(value))).collect::<Vec<_>>().join(", ")
+}
+
+fn schemas_page(spec: &OpenApi) -> String {
+    let mut html = "<h1>Schemas</h1>\n".to_string();
+    for (name, schema) in &spec.components.schemas {
+        html.push_str(&format!("<section class=\"schema\" id=\"schema-{}\">\n<h2>{}</h2>\n", html_escape(name), html_escape(name)));
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            html.push_str(&paragraphs(description));
+        }
+        match shape(schema) {
+            Shape::Object { properties, includes } => {
+                for include in includes {
+                    html.push_str(&format!("<p>Includes all fields of {}.</p>\n", schema_link(include)));
+                }
+                if !properties.is_empty() {
+                    html.push_str("<table>\n<thead><tr><th>Field</th><th>Type</th><th>Required</th><th>Description</th></tr></thead>\n<tbody>\n");
+                    for property in properties {
+                        html.push_str(&format!(
+                            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                            html_escape(property.name),
+                            type_html(property.schema),
+                            if property.required { "yes" } else { "no" },
+                            notes_html(property.schema, None),
+                        ));
+                    }
+                    html.push_str("</tbody>\n</table>\n");
+                }
+            }
+            Shape::Enum(values) => {
+                html.push_str("<p>One of:</p>\n<ul>\n");
+                for value in values {
+                    html.push_str(&format!("<li><code>{}</code></li>\n", html_escape(&value.to_string())));
+                }
+                html.push_str("</ul>\n");
+            }
+            Shape::Variants { variants, discriminator } => {
+                match discriminator {
+                    Some(property) => html.push_str(&format!(
+                        "<p>One of the following, told apart by <code>{}</code>:</p>\n", html_escape(property))),
+                    None => html.push_str("<p>One of the following:</p>\n"),
+                }
+                html.push_str("<ul>\n");
+                for variant in variants {
+                    html.push_str(&format!("<li>{}</li>\n", variant_html(variant)));
+                }
+                html.push_str("</ul>\n");
+            }
+            Shape::Other => html.push_str(&format!("<p>Type: {}</p>\n", type_html(schema))),
+        }
+        html.push_str("<h3>Example</h3>\n");
+        html.push_str(&example_html(spec, schema));
+        html.push_str("</section>\n");
+    }
+    html
+}
+
+// An inline variant is listed with its fields, a referenced one by name
+fn variant_html(variant: &Value) -> String {
+    match shape(variant) {
+        Shape::Object { properties, includes } => {
+            let fields: Vec<String> = includes.into_iter().map(schema_link)
+                .chain(properties.iter().map(|property| format!("<code>{}</code>: {}", html_escape(property.name), type_html(property.schema))))
+                .collect();
+            format!("object with {}", fields.join(", "))
+        }
+        Shape::Enum(values) => values_html(&values.iter().map(Value::to_string).collect::<Vec<_>>()),
+        _ => type_html(variant),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_site() {
+        let spec: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Shop <API>", "version": "1.2.0"},
+            "paths": {
+                "/users/{id}": {
+                    "get": {
+                        "tags": ["modules::user"],
+                        "operationId": "get_user",
+                        "summary": "Get a user",
+                        "parameters": [{"name": "id", "in": "path", "required": true, "schema": {"type": "integer", "format": "int64"}}],
+                        "responses": {"200": {"description": "OK", "content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}}}}
+                    }
+                },
+                "/health": {"get": {"summary": "Health", "responses": {"200": {"description": "OK"}}}}
+            },
+            "components": {"schemas": {
+                "User": {"type": "object", "required": ["id"], "properties": {
+                    "id": {"type": "integer", "format": "int64"},
+                    "role": {"$ref": "#/components/schemas/Role"}
+                }},
+                "Role": {"type": "string", "enum": ["admin", "member"]}
+            }}
+        })).unwrap();
+
+        let files = render_site(&spec);
+        let names: Vec<&str> = files.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["index.html", "schemas.html", "style.css", "tag-default.html", "tag-modules-user.html"]);
+
+        let index = &files["index.html"];
+        assert!(index.contains("<title>Shop &lt;API&gt;</title>"));
+        assert!(index.contains("<a href=\"tag-modules-user.html#get-user\"><code>/users/{id}</code></a></td><td>Get a user</td>"));
+
+        let users = &files["tag-modules-user.html"];
+        assert!(users.contains("<section class=\"operation\" id=\"get-user\">"));
+        assert!(users.contains("<td><code>id</code></td><td>path</td><td>integer (int64)</td><td>yes</td>"));
+        assert!(users.contains("<a href=\"schemas.html#schema-User\">User</a> <code>application/json</code>"));
+        assert!(users.contains("&quot;role&quot;: &quot;admin&quot;"));
+
+        let schemas = &files["schemas.html"];
+        assert!(schemas.contains("<td><code>role</code></td><td><a href=\"schemas.html#schema-Role\">Role</a></td><td>no</td>"));
+        assert!(schemas.contains("<li><code>&quot;member&quot;</code></li>"));
+        assert!(!files.values().any(|file| file.contains("http://") || file.contains("https://")));
+    }
+
+    #[test]
+    fn test_render_site_unicode_and_clashing_tags() {
+        let spec: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Shop", "version": "1"},
+            "paths": {
+                "/users": {"get": {"tags": ["用户"], "operationId": "list_users", "responses": {}}},
+                "/orders": {"get": {"tags": ["订单"], "operationId": "list_orders", "responses": {}}},
+                "/admin": {"get": {"tags": ["Admin"], "operationId": "admin", "responses": {}}},
+                "/admin2": {"get": {"tags": ["admin"], "operationId": "admin2", "responses": {}}}
+            },
+            "components": {"schemas": {}}
+        })).unwrap();
+
+        let files = render_site(&spec);
+        let names: Vec<&str> = files.keys().map(String::as_str).collect();
+        assert_eq!(names, vec![
+            "index.html", "schemas.html", "style.css", "tag-admin-2.html", "tag-admin.html", "tag-用户.html", "tag-订单.html",
+        ]);
+        assert!(files["tag-用户.html"].contains("id=\"list-users\""));
+        assert!(files["tag-订单.html"].contains("id=\"list-orders\""));
+        assert!(files["tag-admin-2.html"].contains("id=\"admin2\""));
+        assert!(files["index.html"].contains("<a href=\"tag-订单.html\">订单</a>"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Source files read during generation. Parsed syntax trees are kept and
+//! reused for as long as a file's content is unchanged, so regenerating after
+//! an edit only re-parses the edited files.
+
+use std::{cell::RefCell, collections::{BTreeSet, HashMap}, error::Error, fmt, fs, io, path::{Path, PathBuf}, rc::Rc};
+
+/// Cache of source files and their syntax trees. Cloning is cheap and
+/// clones share the cache.
+#[derive(Clone, Default)]
+pub struct SourceCache {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    files: HashMap<PathBuf, CachedFile>,
+    /// Files read since the last `clear_reads`
+    reads: BTreeSet<PathBuf>,
+    parses: usize,
+}
+
+struct CachedFile {
+    content: Rc<str>,
+    /// Parsed on first use
+    ast: Option<Result<Rc<syn::File>, syn::Error>>,
+}
+
+/// Failure to read or parse a source file.
+#[derive(Debug)]
+pub enum SourceError {
+    Io(io::Error),
+    Parse(syn::Error),
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Io(e) => e.fmt(f),
+            SourceError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for SourceError {}
+
+impl SourceCache {
+    pub fn new() -> Self {
+        SourceCache::default()
+    }
+
+    /// Reads `path`, keeping the cached syntax tree if the content is unchanged.
+    pub fn read(&self, path: &Path) -> io::Result<Rc<str>> {
+        let content = fs::read_to_string(path)?;
+        let mut inner = self.inner.borrow_mut();
+        inner.reads.insert(path.to_path_buf());
+        match inner.files.get(path) {
+            Some(cached) if *cached.content == *content => Ok(cached.content.clone()),
+            _ => {
+                let content: Rc<str> = content.into();
+                inner.files.insert(path.to_path_buf(), CachedFile { content: content.clone(), ast: None });
+                Ok(content)
+            }
+        }
+    }
+
+    /// Reads and parses `path`, re-parsing only if its content changed since
+    /// the last call.
+    pub fn parse(&self, path: &Path) -> Result<Rc<syn::File>, SourceError> {
+        self.read(path).map_err(SourceError::Io)?;
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+        let cached = inner.files.get_mut(path).expect("read caches the file");
+        if cached.ast.is_none() {
+            inner.parses += 1;
+            cached.ast = Some(syn::parse_file(&cached.content).map(Rc::new));
+        }
+        match cached.ast.as_ref().expect("parsed above") {
+            Ok(ast) => Ok(ast.clone()),
+            Err(e) => Err(SourceError::Parse(e.clone())),
+        }
+    }
+
+    /// Files read since the cache was created or [`SourceCache::clear_reads`]
+    /// was last called.
+    pub fn reads(&self) -> BTreeSet<PathBuf> {
+        self.inner.borrow().reads.clone()
+    }
+
+    pub fn clear_reads(&self) {
+        self.inner.borrow_mut().reads.clear();
+    }
+
+    /// Number of times a file has been parsed, across all generations.
+    pub fn parse_count(&self) -> usize {
+        self.inner.borrow().parses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reparses_only_changed_files() {
+        let dir = std::env::temp_dir().join("axum_doc_source_cache");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let a = dir.join("a.rs");
+        let b = dir.join("b.rs");
+        fs::write(&a, "struct A;").unwrap();
+        fs::write(&b, "struct B;").unwrap();
+
+        let sources = SourceCache::new();
+        sources.parse(&a).unwrap();
+        sources.parse(&b).unwrap();
+        assert_eq!(sources.parse_count(), 2);
+
+        fs::write(&b, "struct B { x: u8 }").unwrap();
+        sources.clear_reads();
+        sources.parse(&a).unwrap();
+        let ast = sources.parse(&b).unwrap();
+        assert_eq!(sources.parse_count(), 3);
+        assert!(matches!(&ast.items[0], syn::Item::Struct(s) if s.fields.len() == 1));
+        assert_eq!(sources.reads(), BTreeSet::from([a.clone(), b.clone()]));
+
+        fs::write(&a, "struct A {").unwrap();
+        assert!(matches!(sources.parse(&a), Err(SourceError::Parse(_))));
+        assert!(matches!(sources.parse(&dir.join("missing.rs")), Err(SourceError::Io(_))));
+    }
+}
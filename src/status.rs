@@ -0,0 +1,134 @@
+//! HTTP status codes as written in handler code (`StatusCode::NOT_FOUND`).
+
+use syn::{Expr, Path};
+
+// Associated constants of `http::StatusCode`
+const STATUS_CODES: &[(&str, u16)] = &[
+    ("CONTINUE", 100),
+    ("SWITCHING_PROTOCOLS", 101),
+    ("OK", 200),
+    ("CREATED", 201),
+    ("ACCEPTED", 202),
+    ("NON_AUTHORITATIVE_INFORMATION", 203),
+    ("NO_CONTENT", 204),
+    ("RESET_CONTENT", 205),
+    ("PARTIAL_CONTENT", 206),
+    ("MULTIPLE_CHOICES", 300),
+    ("MOVED_PERMANENTLY", 301),
+    ("FOUND", 302),
+    ("SEE_OTHER", 303),
+    ("NOT_MODIFIED", 304),
+    ("TEMPORARY_REDIRECT", 307),
+    ("PERMANENT_REDIRECT", 308),
+    ("BAD_REQUEST", 400),
+    ("UNAUTHORIZED", 401),
+    ("PAYMENT_REQUIRED", 402),
+    ("FORBIDDEN", 403),
+    ("NOT_FOUND", 404),
+    ("METHOD_NOT_ALLOWED", 405),
+    ("NOT_ACCEPTABLE", 406),
+    ("REQUEST_TIMEOUT", 408),
+    ("CONFLICT", 409),
+    ("GONE", 410),
+    ("LENGTH_REQUIRED", 411),
+    ("PRECONDITION_FAILED", 412),
+    ("PAYLOAD_TOO_LARGE", 413),
+    ("URI_TOO_LONG", 414),
+    ("UNSUPPORTED_MEDIA_TYPE", 415),
+    ("RANGE_NOT_SATISFIABLE", 416),
+    ("EXPECTATION_FAILED", 417),
+    ("IM_A_TEAPOT", 418),
+    ("UNPROCESSABLE_ENTITY", 422),
+    ("LOCKED", 423),
+    ("FAILED_DEPENDENCY", 424),
+    ("PRECONDITION_REQUIRED", 428),
+    ("TOO_MANY_REQUESTS", 429),
+    ("REQUEST_HEADER_FIELDS_TOO_LARGE", 431),
+    ("UNAVAILABLE_FOR_LEGAL_REASONS", 451),
+    ("INTERNAL_SERVER_ERROR", 500),
+    ("NOT_IMPLEMENTED", 501),
+    ("BAD_GATEWAY", 502),
+    ("SERVICE_UNAVAILABLE", 503),
+    ("GATEWAY_TIMEOUT", 504),
+    ("HTTP_VERSION_NOT_SUPPORTED", 505),
+];
+
+/// Numeric code of a `StatusCode::NAME` path (any prefix, e.g.
+/// `http::StatusCode::NOT_FOUND`).
+pub(crate) fn status_from_path(path: &Path) -> Option<u16> {
+    let mut segments = path.segments.iter().rev();
+    let name = segments.next()?;
+    if segments.next()?.ident != "StatusCode" {
+        return None;
+    }
+    let name = name.ident.to_string();
+    STATUS_CODES.iter().find(|(constant, _)| *constant == name).map(|(_, code)| *code)
+}
+
+/// Numeric code of an expression that is a `StatusCode` constant, or
+/// `StatusCode::from_u16(<literal>)` (optionally followed by `.unwrap()`).
+pub(crate) fn status_from_expr(expr: &Expr) -> Option<u16> {
+    match expr {
+        Expr::Path(expr_path) => status_from_path(&expr_path.path),
+        Expr::Paren(paren) => status_from_expr(&paren.expr),
+        Expr::MethodCall(call) if call.method == "unwrap" || call.method == "expect" => {
+            status_from_expr(&call.receiver)
+        }
+        Expr::Call(call) => match (&*call.func, call.args.first()) {
+            (Expr::Path(func), Some(Expr::Lit(lit))) if is_from_u16(&func.path) => match &lit.lit {
+                syn::Lit::Int(int) => int.base10_parse().ok(),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_from_u16(path: &Path) -> bool {
+    let mut segments = path.segments.iter().rev();
+    matches!(
+        (segments.next(), segments.next()),
+        (Some(method), Some(ty)) if method.ident == "from_u16" && ty.ident == "StatusCode"
+    )
+}
+
+/// Description used for a response with `code`: the constant's name in
+/// title case (`404` gives "Not Found", `200` gives "OK").
+pub(crate) fn reason_phrase(code: u16) -> String {
+    match STATUS_CODES.iter().find(|(_, known)| *known == code) {
+        Some(("OK", _)) => "OK".to_string(),
+        Some((name, _)) => name.split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_string() + &chars.as_str().to_ascii_lowercase(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => format!("Status {}", code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_from_expr() {
+        assert_eq!(status_from_expr(&syn::parse_quote!(StatusCode::CREATED)), Some(201));
+        assert_eq!(status_from_expr(&syn::parse_quote!(axum::http::StatusCode::NOT_FOUND)), Some(404));
+        assert_eq!(status_from_expr(&syn::parse_quote!(StatusCode::from_u16(418).unwrap())), Some(418));
+        assert_eq!(status_from_expr(&syn::parse_quote!(Status::OK)), None);
+        assert_eq!(status_from_expr(&syn::parse_quote!(OK)), None);
+    }
+
+    #[test]
+    fn test_reason_phrase() {
+        assert_eq!(reason_phrase(200), "OK");
+        assert_eq!(reason_phrase(404), "Not Found");
+        assert_eq!(reason_phrase(599), "Status 599");
+    }
+}
@@ -0,0 +1,55 @@
+//! Bundled documentation UI: Swagger UI and Redoc assets and the HTML pages
+//! that load a spec into them.
+
+use crate::site::html_escape;
+
+pub(crate) const SWAGGER_UI_JS: &[u8] = include_bytes!("../assets/swagger-ui/swagger-ui-bundle.js");
+pub(crate) const SWAGGER_UI_CSS: &[u8] = include_bytes!("../assets/swagger-ui/swagger-ui.css");
+#[cfg(feature = "serve")]
+pub(crate) const REDOC_JS: &[u8] = include_bytes!("../assets/redoc/redoc.standalone.js");
+const SWAGGER_PAGE: &str = include_str!("../assets/swagger.html");
+#[cfg(feature = "serve")]
+const REDOC_PAGE: &str = include_str!("../assets/redoc.html");
+
+/// Swagger UI page for the spec at `spec_url`, loading the bundled assets
+/// from `assets_url` (`swagger-ui-bundle.js` and `swagger-ui.css`).
+/// `extra` is inserted at the end of the body.
+pub(crate) fn swagger_page(title: &str, spec_url: &str, assets_url: &str, extra: &str) -> String {
+    SWAGGER_PAGE
+        .replace("{{title}}", &html_escape(title))
+        .replace("{{assets}}", assets_url)
+        .replace("{{spec_url}}", spec_url)
+        .replace("{{extra}}", extra)
+}
+
+/// Redoc page for the spec at `spec_url`, loading Redoc from `redoc_js`.
+#[cfg(feature = "serve")]
+pub(crate) fn redoc_page(title: &str, spec_url: &str, redoc_js: &str, extra: &str) -> String {
+    REDOC_PAGE
+        .replace("{{title}}", &html_escape(title))
+        .replace("{{spec_url}}", spec_url)
+        .replace("{{redoc_js}}", redoc_js)
+        .replace("{{extra}}", extra)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_pages() {
+        let page = swagger_page("Shop <API>", "/api/openapi.json", "/docs/assets", "");
+        assert!(page.contains("<title>Shop &lt;API&gt; - Swagger UI</title>"));
+        assert!(page.contains("<script src=\"/docs/assets/swagger-ui-bundle.js\"></script>"));
+        assert!(page.contains("url: \"/api/openapi.json\""));
+        assert!(!page.contains("{{"));
+    }
+
+    #[cfg(feature = "serve")]
+    #[test]
+    fn test_render_redoc_page() {
+        let page = redoc_page("Shop", "/openapi.json", "/redoc.js", "<script></script>");
+        assert!(page.contains("<redoc spec-url=\"/openapi.json\"></redoc>"));
+        assert!(page.contains("<script src=\"/redoc.js\"></script>\n  <script></script>"));
+    }
+}
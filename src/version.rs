@@ -0,0 +1,245 @@
+//! Target OpenAPI version and output format, and the rewrite of generated
+//! (3.0-style) schemas into OpenAPI 3.1 / JSON Schema 2020-12.
+
+use std::{fmt, str::FromStr};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::openapi::OpenApi;
+
+/// JSON Schema dialect declared by OpenAPI 3.1 documents.
+pub const OAS_3_1_DIALECT: &str = "https://spec.openapis.org/oas/3.1/dialect/base";
+
+/// OpenAPI version of the generated document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum SpecVersion {
+    /// OpenAPI 3.0.0: `nullable`, `example`
+    #[default]
+    V3_0,
+    /// OpenAPI 3.1.0: JSON Schema 2020-12 (`type: [.., "null"]`, `examples`)
+    V3_1,
+}
+
+/// Serialization of the generated document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Yaml,
+    /// Static documentation site, written as a directory
+    Html,
+    /// Markdown API reference
+    Markdown,
+}
+
+impl FromStr for SpecVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "3.0" | "3.0.0" => Ok(SpecVersion::V3_0),
+            "3.1" | "3.1.0" => Ok(SpecVersion::V3_1),
+            other => Err(format!("unsupported OpenAPI version '{}', expected 3.0 or 3.1", other)),
+        }
+    }
+}
+
+impl TryFrom<String> for SpecVersion {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SpecVersion::V3_0 => "3.0.0",
+            SpecVersion::V3_1 => "3.1.0",
+        })
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            "html" => Ok(OutputFormat::Html),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            other => Err(format!("unsupported output format '{}', expected json, yaml, html or markdown", other)),
+        }
+    }
+}
+
+impl TryFrom<String> for OutputFormat {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl OutputFormat {
+    /// Format implied by a file extension: `.yaml`/`.yml` are YAML,
+    /// `.md`/`.markdown` Markdown, anything else JSON.
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("yaml" | "yml") => OutputFormat::Yaml,
+            Some("md" | "markdown") => OutputFormat::Markdown,
+            _ => OutputFormat::Json,
+        }
+    }
+}
+
+// Rewrites a 3.0 document into 3.1. Every schema position the generator
+// produces is visited: components, parameters, request bodies and responses.
+pub(crate) fn upgrade_to_3_1(spec: &mut OpenApi) {
+    spec.openapi = SpecVersion::V3_1.to_string();
+    spec.json_schema_dialect = Some(OAS_3_1_DIALECT.to_string());
+
+    for schema in spec.components.schemas.values_mut() {
+        upgrade_schema(schema);
+    }
+    for path_item in spec.paths.values_mut() {
+        let Some(operations) = path_item.as_object_mut() else { continue };
+        for operation in operations.values_mut() {
+            if let Some(parameters) = operation.get_mut("parameters").and_then(Value::as_array_mut) {
+                for parameter in parameters {
+                    if let Some(schema) = parameter.get_mut("schema") {
+                        upgrade_schema(schema);
+                    }
+                }
+            }
+            if let Some(body) = operation.get_mut("requestBody") {
+                upgrade_content(body);
+            }
+            if let Some(responses) = operation.get_mut("responses").and_then(Value::as_object_mut) {
+                for response in responses.values_mut() {
+                    upgrade_content(response);
+                }
+            }
+        }
+    }
+}
+
+fn upgrade_content(holder: &mut Value) {
+    if let Some(content) = holder.get_mut("content").and_then(Value::as_object_mut) {
+        for media_type in content.values_mut() {
+            if let Some(schema) = media_type.get_mut("schema") {
+                upgrade_schema(schema);
+            }
+        }
+    }
+}
+
+/// Rewrites a single 3.0 schema (and its subschemas) in place:
+/// `nullable: true` becomes a `"null"` type, `example` becomes `examples`.
+pub(crate) fn upgrade_schema(schema: &mut Value) {
+    let Some(object) = schema.as_object_mut() else { return };
+
+    for key in ["properties", "patternProperties"] {
+        if let Some(properties) = object.get_mut(key).and_then(Value::as_object_mut) {
+            properties.values_mut().for_each(upgrade_schema);
+        }
+    }
+    for key in ["items", "additionalProperties", "not"] {
+        if let Some(subschema) = object.get_mut(key) {
+            upgrade_schema(subschema);
+        }
+    }
+    for key in ["allOf", "oneOf", "anyOf"] {
+        if let Some(subschemas) = object.get_mut(key).and_then(Value::as_array_mut) {
+            subschemas.iter_mut().for_each(upgrade_schema);
+        }
+    }
+
+    if let Some(example) = object.remove("example") {
+        object.insert("examples".to_string(), json!([example]));
+    }
+
+    if object.remove("nullable") != Some(Value::Bool(true)) {
+        return;
+    }
+    if let Some(values) = object.get_mut("enum").and_then(Value::as_array_mut) {
+        if !values.contains(&Value::Null) {
+            values.push(Value::Null);
+        }
+    }
+    match object.remove("type") {
+        Some(Value::String(ty)) => {
+            object.insert("type".to_string(), json!([ty, "null"]));
+        }
+        Some(Value::Array(mut types)) => {
+            if !types.contains(&json!("null")) {
+                types.push(json!("null"));
+            }
+            object.insert("type".to_string(), Value::Array(types));
+        }
+        Some(other) => {
+            object.insert("type".to_string(), other);
+        }
+        None if object.get("enum") == Some(&json!([null])) => {
+            // 无类型的 null 枚举（untagged 的单元变体）
+            object.remove("enum");
+            object.insert("type".to_string(), json!("null"));
+        }
+        None => {
+            // $ref / allOf 等没有 type 的 schema：用 anyOf 加上 null
+            let inner = std::mem::take(object);
+            object.insert("anyOf".to_string(), json!([Value::Object(inner), {"type": "null"}]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_schema_nullable() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "nullable": true},
+                "owner": {"$ref": "#/components/schemas/User", "nullable": true},
+                "status": {"type": "string", "enum": ["a", "b"], "nullable": true},
+                "tags": {"type": "array", "items": {"type": "string", "nullable": true}},
+                "id": {"type": "string", "format": "uuid", "example": "550e8400-e29b-41d4-a716-446655440000"}
+            }
+        });
+        upgrade_schema(&mut schema);
+
+        let properties = &schema["properties"];
+        assert_eq!(properties["name"], json!({"type": ["string", "null"]}));
+        assert_eq!(properties["owner"], json!({"anyOf": [{"$ref": "#/components/schemas/User"}, {"type": "null"}]}));
+        assert_eq!(properties["status"], json!({"type": ["string", "null"], "enum": ["a", "b", null]}));
+        assert_eq!(properties["tags"]["items"]["type"], json!(["string", "null"]));
+        assert_eq!(properties["id"]["examples"], json!(["550e8400-e29b-41d4-a716-446655440000"]));
+        assert!(properties["id"].get("example").is_none());
+    }
+
+    #[test]
+    fn test_upgrade_untagged_null_variant() {
+        let mut schema = json!({"oneOf": [{"type": "string"}, {"nullable": true, "enum": [null]}]});
+        upgrade_schema(&mut schema);
+        assert_eq!(schema, json!({"oneOf": [{"type": "string"}, {"type": "null"}]}));
+    }
+
+    #[test]
+    fn test_parse_version_and_format() {
+        assert_eq!("3.1".parse::<SpecVersion>(), Ok(SpecVersion::V3_1));
+        assert_eq!("3.0.0".parse::<SpecVersion>(), Ok(SpecVersion::V3_0));
+        assert!("2.0".parse::<SpecVersion>().is_err());
+        assert_eq!("YAML".parse::<OutputFormat>(), Ok(OutputFormat::Yaml));
+        assert_eq!("html".parse::<OutputFormat>(), Ok(OutputFormat::Html));
+        assert_eq!(OutputFormat::from_path("openapi.yml".as_ref()), OutputFormat::Yaml);
+        assert_eq!(OutputFormat::from_path("openapi.json".as_ref()), OutputFormat::Json);
+        assert_eq!(OutputFormat::from_path("API.md".as_ref()), OutputFormat::Markdown);
+    }
+}
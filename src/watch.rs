@@ -0,0 +1,202 @@
+//! Watch mode: regenerates the document whenever one of the files it was
+//! built from changes.
+//!
+//! The watched files are exactly the ones the last generation read: the
+//! entry file, the module router and handler files reached from it, every
+//! module of the crate and the explicit model files. Files are polled for
+//! changes, and only the changed ones are parsed again.
+
+use std::{collections::BTreeMap, error::Error, fmt, fs, path::{Path, PathBuf}, thread, time::{Duration, SystemTime}};
+
+use crate::diff::{diff_specs, ItemChange, ItemDiff, SpecDiff};
+use crate::generator::Generator;
+use crate::openapi::OpenApi;
+use crate::sources::SourceCache;
+
+// Modification time and length of a file, `None` once it is gone
+type Stamp = Option<(SystemTime, u64)>;
+
+/// Regenerates a document when its sources change.
+pub struct Watcher {
+    generator: Generator,
+    sources: SourceCache,
+    stamps: BTreeMap<PathBuf, Stamp>,
+    spec: Option<OpenApi>,
+}
+
+/// The result of a regeneration.
+pub struct Update {
+    pub spec: OpenApi,
+    /// Watched files that changed since the previous generation
+    pub changed_files: Vec<PathBuf>,
+    /// Number of files that had to be parsed again
+    pub reparsed: usize,
+    /// Changes relative to the previous document
+    pub diff: SpecDiff,
+}
+
+impl Watcher {
+    pub fn new(generator: Generator) -> Self {
+        Watcher {
+            generator,
+            sources: SourceCache::new(),
+            stamps: BTreeMap::new(),
+            spec: None,
+        }
+    }
+
+    /// Generates the document and starts watching the files it was built from.
+    pub fn generate(&mut self) -> Result<&OpenApi, Box<dyn Error>> {
+        let spec = self.regenerate()?;
+        Ok(self.spec.insert(spec))
+    }
+
+    /// Files read by the last generation.
+    pub fn watched_files(&self) -> impl Iterator<Item = &Path> {
+        self.stamps.keys().map(PathBuf::as_path)
+    }
+
+    /// Watched files modified or removed since the last generation.
+    pub fn changed_files(&self) -> Vec<PathBuf> {
+        self.stamps.iter()
+            .filter(|(path, stamp)| stamp_of(path) != **stamp)
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// Regenerates the document if a watched file changed. Returns `None`
+    /// when nothing changed; a failed generation keeps the previous document
+    /// and is retried on the next change.
+    pub fn poll(&mut self) -> Option<Result<Update, Box<dyn Error>>> {
+        let changed_files = self.changed_files();
+        if changed_files.is_empty() {
+            return None;
+        }
+
+        let parses = self.sources.parse_count();
+        let spec = match self.regenerate() {
+            Ok(spec) => spec,
+            Err(e) => return Some(Err(e)),
+        };
+        let diff = match &self.spec {
+            Some(previous) => diff_specs(&previous.to_value(), &spec.to_value()),
+            None => SpecDiff::default(),
+        };
+        self.spec = Some(spec.clone());
+        Some(Ok(Update {
+            spec,
+            changed_files,
+            reparsed: self.sources.parse_count() - parses,
+            diff,
+        }))
+    }
+
+    /// Polls for changes every `interval`, passing each regeneration to
+    /// `on_update`. Never returns.
+    pub fn run(mut self, interval: Duration, mut on_update: impl FnMut(Result<Update, Box<dyn Error>>)) -> ! {
+        loop {
+            thread::sleep(interval);
+            if let Some(result) = self.poll() {
+                on_update(result);
+            }
+        }
+    }
+
+    fn regenerate(&mut self) -> Result<OpenApi, Box<dyn Error>> {
+        self.sources.clear_reads();
+        let result = self.generator.generate_with(&self.sources);
+        let reads = self.sources.reads();
+        if result.is_ok() {
+            self.stamps.clear();
+        }
+        // 生成失败时保留之前的文件列表，以便修复后重新生成
+        for path in reads {
+            let stamp = stamp_of(&path);
+            self.stamps.insert(path, stamp);
+        }
+        result
+    }
+}
+
+fn stamp_of(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+impl fmt::Display for Update {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let files: Vec<String> = self.changed_files.iter().map(|path| path.display().to_string()).collect();
+        writeln!(
+            f,
+            "Regenerated after changes to {} ({} file{} re-parsed)",
+            files.join(", "),
+            self.reparsed,
+            if self.reparsed == 1 { "" } else { "s" },
+        )?;
+        if self.diff.operations.is_empty() && self.diff.schemas.is_empty() {
+            return writeln!(f, "  No changes to routes or schemas");
+        }
+        for (title, items) in [("Routes", &self.diff.operations), ("Schemas", &self.diff.schemas)] {
+            if !items.is_empty() {
+                writeln!(f, "  {}: {}", title, items.iter().map(summarize).collect::<Vec<_>>().join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn summarize(item: &ItemDiff) -> String {
+    let sign = match item.change {
+        ItemChange::Added => '+',
+        ItemChange::Removed => '-',
+        ItemChange::Changed(_) => '~',
+    };
+    format!("{} {}", sign, item.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regenerates_changed_files_only() {
+        let dir = std::env::temp_dir().join(format!("axum_doc_watch_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/main.rs"), r#"
+            mod types;
+            use types::User;
+            async fn get_user() -> Json<User> { todo!() }
+            fn app() -> Router { Router::new().route("/user", get(get_user)) }
+        "#).unwrap();
+        fs::write(dir.join("src/types.rs"), "pub struct User { pub id: u64 }").unwrap();
+
+        let mut watcher = Watcher::new(Generator::new(&dir));
+        assert!(watcher.generate().unwrap().components.schemas.contains_key("User"));
+        assert_eq!(watcher.watched_files().count(), 2);
+        assert!(watcher.poll().is_none());
+
+        fs::write(dir.join("src/types.rs"), "pub struct User { pub id: u64, pub name: String }").unwrap();
+        let update = watcher.poll().unwrap().unwrap();
+        assert_eq!(update.changed_files, vec![dir.join("src/types.rs")]);
+        assert_eq!(update.reparsed, 1);
+        assert!(update.diff.operations.is_empty());
+        assert_eq!(update.diff.schemas.len(), 1);
+        assert!(update.to_string().ends_with("(1 file re-parsed)\n  Schemas: ~ User\n"));
+        assert!(watcher.poll().is_none());
+
+        // A syntax error keeps the previous document until the file is fixed
+        fs::write(dir.join("src/main.rs"), "fn app( {").unwrap();
+        assert!(watcher.poll().unwrap().is_err());
+        assert!(watcher.poll().is_none());
+        fs::write(dir.join("src/main.rs"), r#"
+            mod types;
+            use types::User;
+            async fn get_user() -> Json<User> { todo!() }
+            async fn list_users() -> Json<Vec<User>> { todo!() }
+            fn app() -> Router { Router::new().route("/user", get(get_user)).route("/users", get(list_users)) }
+        "#).unwrap();
+        let update = watcher.poll().unwrap().unwrap();
+        assert!(update.to_string().contains("  Routes: + GET /users\n"));
+    }
+}
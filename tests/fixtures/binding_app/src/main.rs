@@ -0,0 +1,33 @@
+use axum::{routing::get, Json, Router};
+use serde::Serialize;
+
+mod users;
+
+#[derive(Serialize)]
+pub struct Health {
+    pub ok: bool,
+}
+
+/// Service health
+async fn health() -> Json<Health> {
+    Json(Health { ok: true })
+}
+
+/// Service version
+async fn version() -> String {
+    String::from("1.0")
+}
+
+fn meta_routes() -> Router {
+    let meta = Router::new().route("/version", get(version));
+    meta
+}
+
+fn main() {
+    let users = users::router();
+    let api = Router::new().nest("/users", users);
+
+    let mut app = Router::new().route("/health", get(health));
+    app = app.nest("/api", api);
+    let app = app.merge(meta_routes());
+}
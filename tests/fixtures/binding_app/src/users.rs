@@ -0,0 +1,23 @@
+use axum::{extract::Path, routing::get, Json, Router};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct User {
+    pub id: u64,
+    pub name: String,
+}
+
+/// List a user's friends
+async fn list_friends(Path(id): Path<u64>) -> Json<Vec<User>> {
+    Json(Vec::new())
+}
+
+/// Get a user
+async fn get_user(Path(id): Path<u64>) -> Json<User> {
+    Json(User { id, name: String::new() })
+}
+
+pub fn router() -> Router {
+    let router = Router::new().route("/:id", get(get_user));
+    router.route("/:id/friends", get(list_friends))
+}
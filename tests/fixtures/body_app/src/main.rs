@@ -0,0 +1,56 @@
+use axum::{
+    extract::{Multipart, State},
+    http::StatusCode,
+    routing::post,
+    Form, Json, Router,
+};
+use bytes::Bytes;
+use serde::Deserialize;
+
+#[derive(Clone)]
+struct AppState;
+
+#[derive(Deserialize)]
+pub struct Signup {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct Note {
+    pub text: String,
+}
+
+/// Sign up through an HTML form
+async fn signup(Form(form): Form<Signup>) -> StatusCode {
+    StatusCode::CREATED
+}
+
+/// Create a note, optionally with a body
+async fn create_note(State(state): State<AppState>, note: Option<Json<Note>>) -> StatusCode {
+    StatusCode::CREATED
+}
+
+/// Upload a raw blob
+async fn upload_blob(body: Bytes) -> StatusCode {
+    StatusCode::CREATED
+}
+
+/// Echo plain text
+async fn echo(body: String) -> String {
+    body
+}
+
+/// Upload files
+async fn upload_files(multipart: Multipart) -> StatusCode {
+    StatusCode::CREATED
+}
+
+fn main() {
+    let app: Router = Router::new()
+        .route("/signup", post(signup))
+        .route("/notes", post(create_note))
+        .route("/blobs", post(upload_blob))
+        .route("/echo", post(echo))
+        .route("/files", post(upload_files))
+        .with_state(AppState);
+}
@@ -0,0 +1,17 @@
+use axum::{extract::Query, Json};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct OrderFilter {
+    pub status: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Order {
+    pub total: f64,
+}
+
+/// List orders
+pub async fn list(Query(filter): Query<OrderFilter>) -> Json<Vec<Order>> {
+    Json(Vec::new())
+}
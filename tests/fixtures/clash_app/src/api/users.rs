@@ -0,0 +1,12 @@
+use axum::Json;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct User {
+    pub name: String,
+}
+
+/// List users
+pub async fn list() -> Json<Vec<User>> {
+    Json(Vec::new())
+}
@@ -0,0 +1,11 @@
+use axum::{routing::get, Router};
+
+mod api;
+
+use api::{orders, users};
+
+fn main() {
+    let app = Router::new()
+        .route("/users", get(users::list))
+        .route("/orders", get(orders::list));
+}
@@ -0,0 +1,19 @@
+use axum::{extract::Path, routing::get, Json, Router};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Product {
+    pub id: u64,
+    pub price: Decimal,
+    pub discount: Option<Decimal>,
+}
+
+/// Get a product
+async fn get_product(Path(id): Path<u64>) -> Json<Product> {
+    Json(Product { id, price: Decimal::new(1999, 2), discount: None })
+}
+
+pub fn router() -> Router {
+    Router::new().route("/products/:id", get(get_product))
+}
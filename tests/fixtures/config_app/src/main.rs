@@ -0,0 +1,5 @@
+mod app;
+
+fn main() {
+    println!("Config file fixture for axum_doc testing");
+}
@@ -0,0 +1,123 @@
+use axum::{
+    extract::Path as AxumPath,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+mod response;
+mod types;
+
+use response::*;
+use types::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserLogin {
+    pub username: String,
+    pub password: String,
+}
+
+/// 用户登录接口
+/// 
+/// 接收用户名和密码，返回用户资料信息
+async fn login(Json(payload): Json<UserLogin>) -> Json<UserProfile> {
+    Json(UserProfile {
+        id: 1,
+        username: payload.username,
+        email: "test@example.com".to_string(),
+    })
+}
+
+/// 获取用户信息
+/// 
+/// 根据用户ID获取用户基本信息
+async fn get_user(AxumPath(id): AxumPath<u64>) -> Json<User> {
+    Json(User { id, name: "test".to_string() })
+}
+
+// 用户模块的handler函数
+/// 获取用户详细资料
+/// 
+/// 根据用户ID获取用户的完整资料信息，包括用户名和邮箱
+async fn get_user_profile(AxumPath(id): AxumPath<u64>) -> Json<UserProfile> {
+    Json(UserProfile {
+        id,
+        username: format!("user_{}", id),
+        email: format!("user{}@example.com", id),
+    })
+}
+
+/// 更新用户信息
+/// 
+/// 根据用户ID更新用户的基本信息
+async fn update_user(AxumPath(id): AxumPath<u64>, Json(user): Json<User>) -> Json<User> {
+    Json(User { id, name: user.name })
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/login", post(login))
+        .route("/user/:id", get(get_user))
+        .nest("/api", user::router())
+        .nest("/test", test::router())
+        .nest("/events", event::router())
+}
+
+// 添加用户模块
+mod user {
+    use axum::{
+        routing::{get, put},
+        Router,
+    };
+    
+    pub fn router() -> Router {
+        Router::new()
+            .route("/profile/:id", get(super::get_user_profile))
+            .route("/:id", put(super::update_user))
+    }
+}
+
+// 添加测试模块
+mod test {
+    use axum::{
+        routing::get,
+        Router,
+    };
+    
+    pub fn router() -> Router {
+        Router::new()
+            .route("/status", get(super::test_status))
+    }
+}
+
+// 添加事件模块
+mod event {
+    use axum::{
+        routing::get,
+        Router,
+    };
+    
+    pub fn router() -> Router {
+        Router::new()
+            .route("/list", get(super::event_list))
+    }
+}
+
+// 添加测试和事件模块的handler函数
+/// 获取系统状态
+/// 
+/// 返回系统运行状态信息
+async fn test_status() -> &'static str {
+    "OK"
+}
+
+/// 获取事件列表
+/// 
+/// 返回所有可用事件的列表
+async fn event_list() -> &'static str {
+    "[]"
+}
+
+fn main() {
+    let _app = app();
+}
@@ -1,7 +1,5 @@
 // src/response.rs
 
-#![allow(dead_code)]
-
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -9,4 +7,4 @@ pub struct UserProfile {
     pub id: u64,
     pub username: String,
     pub email: String,
-}
\ No newline at end of file
+}
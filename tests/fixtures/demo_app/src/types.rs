@@ -1,11 +1,9 @@
 // src/types.rs
 
-#![allow(dead_code)]
-
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 pub struct User {
     pub id: u64,
     pub name: String,
-}
\ No newline at end of file
+}
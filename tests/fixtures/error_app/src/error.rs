@@ -0,0 +1,24 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}, Json};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct ErrorBody {
+    pub message: String,
+}
+
+pub enum AppError {
+    NotFound,
+    Unauthorized,
+    Internal(String),
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            AppError::NotFound => (StatusCode::NOT_FOUND, "not found".to_string()),
+            AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "unauthorized".to_string()),
+            AppError::Internal(reason) => (StatusCode::INTERNAL_SERVER_ERROR, reason),
+        };
+        (status, Json(ErrorBody { message })).into_response()
+    }
+}
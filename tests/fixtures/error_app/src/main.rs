@@ -0,0 +1,47 @@
+use axum::{extract::Path, http::StatusCode, routing::{delete, get}, Json, Router};
+use serde::Serialize;
+
+mod error;
+
+use error::{AppError, ErrorBody};
+
+#[derive(Serialize)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+/// Get a user
+async fn get_user(Path(id): Path<u64>) -> Result<Json<User>, AppError> {
+    if id == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(Json(User { id, name: "alice".to_string() }))
+}
+
+/// Delete a user
+/// @axum_doc(operation_id = "removeUser", response(403, ErrorBody))
+#[cfg_attr(any(), axum_doc(tag = "admin", security = "bearer", deprecated))]
+async fn delete_user(Path(id): Path<u64>) -> Result<StatusCode, AppError> {
+    if id == 0 {
+        return Err(AppError::Unauthorized);
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/users/:id", get(get_user))
+        .route("/users/:id/delete", delete(delete_user))
+        .route("/probe", get(probe))
+}
+
+/// Internal probe
+#[cfg_attr(any(), axum_doc(hidden))]
+async fn probe() -> Json<ErrorBody> {
+    Json(ErrorBody { message: String::new() })
+}
+
+fn main() {
+    println!("Error type fixture for axum_doc testing");
+}
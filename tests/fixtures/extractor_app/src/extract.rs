@@ -0,0 +1,62 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use axum_extra::{headers::{authorization::Bearer, Authorization}, TypedHeader};
+use serde::de::DeserializeOwned;
+
+/// JSON body that is validated before the handler runs
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+        Ok(ValidatedJson(value))
+    }
+}
+
+/// The authenticated caller
+pub struct AuthUser {
+    pub token: String,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AuthUser {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let TypedHeader(Authorization(bearer)) =
+            TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
+                .await
+                .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        Ok(AuthUser { token: bearer.token().to_string() })
+    }
+}
+
+/// Tenant read by middleware elsewhere; opaque to the analysis
+#[derive(Clone)]
+pub struct Tenant(pub String);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Tenant {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        tenant_from(parts).ok_or(StatusCode::BAD_REQUEST)
+    }
+}
+
+fn tenant_from(parts: &Parts) -> Option<Tenant> {
+    parts.extensions.get::<Tenant>().cloned()
+}
@@ -0,0 +1,26 @@
+use axum::{routing::post, Json, Router};
+use serde::{Deserialize, Serialize};
+
+mod extract;
+
+use extract::{AuthUser, Tenant, ValidatedJson};
+
+#[derive(Deserialize)]
+pub struct CreateNote {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+pub struct Note {
+    pub id: u64,
+    pub text: String,
+}
+
+/// Create a note
+async fn create_note(user: AuthUser, tenant: Tenant, ValidatedJson(note): ValidatedJson<CreateNote>) -> Json<Note> {
+    Json(Note { id: 1, text: note.text })
+}
+
+fn main() {
+    let app: Router = Router::new().route("/notes", post(create_note));
+}
@@ -0,0 +1,16 @@
+use axum::{async_trait, extract::FromRequestParts, http::{request::Parts, StatusCode}};
+
+/// Claims of an admin API key
+pub struct Claims {
+    pub key: String,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Claims {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let key = parts.headers.get("x-admin-key").ok_or(StatusCode::UNAUTHORIZED)?;
+        Ok(Claims { key: key.to_str().unwrap_or_default().to_string() })
+    }
+}
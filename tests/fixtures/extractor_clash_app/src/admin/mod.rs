@@ -0,0 +1,14 @@
+use axum::{routing::get, Router};
+
+mod claims;
+
+use self::claims::Claims;
+
+/// Audit log, for admins holding an API key
+async fn audit(claims: Claims) -> String {
+    String::new()
+}
+
+pub fn router() -> Router {
+    Router::new().route("/audit", get(audit))
+}
@@ -0,0 +1,20 @@
+use axum::{async_trait, extract::FromRequestParts, http::{request::Parts, StatusCode}};
+use axum_extra::{headers::{authorization::Bearer, Authorization}, TypedHeader};
+
+/// Claims from a user's bearer token
+pub struct Claims {
+    pub sub: String,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Claims {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let TypedHeader(Authorization(bearer)) =
+            TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
+                .await
+                .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        Ok(Claims { sub: bearer.token().to_string() })
+    }
+}
@@ -0,0 +1,17 @@
+use axum::{routing::get, Router};
+
+mod admin;
+mod auth;
+
+use auth::Claims;
+
+/// Current user's claims
+async fn me(claims: Claims) -> String {
+    String::new()
+}
+
+fn main() {
+    let app: Router = Router::new()
+        .route("/me", get(me))
+        .nest("/admin", admin::router());
+}
@@ -0,0 +1,21 @@
+use axum::{http::StatusCode, routing::get, Json, Router};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub requests: u64,
+}
+
+/// Request statistics
+pub async fn stats() -> Json<Stats> {
+    Json(Stats { requests: 0 })
+}
+
+/// Reset the statistics
+pub async fn reset_stats() -> StatusCode {
+    StatusCode::NO_CONTENT
+}
+
+pub fn router() -> Router {
+    Router::new().route("/admin/stats", get(stats).put(reset_stats))
+}
@@ -0,0 +1,53 @@
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    routing::{any, delete, get, on, MethodFilter},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+mod admin;
+
+#[derive(Deserialize)]
+pub struct NewItem {
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct Item {
+    pub id: u64,
+    pub name: String,
+}
+
+/// List items
+async fn list_items() -> Json<Vec<Item>> {
+    Json(Vec::new())
+}
+
+/// Create an item
+async fn create_item(Json(item): Json<NewItem>) -> Json<Item> {
+    Json(Item { id: 1, name: item.name })
+}
+
+/// Delete an item
+async fn delete_item(Path(id): Path<u64>) -> StatusCode {
+    StatusCode::NO_CONTENT
+}
+
+/// Replace or update an item
+async fn update_item(Path(id): Path<u64>, Json(item): Json<NewItem>) -> Json<Item> {
+    Json(Item { id, name: item.name })
+}
+
+/// Forward anything else upstream
+async fn proxy() -> StatusCode {
+    StatusCode::BAD_GATEWAY
+}
+
+fn main() {
+    let app: Router = Router::new()
+        .route("/items", get(list_items).post(create_item))
+        .route("/items/:id", delete(delete_item).on(MethodFilter::PUT.or(MethodFilter::PATCH), update_item))
+        .route("/proxy", any(proxy))
+        .merge(admin::router());
+}
@@ -0,0 +1,31 @@
+use axum::{routing::get, Json, Router};
+use serde::Serialize;
+
+mod orders;
+mod users;
+
+use orders::Item as OrderItem;
+
+#[derive(Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+}
+
+#[derive(Serialize)]
+pub struct Summary {
+    pub top_user: users::Item,
+    pub last_order: OrderItem,
+}
+
+/// Dashboard summary
+async fn summary() -> Json<Summary> {
+    unimplemented!()
+}
+
+fn main() {
+    let app: Router = Router::new()
+        .route("/users", get(users::list))
+        .route("/orders", get(orders::list).post(orders::create))
+        .route("/summary", get(summary));
+}
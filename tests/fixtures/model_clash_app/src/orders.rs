@@ -0,0 +1,19 @@
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use crate::Page;
+
+#[derive(Serialize, Deserialize)]
+pub struct Item {
+    pub total: f64,
+}
+
+/// List orders
+pub async fn list() -> Json<Page<Item>> {
+    unimplemented!()
+}
+
+/// Create an order
+pub async fn create(Json(item): Json<Item>) -> Json<Item> {
+    Json(item)
+}
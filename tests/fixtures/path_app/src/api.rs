@@ -0,0 +1,10 @@
+// Not the `api` module: main.rs maps it to routes_v1.rs with #[path]
+use axum::{routing::get, Router};
+
+async fn decoy() -> String {
+    String::new()
+}
+
+pub fn router() -> Router {
+    Router::new().route("/decoy", get(decoy))
+}
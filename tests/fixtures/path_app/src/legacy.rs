@@ -0,0 +1,10 @@
+// Never declared with `mod legacy;`
+use axum::{routing::get, Router};
+
+async fn old() -> String {
+    String::new()
+}
+
+pub fn router() -> Router {
+    Router::new().route("/old", get(old))
+}
@@ -0,0 +1,10 @@
+use axum::Router;
+
+#[path = "routes_v1.rs"]
+mod api;
+
+fn main() {
+    let app: Router = Router::new()
+        .nest("/api", api::router())
+        .merge(legacy::router());
+}
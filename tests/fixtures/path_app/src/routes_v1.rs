@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+
+/// List widgets
+async fn list_widgets() -> String {
+    String::new()
+}
+
+pub fn router() -> Router {
+    Router::new().route("/widgets", get(list_widgets))
+}
@@ -0,0 +1,33 @@
+use axum::{
+    extract::{Extension, Path},
+    http::HeaderMap,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use some_auth::Session;
+
+#[derive(Clone)]
+struct Db;
+
+#[derive(Serialize)]
+pub struct Profile {
+    pub id: u64,
+}
+
+/// Profile of the signed-in user
+async fn profile(session: Session, Path(id): Path<u64>) -> Json<Profile> {
+    Json(Profile { id })
+}
+
+/// Server-only extractors are skipped without a warning
+async fn health(Extension(db): Extension<Db>, headers: HeaderMap) -> &'static str {
+    "ok"
+}
+
+fn main() {
+    let app: Router = Router::new()
+        .route("/profile/:id", get(profile))
+        .route("/health", get(health))
+        .route("/reports", get(reports::summary));
+}
@@ -9,7 +9,7 @@ fn test_fixture(fixture_name: &str, expected_routes: Vec<&str>) {
 
     // Build the command
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
@@ -61,18 +61,18 @@ fn test_simple_app_openapi_structure() {
     let output_file = "/tmp/axum_doc_test_structure.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Verify required OpenAPI fields
@@ -93,18 +93,18 @@ fn test_doc_comment_extraction() {
     let output_file = "/tmp/axum_doc_test_docs.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Check login endpoint has doc comments
@@ -131,19 +131,19 @@ fn test_type_mapping_uuid() {
     let output_file = "/tmp/axum_doc_test_uuid.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
             "--model-files", "src/response.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Check user_id field in LoginResponse
@@ -160,19 +160,19 @@ fn test_type_mapping_datetime() {
     let output_file = "/tmp/axum_doc_test_datetime.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
             "--model-files", "src/types.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Check created_at field in User schema
@@ -189,23 +189,23 @@ fn test_type_mapping_option() {
     let output_file = "/tmp/axum_doc_test_option.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
             "--model-files", "src/form.rs,src/response.rs,src/types.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Check that schemas exist (we're testing the code doesn't crash with Option types)
-    assert!(json["components"]["schemas"].as_object().unwrap().len() > 0);
+    assert!(!json["components"]["schemas"].as_object().unwrap().is_empty());
 }
 
 #[test]
@@ -215,18 +215,18 @@ fn test_type_mapping_vec() {
     let output_file = "/tmp/axum_doc_test_vec.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Verify the OpenAPI spec is valid
@@ -240,18 +240,18 @@ fn test_http_methods() {
     let output_file = "/tmp/axum_doc_test_methods.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Check for GET method
@@ -273,19 +273,19 @@ fn test_request_body() {
     let output_file = "/tmp/axum_doc_test_body.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
             "--model-files", "src/form.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Check login endpoint has request body
@@ -303,19 +303,19 @@ fn test_response_schemas() {
     let output_file = "/tmp/axum_doc_test_response.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
             "--model-files", "src/response.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Check login endpoint has 200 response
@@ -331,18 +331,18 @@ fn test_parameters() {
     let output_file = "/tmp/axum_doc_test_params.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     // Check /user/:id has id parameter
@@ -368,7 +368,7 @@ fn test_custom_output_file() {
     let custom_output = "/tmp/custom_openapi.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
@@ -393,12 +393,12 @@ fn test_missing_model_files() {
     let output_file = "/tmp/axum_doc_test_missing.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
             "--model-files", "src/nonexistent.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
@@ -407,7 +407,7 @@ fn test_missing_model_files() {
     assert!(output.status.success());
 
     // Verify output is still generated
-    assert!(fs::metadata(&output_file).is_ok());
+    assert!(fs::metadata(output_file).is_ok());
 }
 
 #[test]
@@ -417,18 +417,18 @@ fn test_json_output_validity() {
     let output_file = "/tmp/axum_doc_test_validity.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
 
     // Verify it's valid JSON
     let json: serde_json::Value = serde_json::from_str(&content)
@@ -448,19 +448,19 @@ fn test_components_schemas() {
     let output_file = "/tmp/axum_doc_test_schemas.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
             "--model-files", "src/form.rs,src/response.rs,src/types.rs",
-            "--output", &output_file
+            "--output", output_file
         ])
         .output()
         .expect("Failed to run axum_doc");
 
     assert!(output.status.success());
 
-    let content = fs::read_to_string(&output_file).unwrap();
+    let content = fs::read_to_string(output_file).unwrap();
     let json: serde_json::Value = serde_json::from_str(&content).unwrap();
 
     let schemas = &json["components"]["schemas"];
@@ -489,7 +489,7 @@ fn test_duplicate_path_prefix_detection() {
     let output_file = "/tmp/axum_doc_test_dup_path.json";
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run", "--",
             "--base-dir", fixture_dir.to_str().unwrap(),
             "--handler-file", "src/main.rs",
@@ -519,3 +519,611 @@ fn test_duplicate_path_prefix_detection() {
     assert!(!paths.contains_key("/api/v1/user/api/v1/user/login"),
             "Duplicate path '/api/v1/user/api/v1/user/login' should not exist");
 }
+
+#[test]
+fn test_library_generator_api() {
+    // The library builder should produce the same document the binary writes
+    let spec = axum_doc::Generator::new("tests/fixtures/simple_app")
+        .entry_file("src/main.rs")
+        .model_files(["src/form.rs", "src/response.rs", "src/types.rs"])
+        .generate()
+        .expect("Generator failed for simple_app fixture");
+
+    assert_eq!(spec.openapi, "3.0.0");
+    assert_eq!(spec.info.title, "Generated API");
+    assert!(spec.paths.contains_key("/login"));
+    assert!(spec.components.schemas.contains_key("LoginForm"));
+
+    let json = spec.to_value();
+    assert_eq!(json["paths"]["/login"]["post"]["operationId"], "login");
+}
+
+#[test]
+fn test_library_generator_missing_entry_file() {
+    let result = axum_doc::Generator::new("tests/fixtures/simple_app")
+        .entry_file("src/does_not_exist.rs")
+        .generate();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_handlers_with_clashing_names() {
+    // api::users::list and api::orders::list must each document their own signature
+    let spec = axum_doc::Generator::new("tests/fixtures/clash_app")
+        .generate()
+        .expect("Generator failed for clash_app fixture");
+
+    let json = spec.to_value();
+    let users = &json["paths"]["/users"]["get"];
+    let orders = &json["paths"]["/orders"]["get"];
+    assert_eq!(users["summary"], "List users");
+    assert_eq!(orders["summary"], "List orders");
+    assert_eq!(users["operationId"], "users_list");
+    assert_eq!(orders["operationId"], "orders_list");
+    assert!(users.get("parameters").is_none());
+    assert_eq!(orders["parameters"][0]["name"], "status");
+    assert_eq!(users["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"],
+               "#/components/schemas/User");
+}
+
+#[test]
+fn test_clashing_model_names() {
+    // users::Item and orders::Item are separate schemas, referenced through each module's imports
+    let spec = axum_doc::Generator::new("tests/fixtures/model_clash_app")
+        .generate()
+        .expect("Generator failed for model_clash_app fixture");
+    let json = spec.to_value();
+    let schemas = &json["components"]["schemas"];
+    let content = |path: &str, method: &str| json["paths"][path][method]["responses"]["200"]["content"]["application/json"]["schema"].clone();
+
+    let mut names: Vec<&str> = schemas.as_object().unwrap().keys().map(String::as_str).collect();
+    names.sort();
+    assert_eq!(names, vec!["Page_orders.Item", "Summary", "orders.Item", "users.Item"]);
+    assert_eq!(schemas["users.Item"]["properties"]["name"]["type"], "string");
+    assert_eq!(schemas["orders.Item"]["properties"]["total"]["type"], "number");
+
+    assert_eq!(content("/users", "get")["items"]["$ref"], "#/components/schemas/users.Item");
+    assert_eq!(content("/orders", "get")["$ref"], "#/components/schemas/Page_orders.Item");
+    assert_eq!(content("/orders", "post")["$ref"], "#/components/schemas/orders.Item");
+    assert_eq!(json["paths"]["/orders"]["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+               "#/components/schemas/orders.Item");
+    assert_eq!(schemas["Page_orders.Item"]["properties"]["items"]["items"]["$ref"], "#/components/schemas/orders.Item");
+    assert_eq!(schemas["Summary"]["properties"]["top_user"]["$ref"], "#/components/schemas/users.Item");
+    assert_eq!(schemas["Summary"]["properties"]["last_order"]["$ref"], "#/components/schemas/orders.Item");
+}
+
+#[test]
+fn test_method_router_chains() {
+    // get(a).post(b) chains, any(..) and a merged module router
+    let spec = axum_doc::Generator::new("tests/fixtures/method_app")
+        .generate()
+        .expect("Generator failed for method_app fixture");
+    let json = spec.to_value();
+    let paths = &json["paths"];
+
+    assert_eq!(paths["/items"]["get"]["operationId"], "list_items");
+    assert_eq!(paths["/items"]["post"]["operationId"], "create_item");
+    assert_eq!(paths["/items"]["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+               "#/components/schemas/NewItem");
+    assert_eq!(paths["/items/:id"]["delete"]["summary"], "Delete an item");
+    assert_eq!(paths["/items/:id"]["delete"]["operationId"], "delete_item");
+
+    // on(PUT.or(PATCH), h) documents the handler twice, with distinct operationIds
+    assert_eq!(paths["/items/:id"]["put"]["operationId"], "update_item_put");
+    assert_eq!(paths["/items/:id"]["patch"]["operationId"], "update_item_patch");
+    assert!(paths["/items/:id"]["put"].get("x-any-method").is_none());
+    assert_eq!(paths["/admin/stats"]["get"]["summary"], "Request statistics");
+    assert_eq!(paths["/admin/stats"]["put"]["summary"], "Reset the statistics");
+
+    // any(..) covers the common methods only, each marked and with its own operationId
+    let proxy = paths["/proxy"].as_object().unwrap();
+    let mut methods: Vec<&str> = proxy.keys().map(String::as_str).collect();
+    methods.sort();
+    assert_eq!(methods, vec!["delete", "get", "patch", "post", "put"]);
+    for (method, operation) in proxy {
+        assert_eq!(operation["operationId"], format!("proxy_{}", method));
+        assert_eq!(operation["x-any-method"], true);
+    }
+    assert!(paths["/items"]["get"].get("x-any-method").is_none());
+}
+
+#[test]
+fn test_nested_router_resolved_through_module_tree() {
+    // `#[path = "routes_v1.rs"] mod api;` is followed; the unrelated src/api.rs and
+    // the undeclared src/legacy.rs are never picked up by file name
+    let output_file = "/tmp/axum_doc_test_path_app.json";
+    let output = Command::new("cargo")
+        .args(["run", "--", "--base-dir", "tests/fixtures/path_app", "--output", output_file])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let content = fs::read_to_string(output_file).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let paths: Vec<&String> = json["paths"].as_object().unwrap().keys().collect();
+    assert_eq!(paths, vec!["/api/widgets"]);
+    assert_eq!(json["paths"]["/api/widgets"]["get"]["summary"], "List widgets");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Warning: Router `legacy :: router ()` passed to merge() in module 'crate' could not be resolved"),
+            "missing warning: {}", stderr);
+}
+
+#[test]
+fn test_demo_app() {
+    // Inline `mod user { .. }` routers calling `super::` handlers, with `Path` imported as `AxumPath`
+    let spec = axum_doc::Generator::new("tests/fixtures/demo_app")
+        .generate()
+        .expect("Generator failed for demo_app fixture");
+    let json = spec.to_value();
+
+    let paths: Vec<&String> = json["paths"].as_object().unwrap().keys().collect();
+    assert_eq!(paths, vec!["/api/:id", "/api/profile/:id", "/events/list", "/login", "/test/status", "/user/:id"]);
+    assert_eq!(json["paths"]["/test/status"]["get"]["operationId"], "test_status");
+    assert_eq!(json["paths"]["/user/:id"]["get"]["parameters"][0]["name"], "id");
+    assert_eq!(json["paths"]["/api/:id"]["put"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+               "#/components/schemas/User");
+}
+
+#[test]
+fn test_let_bound_routers() {
+    // Routers bound with `let`, reassigned, returned from functions and then nested/merged
+    let spec = axum_doc::Generator::new("tests/fixtures/binding_app")
+        .generate()
+        .expect("Generator failed for binding_app fixture");
+    let json = spec.to_value();
+    let paths = json["paths"].as_object().unwrap();
+
+    let mut found: Vec<&str> = paths.keys().map(String::as_str).collect();
+    found.sort();
+    assert_eq!(found, vec!["/api/users/:id", "/api/users/:id/friends", "/health", "/version"]);
+
+    assert_eq!(paths["/api/users/:id"]["get"]["operationId"], "get_user");
+    assert_eq!(paths["/api/users/:id"]["get"]["tags"], serde_json::json!(["users"]));
+    assert_eq!(paths["/api/users/:id/friends"]["get"]["summary"], "List a user's friends");
+    assert_eq!(paths["/health"]["get"]["operationId"], "health");
+    assert_eq!(paths["/version"]["get"]["operationId"], "version");
+    assert!(spec.components.schemas.contains_key("User"));
+    assert!(spec.components.schemas.contains_key("Health"));
+}
+
+#[test]
+fn test_request_body_media_types() {
+    // Each body extractor documents its own media type
+    let spec = axum_doc::Generator::new("tests/fixtures/body_app")
+        .generate()
+        .expect("Generator failed for body_app fixture");
+    let json = spec.to_value();
+    let body = |path: &str| json["paths"][path]["post"]["requestBody"].clone();
+
+    assert_eq!(body("/signup")["content"]["application/x-www-form-urlencoded"]["schema"]["$ref"],
+               "#/components/schemas/Signup");
+    assert_eq!(body("/signup")["required"], true);
+    assert_eq!(body("/notes")["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Note");
+    assert_eq!(body("/notes")["required"], false);
+    assert_eq!(body("/blobs")["content"]["application/octet-stream"]["schema"]["format"], "binary");
+    assert_eq!(body("/echo")["content"]["text/plain"]["schema"]["type"], "string");
+    assert_eq!(body("/files")["content"]["multipart/form-data"]["schema"]["type"], "object");
+    // State<_> is server-only and never becomes a parameter
+    assert!(json["paths"]["/notes"]["post"].get("parameters").is_none());
+}
+
+#[test]
+fn test_unknown_extractor_warning() {
+    // An argument type that is neither a known extractor nor a custom one is reported on stderr
+    let output_file = "/tmp/axum_doc_test_warning_app.json";
+    let output = Command::new("cargo")
+        .args(["run", "--", "--base-dir", "tests/fixtures/warning_app", "--output", output_file])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let warnings: Vec<&str> = stderr.lines().filter(|line| line.contains("extractor")).collect();
+    assert_eq!(warnings.len(), 1, "unexpected warnings: {:?}", warnings);
+    assert!(warnings[0].contains("Handler 'profile' takes unrecognized extractor `Session`"));
+
+    // A handler outside the module tree is reported, never matched to a same-named file
+    assert!(stderr.contains("Warning: Handler 'reports::summary' routed at '/reports' could not be resolved"));
+
+    // The rest of the handler is still documented
+    let content = fs::read_to_string(output_file).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let profile = &json["paths"]["/profile/:id"]["get"];
+    assert_eq!(profile["parameters"].as_array().unwrap().len(), 1);
+    assert_eq!(profile["parameters"][0]["name"], "id");
+    assert!(json["paths"]["/health"]["get"].get("parameters").is_none());
+    assert!(json["paths"].get("/reports").is_none());
+}
+
+#[test]
+fn test_models_discovered_from_module_tree() {
+    // No --model-files: models in modules/auth_handler.rs and modules/user_handler.rs
+    // must be found by following `mod` declarations from src/main.rs
+    let spec = axum_doc::Generator::new("tests/fixtures/modular_app")
+        .generate()
+        .expect("Generator failed for modular_app fixture");
+
+    let schemas = &spec.components.schemas;
+    assert!(schemas.contains_key("LoginCredentials"));
+    assert!(schemas.contains_key("LoginResponse"));
+    assert!(schemas.contains_key("UserInfo"));
+
+    let json = spec.to_value();
+    assert_eq!(
+        json["paths"]["/login"]["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/LoginCredentials"
+    );
+}
+
+#[test]
+fn test_required_fields() {
+    // Non-Option fields are listed in `required`, and JSON bodies are required
+    let spec = axum_doc::Generator::new("tests/fixtures/simple_app")
+        .generate()
+        .expect("Generator failed for simple_app fixture");
+    let json = spec.to_value();
+
+    assert_eq!(
+        json["components"]["schemas"]["LoginForm"]["required"],
+        serde_json::json!(["username", "password"])
+    );
+    assert_eq!(json["paths"]["/login"]["post"]["requestBody"]["required"], true);
+}
+
+#[test]
+fn test_error_responses_from_into_response_impl() {
+    // AppError's IntoResponse impl maps variants to 404/401/500 with an ErrorBody
+    let spec = axum_doc::Generator::new("tests/fixtures/error_app")
+        .generate()
+        .expect("Generator failed for error_app fixture");
+    let json = spec.to_value();
+
+    let responses = &json["paths"]["/users/:id"]["get"]["responses"];
+    assert_eq!(responses["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/User");
+    for code in ["404", "401", "500"] {
+        assert_eq!(
+            responses[code]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ErrorBody",
+            "missing error response {}", code
+        );
+    }
+    assert!(responses.get("default").is_none());
+
+    let responses = &json["paths"]["/users/:id/delete"]["delete"]["responses"];
+    assert_eq!(responses["204"]["description"], "No Content");
+    assert!(responses["204"].get("content").is_none());
+    assert_eq!(responses["401"]["description"], "Unauthorized");
+
+    assert!(spec.components.schemas.contains_key("ErrorBody"));
+    // The error type is described by its IntoResponse impl, not as a schema
+    assert!(!spec.components.schemas.contains_key("AppError"));
+}
+
+#[test]
+fn test_axum_doc_annotations() {
+    let spec = axum_doc::Generator::new("tests/fixtures/error_app")
+        .generate()
+        .expect("Generator failed for error_app fixture");
+    let json = spec.to_value();
+
+    let operation = &json["paths"]["/users/:id/delete"]["delete"];
+    assert_eq!(operation["operationId"], "removeUser");
+    assert_eq!(operation["summary"], "Delete a user");
+    assert!(operation.get("description").is_none(), "directive lines are not documentation");
+    assert_eq!(operation["tags"], serde_json::json!(["admin"]));
+    assert_eq!(operation["deprecated"], true);
+    assert_eq!(operation["security"], serde_json::json!([{"bearer": []}]));
+    assert_eq!(operation["responses"]["403"]["content"]["application/json"]["schema"]["$ref"],
+               "#/components/schemas/ErrorBody");
+    assert_eq!(json["components"]["securitySchemes"]["bearer"]["scheme"], "bearer");
+
+    // `hidden` operations are left out
+    assert!(json["paths"].get("/probe").is_none());
+}
+
+#[test]
+fn test_config_file() {
+    // axum_doc.toml sets the entry file, document info, servers, tags and a type override
+    let base_dir = std::path::Path::new("tests/fixtures/config_app");
+    let config = axum_doc::Config::load(base_dir).expect("Failed to load axum_doc.toml");
+    let spec = axum_doc::Generator::new(base_dir)
+        .config(config)
+        .generate()
+        .expect("Generator failed for config_app fixture");
+    let json = spec.to_value();
+
+    assert_eq!(json["info"]["title"], "Shop API");
+    assert_eq!(json["info"]["version"], "2.1.0");
+    assert_eq!(json["info"]["contact"]["email"], "api@example.com");
+    assert_eq!(json["info"]["license"]["name"], "MIT");
+    assert_eq!(json["servers"][0]["url"], "https://api.example.com");
+    assert_eq!(json["tags"][0]["name"], "products");
+
+    assert!(json["paths"]["/products/:id"]["get"].is_object());
+    let product = &json["components"]["schemas"]["Product"];
+    assert_eq!(product["properties"]["price"]["$ref"], "#/components/schemas/Decimal");
+    assert_eq!(json["components"]["schemas"]["Decimal"]["format"], "decimal");
+}
+
+#[test]
+fn test_custom_extractors() {
+    // ValidatedJson<T> and AuthUser are analyzed from their impls; Tenant is mapped in Cargo.toml
+    let base_dir = std::path::Path::new("tests/fixtures/extractor_app");
+    let config = axum_doc::Config::load(base_dir).expect("Failed to load extractor_app config");
+    let spec = axum_doc::Generator::new(base_dir)
+        .config(config)
+        .generate()
+        .expect("Generator failed for extractor_app fixture");
+    let json = spec.to_value();
+
+    let operation = &json["paths"]["/notes"]["post"];
+    assert_eq!(operation["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+               "#/components/schemas/CreateNote");
+    assert_eq!(operation["parameters"][0]["name"], "x-tenant");
+    assert_eq!(operation["parameters"][0]["in"], "header");
+    assert_eq!(operation["security"], serde_json::json!([{"bearer": []}]));
+    assert_eq!(json["components"]["securitySchemes"]["bearer"]["scheme"], "bearer");
+}
+
+#[test]
+fn test_clashing_custom_extractors() {
+    // auth::Claims and admin::claims::Claims are told apart through each handler's imports
+    let spec = axum_doc::Generator::new("tests/fixtures/extractor_clash_app")
+        .generate()
+        .expect("Generator failed for extractor_clash_app fixture");
+    let json = spec.to_value();
+
+    let me = &json["paths"]["/me"]["get"];
+    assert_eq!(me["security"], serde_json::json!([{"bearer": []}]));
+    assert!(me.get("parameters").is_none());
+
+    let audit = &json["paths"]["/admin/audit"]["get"];
+    assert!(audit.get("security").is_none());
+    assert_eq!(audit["parameters"][0]["name"], "x-admin-key");
+    assert_eq!(audit["parameters"][0]["in"], "header");
+}
+
+#[test]
+fn test_extractor_config_without_type() {
+    // A json entry documents the extractor's type argument; Tenant has none
+    let base_dir = std::path::Path::new("tests/fixtures/extractor_app");
+    let mut config = axum_doc::Config::load(base_dir).expect("Failed to load extractor_app config");
+    config.extractors.insert("Tenant".to_string(), axum_doc::config::ExtractorConfig {
+        kind: axum_doc::config::ExtractorKind::Json,
+        ty: None,
+        name: None,
+    });
+    let error = axum_doc::Generator::new(base_dir)
+        .config(config)
+        .generate()
+        .expect_err("a json entry without a type should be rejected")
+        .to_string();
+    assert!(error.contains("[extractors] entry 'Tenant'"), "unexpected error: {}", error);
+    assert!(error.contains("needs a `type`"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_cli_flags_override_config_file() {
+    let output_file = "/tmp/axum_doc_test_config_app.json";
+    let output = Command::new("cargo")
+        .args([
+            "run", "--",
+            "--base-dir", "tests/fixtures/config_app",
+            "--output", output_file,
+        ])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    // --output wins over [output] path; the entry file still comes from the config
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+    assert_eq!(json["info"]["title"], "Shop API");
+    assert!(json["paths"]["/products/:id"].is_object());
+    assert!(!PathBuf::from("tests/fixtures/config_app/openapi.json").exists());
+}
+
+#[test]
+fn test_yaml_output_openapi_3_1() {
+    let output_file = "/tmp/axum_doc_test_config_app_3_1.yaml";
+    let output = Command::new("cargo")
+        .args([
+            "run", "--",
+            "--base-dir", "tests/fixtures/config_app",
+            "--output", output_file,
+            "--spec-version", "3.1",
+        ])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    // The format follows the .yaml extension
+    let spec: serde_json::Value = serde_yaml::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+    assert_eq!(spec["openapi"], "3.1.0");
+    assert_eq!(spec["jsonSchemaDialect"], "https://spec.openapis.org/oas/3.1/dialect/base");
+    assert_eq!(spec["info"]["title"], "Shop API");
+
+    let discount = &spec["components"]["schemas"]["Product"]["properties"]["discount"];
+    assert!(discount.get("nullable").is_none(), "discount: {}", discount);
+    assert_eq!(discount["anyOf"][1], serde_json::json!({"type": "null"}));
+}
+
+#[test]
+fn test_html_site() {
+    let output_dir = PathBuf::from("/tmp/axum_doc_test_site");
+    let _ = fs::remove_dir_all(&output_dir);
+    let output = Command::new("cargo")
+        .args([
+            "run", "--",
+            "--base-dir", "tests/fixtures/modular_app",
+            "--output", output_dir.to_str().unwrap(),
+            "--format", "html",
+        ])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    // Operations are grouped by the module they are routed from
+    let index = fs::read_to_string(output_dir.join("index.html")).unwrap();
+    assert!(index.contains("<a href=\"tag-modules-auth.html\">modules::auth</a>"), "{}", index);
+    assert!(index.contains("<a href=\"tag-modules-user.html#get-user-info\"><code>/api/v1/user/info</code></a>"), "{}", index);
+
+    let auth = fs::read_to_string(output_dir.join("tag-modules-auth.html")).unwrap();
+    assert!(auth.contains("<a href=\"schemas.html#schema-LoginCredentials\">LoginCredentials</a>"), "{}", auth);
+    let schemas = fs::read_to_string(output_dir.join("schemas.html")).unwrap();
+    assert!(schemas.contains("<section class=\"schema\" id=\"schema-LoginCredentials\">"));
+    assert!(output_dir.join("style.css").exists());
+}
+
+#[test]
+fn test_markdown_reference() {
+    // The format follows the .md extension
+    let output_file = "/tmp/axum_doc_test_reference.md";
+    let output = Command::new("cargo")
+        .args(["run", "--", "--base-dir", "tests/fixtures/modular_app", "--output", output_file])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+    let reference = fs::read_to_string(output_file).unwrap();
+    assert!(reference.contains("## modules::auth <a id=\"tag-modules-auth\"></a>"), "{}", reference);
+    assert!(reference.contains("### `POST /login` <a id=\"login\"></a>\n\n**User login endpoint**\n\nAuthenticates a user"), "{}", reference);
+    assert!(reference.contains("`application/json`: [LoginCredentials](#schema-LoginCredentials)"), "{}", reference);
+
+    let output_dir = PathBuf::from("/tmp/axum_doc_test_reference");
+    let _ = fs::remove_dir_all(&output_dir);
+    let output = Command::new("cargo")
+        .args([
+            "run", "--",
+            "--base-dir", "tests/fixtures/modular_app",
+            "--output", output_dir.to_str().unwrap(),
+            "--format", "markdown",
+            "--split",
+        ])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+    let user = fs::read_to_string(output_dir.join("tag-modules-user.md")).unwrap();
+    assert!(user.starts_with("# modules::user\n\n## `GET /api/v1/user/info`"), "{}", user);
+    assert!(output_dir.join("README.md").exists());
+    assert!(output_dir.join("schemas.md").exists());
+
+    // --split only applies to Markdown
+    let output = Command::new("cargo")
+        .args(["run", "--", "--base-dir", "tests/fixtures/modular_app", "--output", "/tmp/axum_doc_test_split.json", "--split"])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_check_mode() {
+    let output_file = "/tmp/axum_doc_test_check.json";
+    let run = |extra: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--", "--base-dir", "tests/fixtures/config_app", "--output", output_file])
+            .args(extra)
+            .output()
+            .expect("Failed to run axum_doc")
+    };
+    let output = run(&[]);
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    // Reformatted but semantically equal: the check passes
+    let spec: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_file).unwrap()).unwrap();
+    fs::write(output_file, serde_json::to_string(&spec).unwrap()).unwrap();
+    let output = run(&["--check"]);
+    assert!(output.status.success(), "check failed: {}", String::from_utf8_lossy(&output.stdout));
+
+    // A stale spec fails with a diff and is left untouched
+    let mut stale = spec.clone();
+    stale["paths"].as_object_mut().unwrap().insert("/legacy".to_string(), serde_json::json!({"get": {}}));
+    stale["components"]["schemas"]["Product"]["properties"]["id"]["type"] = serde_json::json!("string");
+    let stale_text = serde_json::to_string_pretty(&stale).unwrap();
+    fs::write(output_file, &stale_text).unwrap();
+    let output = run(&["--check"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("  - GET /legacy"), "{}", stdout);
+    assert!(stdout.contains("  ~ Product\n      ~ properties.id.type: \"string\" -> \"integer\""), "{}", stdout);
+    assert_eq!(fs::read_to_string(output_file).unwrap(), stale_text);
+}
+
+#[test]
+fn test_diff_subcommand() {
+    let old_file = "/tmp/axum_doc_test_diff_old.json";
+    let new_file = "/tmp/axum_doc_test_diff_new.yaml";
+    let output = Command::new("cargo")
+        .args(["run", "--", "--base-dir", "tests/fixtures/config_app", "--output", old_file])
+        .output()
+        .expect("Failed to run axum_doc");
+    assert!(output.status.success(), "axum_doc failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    // The new version drops a response field and adds an operation
+    let mut spec: serde_json::Value = serde_json::from_str(&fs::read_to_string(old_file).unwrap()).unwrap();
+    spec["components"]["schemas"]["Product"]["properties"].as_object_mut().unwrap().remove("discount");
+    spec["paths"]["/products"] = serde_json::json!({"get": {"responses": {"200": {"description": "OK"}}}});
+    fs::write(new_file, serde_yaml::to_string(&spec).unwrap()).unwrap();
+
+    let diff = |extra: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--", "diff", old_file, new_file])
+            .args(extra)
+            .output()
+            .expect("Failed to run axum_doc diff")
+    };
+    let output = diff(&["--markdown"]);
+    assert!(output.status.success(), "diff failed: {}", String::from_utf8_lossy(&output.stderr));
+    let markdown = String::from_utf8_lossy(&output.stdout);
+    assert!(markdown.contains("### ⚠️ Breaking changes\n\n- `#/components/schemas/Product`: field `discount` removed\n"), "{}", markdown);
+    assert!(markdown.contains("### Non-breaking changes\n\n- `GET /products`: operation added\n"), "{}", markdown);
+
+    assert_eq!(diff(&["--fail-on-breaking"]).status.code(), Some(1));
+    let output = Command::new("cargo")
+        .args(["run", "--", "diff", old_file, old_file, "--fail-on-breaking"])
+        .output()
+        .expect("Failed to run axum_doc diff");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "No API changes\n");
+}
+
+// Minimal HTTP/1.1 GET, returning the raw response
+fn http_get(port: u16, path: &str) -> Option<String> {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).ok()?;
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path).ok()?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    Some(response)
+}
+
+#[test]
+fn test_serve_subcommand() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let mut server = Command::new(env!("CARGO_BIN_EXE_axum_doc"))
+        .args(["serve", "--base-dir", "tests/fixtures/config_app", "--port", &port.to_string()])
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .expect("Failed to start axum_doc serve");
+
+    let mut spec = None;
+    for _ in 0..100 {
+        spec = http_get(port, "/openapi.json");
+        if spec.is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    let swagger = http_get(port, "/swagger");
+    let redoc = http_get(port, "/redoc");
+    let bundle = http_get(port, "/assets/swagger-ui-bundle.js");
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    let spec = spec.expect("server did not start");
+    assert!(spec.starts_with("HTTP/1.1 200"), "{}", spec);
+    assert!(spec.contains("\"title\": \"Shop API\""));
+    assert!(swagger.unwrap().contains("<script src=\"/assets/swagger-ui-bundle.js\"></script>"));
+    assert!(redoc.unwrap().contains("<redoc spec-url=\"/openapi.json\"></redoc>"));
+    assert!(bundle.unwrap().contains("content-type: text/javascript"));
+}